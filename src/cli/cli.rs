@@ -4,6 +4,12 @@ pub struct Cli {
     simulator: crate::domain::LifeSimulator,
 }
 
+impl Default for Cli {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Cli {
     pub fn new() -> Self {
         println!("Welcome to Life Simulator!");
@@ -23,6 +29,20 @@ impl Cli {
         Cli { simulator }
     }
 
+    /// Starts from an existing simulator, e.g. one created from a template.
+    pub fn with_simulator(simulator: crate::domain::LifeSimulator) -> Self {
+        println!("Welcome to Life Simulator!");
+        let person = simulator.get_person();
+        println!(
+            "Starting as {}, age {}, with {} expenses and {} incomes.",
+            person.name,
+            person.age,
+            person.expenses.len(),
+            person.incomes.len()
+        );
+        Cli { simulator }
+    }
+
     pub fn run(&mut self) {
         loop {
             println!("\n--- Life Simulator Menu ---");
@@ -30,7 +50,8 @@ impl Cli {
             println!("2. Add expense");
             println!("3. View balance at specific age");
             println!("4. Show balance history");
-            println!("5. Exit");
+            println!("5. Save scenario to file");
+            println!("6. Exit");
             print!("Choose an option: ");
             io::stdout().flush().unwrap();
 
@@ -41,7 +62,8 @@ impl Cli {
                 2 => self.add_expense(),
                 3 => self.view_balance_at_age(),
                 4 => self.show_balance_history(),
-                5 => {
+                5 => self.save_scenario(),
+                6 => {
                     println!("Thanks for using Life Simulator!");
                     break;
                 }
@@ -91,7 +113,18 @@ impl Cli {
             Some(end_input.trim().parse::<u32>().expect("Invalid age"))
         };
 
-        let expense = crate::domain::Expense::new(name, amount, frequency, start_age, end_age);
+        let category = crate::domain::Category::parse(&Self::get_input(
+            "Category (e.g. Housing > Rent, leave empty for none): ",
+        ));
+        let tags = Self::get_input("Tags (comma separated, optional): ")
+            .split(',')
+            .map(|tag| tag.trim().to_string())
+            .filter(|tag| !tag.is_empty())
+            .collect();
+
+        let expense = crate::domain::Expense::new(name, amount, frequency, start_age, end_age)
+            .with_category(category)
+            .with_tags(tags);
         self.simulator.add_expense(expense);
 
         println!("Expense added successfully!");
@@ -105,20 +138,42 @@ impl Cli {
         println!("Projected balance at age {}: ${:.2}", target_age, balance);
     }
 
-    fn show_balance_history(&self) {
+    fn show_balance_history(&mut self) {
+        // Project over the saved simulation settings, not just the ages looked at so far
+        let config = self.simulator.get_simulation().clone();
+        self.simulator.balance_series(config.end_age());
         let history = self.simulator.get_balance_history();
-        if history.is_empty() {
-            println!("No balance history available.");
-            return;
-        }
 
         println!("\n--- Balance History ---");
-        let mut ages: Vec<u32> = history.keys().cloned().collect();
-        ages.sort();
+        for age in config.output_ages(self.simulator.get_current_age()) {
+            let Some(balance) = history.get(&age) else {
+                continue;
+            };
+            // Balances are taken at the end of the year before, so is the credit line state
+            match age
+                .checked_sub(1)
+                .and_then(|year| self.simulator.year_result(year))
+            {
+                Some(result) if result.debt > 0.0 || result.interest > 0.0 => println!(
+                    "Age {}: ${:.2} (owed ${:.2}, interest ${:.2})",
+                    age, balance, result.debt, result.interest
+                ),
+                _ => println!("Age {}: ${:.2}", age, balance),
+            }
+        }
+    }
 
-        for age in ages {
-            let balance = history.get(&age).unwrap();
-            println!("Age {}: ${:.2}", age, balance);
+    fn save_scenario(&self) {
+        let name = Self::get_input("Scenario name: ");
+        let path = Self::get_input("File path (e.g. plan.json): ");
+
+        let scenario = crate::domain::Scenario::new(name, self.simulator.clone());
+        match crate::infrastructure::persistence::save_scenario(
+            std::path::Path::new(&path),
+            &scenario,
+        ) {
+            Ok(()) => println!("Scenario saved to {}", path),
+            Err(e) => println!("{}", e),
         }
     }
 
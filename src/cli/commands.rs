@@ -0,0 +1,935 @@
+use crate::domain::{
+    Account, AssetAllocation, AssetClass, FireVariant, GlidePath, Horizon, LifeSimulator,
+    LifeTable, Mortality, Mortgage, Pension, PensionRules, Property, Scenario, Sex,
+    SimulationConfig, TimeStep, buy_vs_rent, compare_balances, configured_projection, fire_report,
+    longevity_report, person_from_transactions,
+};
+use crate::infrastructure::export::{Chart, ChartFormat, ReportFormat, SeriesKind};
+use crate::infrastructure::import::load_statement;
+use crate::infrastructure::persistence::{
+    TemplateSource, available_templates, load_scenario, save_scenario, user_template_dir,
+};
+use clap::{Args, Parser, Subcommand};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Parser)]
+#[command(name = "life_simulator", about = "Model your financial life over time")]
+pub struct CliArgs {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+    /// Start the interactive mode from a scenario template (see the `templates` command)
+    #[arg(long)]
+    pub template: Option<String>,
+}
+
+/// Overrides of the simulation settings saved in a scenario.
+#[derive(Args, Default)]
+pub struct SimulationArgs {
+    /// First age listed or drawn; defaults to the current age
+    #[arg(long, visible_alias = "from-age")]
+    pub start_age: Option<u32>,
+    /// Last age to project; defaults to the scenario's setting, 100 unless changed
+    #[arg(long, visible_alias = "to-age", group = "horizon")]
+    pub end_age: Option<u32>,
+    /// Project up to this life expectancy plus --margin years instead of a fixed age
+    #[arg(long, group = "horizon")]
+    pub life_expectancy: Option<u32>,
+    /// Years planned past the life expectancy
+    #[arg(long, requires = "life_expectancy")]
+    pub margin: Option<u32>,
+    /// Weigh the projection by a bundled life table (see the `life-tables` command)
+    #[arg(long, group = "horizon")]
+    pub mortality: Option<String>,
+    /// Column of the life table to use: female or male
+    #[arg(long, requires = "mortality", value_parser = Sex::parse)]
+    pub sex: Option<Sex>,
+    /// Weigh the projection by your own chance of being alive by age, e.g. "65:1,85:0.5,100:0"
+    #[arg(long, group = "horizon", value_parser = Mortality::parse_curve)]
+    pub survival: Option<Mortality>,
+    /// Length of the simulation steps: yearly or monthly
+    #[arg(long, value_parser = TimeStep::parse)]
+    pub time_step: Option<TimeStep>,
+    /// Years between listed rows
+    #[arg(long)]
+    pub every: Option<u32>,
+}
+
+impl SimulationArgs {
+    /// Applies the flags on top of the settings saved with the simulator.
+    pub fn apply(&self, simulator: &mut LifeSimulator) -> Result<SimulationConfig, String> {
+        let mut config = simulator.get_simulation().clone();
+        if let Some(age) = self.start_age {
+            config.start_age = Some(age);
+        }
+        if let Some(age) = self.end_age {
+            config.horizon = Horizon::EndAge(age);
+        }
+        if let Some(age) = self.life_expectancy {
+            let margin = match (self.margin, &config.horizon) {
+                (Some(margin), _) => margin,
+                (None, Horizon::LifeExpectancy { margin, .. }) => *margin,
+                (None, _) => Horizon::DEFAULT_MARGIN,
+            };
+            config.horizon = Horizon::LifeExpectancy { age, margin };
+        }
+        if let Some(table) = &self.mortality {
+            config.horizon = Horizon::Mortality(Mortality::Table {
+                table: table.clone(),
+                sex: self.sex.unwrap_or_default(),
+            });
+        }
+        if let Some(curve) = &self.survival {
+            config.horizon = Horizon::Mortality(curve.clone());
+        }
+        if let Some(time_step) = self.time_step {
+            config.time_step = time_step;
+        }
+        if let Some(every) = self.every {
+            config.granularity = every;
+        }
+        config.validate(simulator.get_current_age())?;
+        simulator.set_simulation(config.clone());
+        Ok(config)
+    }
+}
+
+/// A property to add to a scenario. Rates are in percent.
+#[derive(Args)]
+pub struct PropertyArgs {
+    /// Price in today's money; without it the scenario's own properties are shown
+    #[arg(long)]
+    pub price: Option<f64>,
+    /// Age at which it is bought; defaults to the current age
+    #[arg(long, requires = "price")]
+    pub buy_at: Option<u32>,
+    /// Name of the property
+    #[arg(long, default_value = "Home")]
+    pub name: String,
+    /// Share of the price paid from savings when borrowing the rest
+    #[arg(long, default_value_t = 20.0)]
+    pub down_payment: f64,
+    /// Yearly mortgage interest; without it the whole price is paid from savings
+    #[arg(long)]
+    pub mortgage_rate: Option<f64>,
+    /// Years to repay the mortgage over
+    #[arg(long, default_value_t = 25)]
+    pub mortgage_years: u32,
+    /// Yearly growth of the value
+    #[arg(long, default_value_t = 0.0, allow_hyphen_values = true)]
+    pub appreciation: f64,
+    /// Yearly upkeep as a share of the value
+    #[arg(long, default_value_t = 0.0)]
+    pub maintenance: f64,
+    /// Yearly property tax as a share of the value
+    #[arg(long, default_value_t = 0.0)]
+    pub property_tax: f64,
+    /// Yearly insurance as a share of the value
+    #[arg(long, default_value_t = 0.0)]
+    pub insurance: f64,
+    /// Yearly rent received in today's money, for a let property
+    #[arg(long)]
+    pub rental_income: Option<f64>,
+    /// Age at which it is sold
+    #[arg(long)]
+    pub sell_at: Option<u32>,
+    /// Agent fees and taxes on the sale as a share of the price
+    #[arg(long, default_value_t = 0.0)]
+    pub selling_costs: f64,
+}
+
+impl PropertyArgs {
+    /// The property described by the flags, if a price was given.
+    pub fn property(&self, current_age: u32) -> Option<Property> {
+        let price = self.price?;
+        let mut property = Property::new(&self.name, price, self.buy_at.unwrap_or(current_age))
+            .with_appreciation(self.appreciation / 100.0)
+            .with_costs(
+                self.maintenance / 100.0,
+                self.property_tax / 100.0,
+                self.insurance / 100.0,
+            );
+        if let Some(rate) = self.mortgage_rate {
+            let mortgage = Mortgage::new(rate / 100.0, self.mortgage_years);
+            property = property.with_mortgage(self.down_payment / 100.0, mortgage);
+        }
+        if let Some(rent) = self.rental_income {
+            property = property.with_rental_income(rent);
+        }
+        if let Some(age) = self.sell_at {
+            property = property.with_sale(age, self.selling_costs / 100.0);
+        }
+        Some(property)
+    }
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Print the balance of each tax-advantaged account of a saved scenario, and the tax paid on
+    /// withdrawals, by age
+    Accounts {
+        /// Scenario file to project
+        scenario: PathBuf,
+        /// Account type to add at the end of the priority order, repeatable: 401k, traditional-ira,
+        /// roth-ira, isa or pension-pot
+        #[arg(long, value_parser = account_preset)]
+        add: Vec<Account>,
+        #[command(flatten)]
+        simulation: SimulationArgs,
+        /// Store the added accounts and simulation settings in the scenario file
+        #[arg(long)]
+        save: bool,
+    },
+    /// Print the asset allocation of a saved scenario by age, with its expected return and
+    /// volatility
+    Allocation {
+        /// Scenario file to project
+        scenario: PathBuf,
+        /// Glide path to follow, e.g. "30:90/10/0, 65:50/45/5" (age:stocks/bonds/cash in %)
+        #[arg(long, value_parser = GlidePath::parse, conflicts_with = "target_date")]
+        glide_path: Option<GlidePath>,
+        /// Follow a target-date glide path that reaches 50/45/5 at this retirement age
+        #[arg(long)]
+        target_date: Option<u32>,
+        #[command(flatten)]
+        simulation: SimulationArgs,
+        /// Store the allocation and simulation settings in the scenario file
+        #[arg(long)]
+        save: bool,
+    },
+    /// Draw the balance chart of a saved scenario to an SVG or PNG file
+    Chart {
+        /// Scenario file to draw
+        scenario: PathBuf,
+        /// Image to write; the format is taken from the extension (.svg or .png)
+        #[arg(long, short)]
+        output: PathBuf,
+        /// Width of the chart
+        #[arg(long, default_value_t = 960)]
+        width: u32,
+        /// Height of the chart
+        #[arg(long, default_value_t = 400)]
+        height: u32,
+        /// Pixels per unit in PNG images, for sharper slides
+        #[arg(long, default_value_t = 2)]
+        scale: u32,
+        #[command(flatten)]
+        simulation: SimulationArgs,
+        /// Lowest amount shown
+        #[arg(long, allow_hyphen_values = true)]
+        min: Option<f64>,
+        /// Highest amount shown
+        #[arg(long, allow_hyphen_values = true)]
+        max: Option<f64>,
+        /// Series to draw, comma separated: balance, net-worth, what-if, expenses, income, interest, debt
+        #[arg(long, value_delimiter = ',', value_parser = SeriesKind::parse)]
+        series: Vec<SeriesKind>,
+    },
+    /// Compare the projected balances of two saved scenarios
+    Compare {
+        /// Scenario file used as the baseline
+        baseline: PathBuf,
+        /// Scenario file compared against the baseline
+        other: PathBuf,
+        #[command(flatten)]
+        simulation: SimulationArgs,
+    },
+    /// Report the savings rate, FIRE number and financial-independence age of a saved scenario
+    Fire {
+        /// Scenario file to analyse
+        scenario: PathBuf,
+        /// Spending level to retire on: lean, regular or fat
+        #[arg(long, default_value = "regular", value_parser = FireVariant::parse)]
+        variant: FireVariant,
+        /// Safe withdrawal rate in percent
+        #[arg(long, default_value_t = 4.0)]
+        withdrawal_rate: f64,
+        #[command(flatten)]
+        simulation: SimulationArgs,
+    },
+    /// Create a scenario from a bank statement (CSV, OFX, QIF) or a ledger/hledger journal,
+    /// with the recurring transactions as expenses and incomes
+    Import {
+        /// Statement or journal file; the format is taken from the extension
+        file: PathBuf,
+        /// Scenario file to write
+        #[arg(long, short, default_value = "scenario.json")]
+        output: PathBuf,
+        /// Name of the person
+        #[arg(long, default_value = "Me")]
+        name: String,
+        /// Current age of the person
+        #[arg(long)]
+        age: u32,
+        /// Starting capital; defaults to the balance found in the file
+        #[arg(long)]
+        capital: Option<f64>,
+    },
+    /// List the bundled life tables that can be passed to --mortality
+    LifeTables,
+    /// Report the chance of outliving the money and the expected bequest of a saved scenario
+    Longevity {
+        /// Scenario file to analyse
+        scenario: PathBuf,
+        #[command(flatten)]
+        simulation: SimulationArgs,
+    },
+    /// Work out a pension from contributions, by claiming age, and optionally add it to a
+    /// saved scenario
+    Pension {
+        /// Scenario file whose earned incomes count as future contributions
+        scenario: PathBuf,
+        /// Bundled rule set (see the `pension-rules` command) or a rules JSON file
+        #[arg(long)]
+        rules: String,
+        /// Age at which the pension is claimed; defaults to the normal age of the rules
+        #[arg(long)]
+        claim_at: Option<u32>,
+        /// Earnings of past years in today's money, e.g. "25-34:40000, 35:52000"
+        #[arg(long, default_value = "")]
+        history: String,
+        /// Name of the pension income
+        #[arg(long, default_value = "State pension")]
+        name: String,
+        /// Add the pension to the scenario file
+        #[arg(long)]
+        save: bool,
+    },
+    /// List the bundled pension rule sets that can be passed to --rules
+    PensionRules,
+    /// Print the equity of the properties of a saved scenario by age, and optionally compare
+    /// buying them with renting
+    Property {
+        /// Scenario file to project
+        scenario: PathBuf,
+        #[command(flatten)]
+        property: PropertyArgs,
+        /// Yearly rent in today's money to compare buying with
+        #[arg(long)]
+        rent_instead: Option<f64>,
+        #[command(flatten)]
+        simulation: SimulationArgs,
+        /// Add the property and store the simulation settings in the scenario file
+        #[arg(long)]
+        save: bool,
+    },
+    /// Print the projected balance of a saved scenario over its simulation settings
+    Project {
+        /// Scenario file to project
+        scenario: PathBuf,
+        #[command(flatten)]
+        simulation: SimulationArgs,
+        /// Store the simulation settings given here in the scenario file
+        #[arg(long)]
+        save: bool,
+    },
+    /// Write a shareable report or spreadsheet of a saved scenario
+    Report {
+        /// Scenario file to report on
+        scenario: PathBuf,
+        /// Report format
+        #[arg(long, default_value = "html", value_parser = ReportFormat::parse)]
+        format: ReportFormat,
+        /// File to write; prints to standard output when omitted
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+        #[command(flatten)]
+        simulation: SimulationArgs,
+    },
+    /// List the scenario templates that can be passed to --template
+    Templates,
+}
+
+pub fn run_command(command: Command) -> Result<(), String> {
+    match command {
+        Command::Accounts {
+            scenario,
+            add,
+            simulation,
+            save,
+        } => accounts(&scenario, add, &simulation, save),
+        Command::Allocation {
+            scenario,
+            glide_path,
+            target_date,
+            simulation,
+            save,
+        } => allocation(
+            &scenario,
+            glide_path.or(target_date.map(GlidePath::target_date)),
+            &simulation,
+            save,
+        ),
+        Command::Chart {
+            scenario,
+            output,
+            width,
+            height,
+            scale,
+            simulation,
+            min,
+            max,
+            series,
+        } => chart(
+            &scenario,
+            &output,
+            [width, height, scale],
+            &simulation,
+            (min, max),
+            &series,
+        ),
+        Command::Compare {
+            baseline,
+            other,
+            simulation,
+        } => compare(&baseline, &other, &simulation),
+        Command::Fire {
+            scenario,
+            variant,
+            withdrawal_rate,
+            simulation,
+        } => fire(&scenario, variant, withdrawal_rate, &simulation),
+        Command::Import {
+            file,
+            output,
+            name,
+            age,
+            capital,
+        } => import(&file, &output, name, age, capital),
+        Command::LifeTables => life_tables(),
+        Command::Longevity {
+            scenario,
+            simulation,
+        } => longevity(&scenario, &simulation),
+        Command::Pension {
+            scenario,
+            rules,
+            claim_at,
+            history,
+            name,
+            save,
+        } => pension(&scenario, &rules, claim_at, &history, name, save),
+        Command::PensionRules => pension_rules(),
+        Command::Property {
+            scenario,
+            property,
+            rent_instead,
+            simulation,
+            save,
+        } => property_command(&scenario, &property, rent_instead, &simulation, save),
+        Command::Project {
+            scenario,
+            simulation,
+            save,
+        } => project(&scenario, &simulation, save),
+        Command::Report {
+            scenario,
+            format,
+            output,
+            simulation,
+        } => report(&scenario, format, output.as_deref(), &simulation),
+        Command::Templates => templates(),
+    }
+}
+
+// Account presets by their name in lower case, with anything but letters and digits as dashes
+fn account_preset(name: &str) -> Result<Account, String> {
+    let slug = |text: &str| {
+        text.to_lowercase()
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join("-")
+    };
+    Account::presets()
+        .into_iter()
+        .find(|preset| slug(&preset.name).replace("401-k", "401k") == slug(name))
+        .ok_or_else(|| format!("Unknown account type '{}'", name))
+}
+
+fn accounts(
+    path: &Path,
+    add: Vec<Account>,
+    simulation: &SimulationArgs,
+    save: bool,
+) -> Result<(), String> {
+    let mut scenario = load_scenario(path)?;
+    let config = simulation.apply(&mut scenario.simulator)?;
+    let simulator = &mut scenario.simulator;
+    let mut accounts = simulator.get_accounts().to_vec();
+    accounts.extend(add);
+    for account in &accounts {
+        account.validate()?;
+    }
+    simulator.set_accounts(accounts);
+    if simulator.get_accounts().is_empty() {
+        return Err("The scenario has no accounts; add one with --add".to_string());
+    }
+
+    let names: Vec<&str> = simulator
+        .get_accounts()
+        .iter()
+        .map(|account| account.name.as_str())
+        .collect();
+    // Tax is summed over the years until the next row
+    println!("age,{},tax", names.join(","));
+    let ages = config.output_ages(simulator.get_current_age());
+    simulator.calculate_balance_at_age(config.end_age());
+    for (i, age) in ages.iter().enumerate() {
+        let balances: Vec<String> = simulator
+            .account_balances_at(*age)
+            .iter()
+            .map(|balance| format!("{:.2}", balance))
+            .collect();
+        let next_age = ages.get(i + 1).copied().unwrap_or(*age);
+        let tax = (*age..next_age)
+            .filter_map(|age| simulator.year_result(age))
+            .fold(0.0, |tax, result| tax + result.tax);
+        println!("{},{},{:.2}", age, balances.join(","), tax);
+    }
+
+    if save {
+        save_scenario(path, &scenario)?;
+        println!("Saved the accounts to {}", path.display());
+    }
+    Ok(())
+}
+
+fn allocation(
+    path: &Path,
+    glide_path: Option<GlidePath>,
+    simulation: &SimulationArgs,
+    save: bool,
+) -> Result<(), String> {
+    let mut scenario = load_scenario(path)?;
+    let config = simulation.apply(&mut scenario.simulator)?;
+    let simulator = &mut scenario.simulator;
+    let mut assumptions = simulator.get_assumptions().clone();
+    if let Some(glide_path) = glide_path {
+        let mut allocation = assumptions.allocation.unwrap_or_default();
+        allocation.glide_path = glide_path;
+        assumptions.allocation = Some(allocation);
+        simulator.set_assumptions(assumptions.clone());
+    }
+    let Some(allocation) = assumptions.allocation.as_ref() else {
+        return Err(
+            "The scenario has no asset allocation; set one with --glide-path or --target-date"
+                .to_string(),
+        );
+    };
+
+    print_classes(allocation);
+    println!();
+    println!("age,stocks,bonds,cash,expected_return,volatility");
+    for age in config.output_ages(simulator.get_current_age()) {
+        let shares = allocation.glide_path.allocation_at(age);
+        println!(
+            "{},{:.1},{:.1},{:.1},{:.2},{:.2}",
+            age,
+            shares.stocks * 100.0,
+            shares.bonds * 100.0,
+            shares.cash * 100.0,
+            allocation.return_at(age) * 100.0,
+            allocation.volatility_at(age) * 100.0
+        );
+    }
+
+    if save {
+        save_scenario(path, &scenario)?;
+        println!("Saved the allocation to {}", path.display());
+    }
+    Ok(())
+}
+
+fn print_classes(allocation: &AssetAllocation) {
+    for class in AssetClass::ALL {
+        let class_return = allocation.class(class);
+        println!(
+            "{}: {:.2}% expected return, {:.2}% volatility",
+            class,
+            class_return.expected_return * 100.0,
+            class_return.volatility * 100.0
+        );
+    }
+}
+
+fn chart(
+    scenario: &Path,
+    output: &Path,
+    [width, height, scale]: [u32; 3],
+    simulation: &SimulationArgs,
+    (min, max): (Option<f64>, Option<f64>),
+    series: &[SeriesKind],
+) -> Result<(), String> {
+    let format = ChartFormat::from_path(output)?;
+    let mut scenario = load_scenario(scenario)?;
+    let config = simulation.apply(&mut scenario.simulator)?;
+    let first_age = config.first_age(scenario.simulator.get_current_age());
+    let end_age = config.end_age();
+    let mut chart = Chart::balance(&scenario.name, &mut scenario.simulator, end_age, None)
+        .starting_at(first_age);
+    if !series.is_empty() {
+        chart = chart.retain(series);
+    }
+
+    // Unset limits fall back to the extent of the data
+    let (_, y_bounds) = chart.bounds();
+    let y_range = (min.is_some() || max.is_some())
+        .then(|| [min.unwrap_or(y_bounds[0]), max.unwrap_or(y_bounds[1])]);
+    if y_range.is_some_and(|[low, high]| low >= high) {
+        return Err("The lowest amount shown must be below the highest".to_string());
+    }
+    let chart = chart.with_view(None, y_range);
+
+    fs::write(output, format.render(&chart, width, height, scale))
+        .map_err(|e| format!("Failed to write file: {}", e))?;
+    println!("Saved {}", output.display());
+    Ok(())
+}
+
+fn compare(baseline: &Path, other: &Path, simulation: &SimulationArgs) -> Result<(), String> {
+    let mut baseline = load_scenario(baseline)?;
+    let mut other = load_scenario(other)?;
+    // Both are projected over the baseline's settings
+    let config = simulation.apply(&mut baseline.simulator)?;
+    other.simulator.set_simulation(config.clone());
+    let ages = config.output_ages(baseline.simulator.get_current_age());
+
+    let deltas = compare_balances(
+        &mut baseline.simulator,
+        &mut other.simulator,
+        config.end_age(),
+    );
+
+    println!("age,{},{},delta", baseline.name, other.name);
+    for delta in deltas.iter().filter(|delta| ages.contains(&delta.age)) {
+        println!(
+            "{},{:.2},{:.2},{:.2}",
+            delta.age,
+            delta.baseline,
+            delta.other,
+            delta.delta()
+        );
+    }
+    Ok(())
+}
+
+fn fire(
+    scenario: &Path,
+    variant: FireVariant,
+    withdrawal_rate: f64,
+    simulation: &SimulationArgs,
+) -> Result<(), String> {
+    if withdrawal_rate <= 0.0 {
+        return Err("The withdrawal rate must be positive".to_string());
+    }
+    let mut scenario = load_scenario(scenario)?;
+    let config = simulation.apply(&mut scenario.simulator)?;
+    let to_age = config.end_age();
+    let ages = config.output_ages(scenario.simulator.get_current_age());
+    let report = fire_report(
+        &mut scenario.simulator,
+        variant,
+        withdrawal_rate / 100.0,
+        to_age,
+    );
+
+    println!(
+        "{} FIRE for {} at a {:.2}% withdrawal rate",
+        report.variant, scenario.name, withdrawal_rate
+    );
+    if let Some(fire_number) = report.current_fire_number() {
+        println!("FIRE number today: {:.2}", fire_number);
+    }
+    if let Some(rate) = report.average_savings_rate() {
+        println!("Average savings rate: {:.1}%", rate * 100.0);
+    }
+    match report.fire_age {
+        Some(age) => println!("Financially independent at age {}", age),
+        None => println!("Not financially independent by age {}", to_age),
+    }
+
+    println!();
+    println!("age,balance,incomes,expenses,savings_rate,fire_number");
+    for year in report.years.iter().filter(|year| ages.contains(&year.age)) {
+        println!(
+            "{},{:.2},{:.2},{:.2},{},{:.2}",
+            year.age,
+            year.balance,
+            year.incomes,
+            year.expenses,
+            year.savings_rate()
+                .map_or(String::new(), |rate| format!("{:.4}", rate)),
+            year.fire_number
+        );
+    }
+    Ok(())
+}
+
+fn import(
+    file: &Path,
+    output: &Path,
+    name: String,
+    age: u32,
+    capital: Option<f64>,
+) -> Result<(), String> {
+    let statement = load_statement(file)?;
+    let capital = capital
+        .or(statement.balance)
+        .ok_or("The file has no balance; pass the starting capital with --capital".to_string())?;
+    let person = person_from_transactions(name, age, capital, &statement.transactions);
+
+    println!(
+        "Read {} transactions: {} recurring expenses and {} incomes, starting capital {:.2}",
+        statement.transactions.len(),
+        person.expenses.len(),
+        person.incomes.len(),
+        capital
+    );
+    for expense in &person.expenses {
+        println!(
+            "  expense {}: {:.2} ({:?}) [{}]",
+            expense.name, expense.amount, expense.frequency, expense.category
+        );
+    }
+    for income in &person.incomes {
+        println!(
+            "  income  {}: {:.2} ({:?}) [{}]",
+            income.name, income.amount, income.frequency, income.category
+        );
+    }
+
+    let scenario_name = file.file_stem().map_or("Imported".to_string(), |stem| {
+        stem.to_string_lossy().to_string()
+    });
+    let scenario = Scenario::new(scenario_name, LifeSimulator::new(person));
+    save_scenario(output, &scenario)?;
+    println!("Saved {}", output.display());
+    Ok(())
+}
+
+fn life_tables() -> Result<(), String> {
+    for table in LifeTable::builtin() {
+        println!("{:<20} {}", table.id, table.name);
+        println!("{:<20} {}", "", table.source);
+    }
+    Ok(())
+}
+
+fn longevity(scenario: &Path, simulation: &SimulationArgs) -> Result<(), String> {
+    let mut scenario = load_scenario(scenario)?;
+    let config = simulation.apply(&mut scenario.simulator)?;
+    let mortality = config.mortality().ok_or(
+        "The scenario has no mortality; pass --mortality <table> or --survival <curve>".to_string(),
+    )?;
+    let report = longevity_report(&mut scenario.simulator, mortality)?;
+
+    println!("{} weighed by {}", scenario.name, mortality);
+    println!("Life expectancy: {:.1}", report.life_expectancy);
+    match report.depletion_age {
+        Some(age) => println!(
+            "Money runs out at age {}; chance of living that long: {:.1}%",
+            age,
+            report.ruin_probability * 100.0
+        ),
+        None => println!("The money lasts a lifetime"),
+    }
+    println!("Expected bequest: {:.2}", report.expected_bequest);
+
+    println!();
+    println!("age,survival,balance");
+    let ages = config.output_ages(scenario.simulator.get_current_age());
+    for (age, alive) in report.survival.iter().filter(|(age, _)| ages.contains(age)) {
+        println!(
+            "{},{:.4},{:.2}",
+            age,
+            alive,
+            scenario.simulator.calculate_balance_at_age(*age)
+        );
+    }
+    Ok(())
+}
+
+fn pension_rules() -> Result<(), String> {
+    for rules in PensionRules::builtin() {
+        println!("{:<20} {}", rules.id, rules.name);
+        println!("{:<20} {}", "", rules.source);
+    }
+    Ok(())
+}
+
+// A bundled rule set by id, or a rules file
+fn find_pension_rules(name: &str) -> Result<PensionRules, String> {
+    if let Ok(rules) = PensionRules::find(name) {
+        return Ok(rules.clone());
+    }
+    let path = Path::new(name);
+    if path.is_file() {
+        let json = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        return PensionRules::from_json(&json);
+    }
+    Err(format!("Unknown pension rules \"{}\"", name))
+}
+
+fn pension(
+    path: &Path,
+    rules: &str,
+    claim_at: Option<u32>,
+    history: &str,
+    name: String,
+    save: bool,
+) -> Result<(), String> {
+    let mut scenario = load_scenario(path)?;
+    let rules = find_pension_rules(rules)?;
+    let mut pension = Pension::new(name, rules).with_history(Pension::parse_history(history)?);
+    if let Some(age) = claim_at {
+        pension = pension.with_claiming_age(age);
+    }
+    pension.validate()?;
+
+    let simulator = &scenario.simulator;
+    let person = simulator.get_person();
+    let income = simulator.pension_income(person, &pension);
+    let rules = &pension.rules;
+    println!("{}", rules.name);
+    println!("Normal age {}, {}", rules.normal_age, rules.indexation);
+    println!(
+        "Yearly benefit from age {}: {:.2}",
+        pension.claiming_age, income.amount
+    );
+
+    println!();
+    println!("claiming_age,yearly_benefit");
+    let end_age = simulator.get_simulation().end_age();
+    for age in rules.claiming_ages(pension.claiming_age.max(rules.normal_age + 5)) {
+        let at_age = pension.clone().with_claiming_age(age);
+        let benefit = simulator.pension_income(person, &at_age).amount;
+        if age < end_age {
+            println!("{},{:.2}", age, benefit);
+        }
+    }
+
+    if save {
+        scenario.simulator.add_pension(pension);
+        save_scenario(path, &scenario)?;
+        println!("Added the pension to {}", path.display());
+    }
+    Ok(())
+}
+
+fn property_command(
+    path: &Path,
+    property: &PropertyArgs,
+    rent_instead: Option<f64>,
+    simulation: &SimulationArgs,
+    save: bool,
+) -> Result<(), String> {
+    let mut scenario = load_scenario(path)?;
+    let config = simulation.apply(&mut scenario.simulator)?;
+    let simulator = &mut scenario.simulator;
+    let mut properties = simulator.get_properties().to_vec();
+    properties.extend(property.property(simulator.get_current_age()));
+    for property in &properties {
+        property.validate()?;
+    }
+    simulator.set_properties(properties);
+    if simulator.get_properties().is_empty() {
+        return Err("The scenario has no property; add one with --price".to_string());
+    }
+
+    // Renting is the baseline, so the difference is how much better buying ends up
+    let comparison = match rent_instead {
+        Some(rent) => Some(buy_vs_rent(simulator, rent, config.end_age())?),
+        None => None,
+    };
+    match comparison {
+        Some(_) => println!("age,equity,balance,net_worth,renting,difference"),
+        None => println!("age,equity,balance,net_worth"),
+    }
+    for age in config.output_ages(simulator.get_current_age()) {
+        let equity = simulator.property_equity_at(age);
+        let balance = simulator.calculate_balance_at_age(age);
+        let net_worth = simulator.net_worth_at(age);
+        let renting = comparison
+            .as_ref()
+            .and_then(|deltas| deltas.iter().find(|delta| delta.age == age));
+        match renting {
+            Some(delta) => println!(
+                "{},{:.2},{:.2},{:.2},{:.2},{:.2}",
+                age,
+                equity,
+                balance,
+                net_worth,
+                delta.baseline,
+                delta.delta()
+            ),
+            None => println!("{},{:.2},{:.2},{:.2}", age, equity, balance, net_worth),
+        }
+    }
+
+    if save {
+        save_scenario(path, &scenario)?;
+        println!("Saved the properties to {}", path.display());
+    }
+    Ok(())
+}
+
+fn project(path: &Path, simulation: &SimulationArgs, save: bool) -> Result<(), String> {
+    let mut scenario = load_scenario(path)?;
+    let config = simulation.apply(&mut scenario.simulator)?;
+
+    println!("age,balance,incomes,expenses,net_change");
+    for row in configured_projection(&mut scenario.simulator, &config) {
+        println!(
+            "{},{:.2},{:.2},{:.2},{:.2}",
+            row.age, row.balance, row.incomes, row.expenses, row.net_change
+        );
+    }
+
+    if save {
+        save_scenario(path, &scenario)?;
+        println!("Saved the simulation settings to {}", path.display());
+    }
+    Ok(())
+}
+
+fn report(
+    scenario: &Path,
+    format: ReportFormat,
+    output: Option<&Path>,
+    simulation: &SimulationArgs,
+) -> Result<(), String> {
+    if output.is_none() && !format.is_text() {
+        return Err(format!(
+            "{} reports need a file, pass it with --output",
+            format.label()
+        ));
+    }
+    let mut scenario = load_scenario(scenario)?;
+    let to_age = simulation.apply(&mut scenario.simulator)?.end_age();
+    let report = format.render(&mut scenario, to_age);
+    match output {
+        Some(path) => {
+            fs::write(path, report).map_err(|e| format!("Failed to write file: {}", e))?;
+            println!("Saved {}", path.display());
+        }
+        None => print!("{}", String::from_utf8_lossy(&report)),
+    }
+    Ok(())
+}
+
+fn templates() -> Result<(), String> {
+    for template in available_templates()? {
+        let source = match &template.source {
+            TemplateSource::Builtin => "built-in".to_string(),
+            TemplateSource::File(path) => path.display().to_string(),
+        };
+        println!("{:<28} {} ({})", template.id, template.title(), source);
+    }
+    if let Some(dir) = user_template_dir() {
+        println!();
+        println!("Add your own templates as .json files in {}", dir.display());
+    }
+    Ok(())
+}
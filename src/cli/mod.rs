@@ -1,2 +1,5 @@
+#[allow(clippy::module_inception)]
 pub mod cli;
+#[cfg(feature = "cli")]
+pub mod commands;
 pub use cli::Cli;
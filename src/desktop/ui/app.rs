@@ -1,7 +1,15 @@
 use crate::desktop::ui::components::{
     analytics_component::AnalyticsComponent,
+    categories_component::CategoriesComponent,
+    compare_component::CompareComponent,
     expenses_component::ExpensesComponent,
+    export_component::ExportComponent,
+    fire_component::FireComponent,
+    goals_component::GoalsComponent,
+    household_component::HouseholdComponent,
+    import_component::ImportComponent,
     incomes_component::IncomesComponent,
+    scenarios_component::ScenariosComponent,
     settings_component::SettingsComponent,
     setup_component::SetupComponent,
     shared_state::{AppTab, SharedState},
@@ -12,11 +20,19 @@ use eframe::egui;
 pub struct LifeSimulatorApp {
     state: SharedState,
     setup_component: SetupComponent,
+    household_component: HouseholdComponent,
     expenses_component: ExpensesComponent,
     incomes_component: IncomesComponent,
+    categories_component: CategoriesComponent,
+    import_component: ImportComponent,
     simulation_component: SimulationComponent,
+    goals_component: GoalsComponent,
     analytics_component: AnalyticsComponent,
+    fire_component: FireComponent,
+    scenarios_component: ScenariosComponent,
+    compare_component: CompareComponent,
     settings_component: SettingsComponent,
+    export_component: ExportComponent,
 }
 
 impl LifeSimulatorApp {
@@ -31,11 +47,19 @@ impl LifeSimulatorApp {
         Self {
             state: SharedState::new(),
             setup_component: SetupComponent::new(),
+            household_component: HouseholdComponent::new(),
             expenses_component: ExpensesComponent::new(),
             incomes_component: IncomesComponent::new(),
+            categories_component: CategoriesComponent::new(),
+            import_component: ImportComponent::new(),
             simulation_component: SimulationComponent::new(),
+            goals_component: GoalsComponent::new(),
             analytics_component: AnalyticsComponent::new(),
+            fire_component: FireComponent::new(),
+            scenarios_component: ScenariosComponent::new(),
+            compare_component: CompareComponent::new(),
             settings_component,
+            export_component: ExportComponent::new(),
         }
     }
 }
@@ -47,9 +71,17 @@ impl eframe::App for LifeSimulatorApp {
 
         egui::TopBottomPanel::top("top_panel").show(ctx, |ui| {
             ui.horizontal(|ui| {
+                self.export_component.show_file_menu(ui, &mut self.state);
+                ui.separator();
                 ui.selectable_value(&mut self.state.current_tab, AppTab::Setup, "Setup");
                 ui.selectable_value(&mut self.state.current_tab, AppTab::Expenses, "Expenses");
                 ui.selectable_value(&mut self.state.current_tab, AppTab::Incomes, "Incomes");
+                ui.selectable_value(
+                    &mut self.state.current_tab,
+                    AppTab::Categories,
+                    "Categories",
+                );
+                ui.selectable_value(&mut self.state.current_tab, AppTab::Import, "Import");
                 ui.selectable_value(
                     &mut self.state.current_tab,
                     AppTab::Simulation,
@@ -57,14 +89,11 @@ impl eframe::App for LifeSimulatorApp {
                 );
 
                 // Only show Analytics tab if simulator has data
-                if self.state.simulator.is_some()
-                    && !self
-                        .state
-                        .simulator
-                        .as_ref()
-                        .unwrap()
-                        .get_balance_history()
-                        .is_empty()
+                if self
+                    .state
+                    .scenarios
+                    .active()
+                    .is_some_and(|simulator| !simulator.get_balance_history().is_empty())
                 {
                     ui.selectable_value(
                         &mut self.state.current_tab,
@@ -73,11 +102,19 @@ impl eframe::App for LifeSimulatorApp {
                     );
                 }
 
+                ui.selectable_value(&mut self.state.current_tab, AppTab::Fire, "FIRE");
+
+                if self.state.scenarios.len() > 1 {
+                    ui.selectable_value(&mut self.state.current_tab, AppTab::Compare, "Compare");
+                }
+
                 ui.separator();
+                self.scenarios_component.show_selector(ui, &mut self.state);
 
                 // Use settings component with dropdown button
                 ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                    self.settings_component.show_settings_button(ui, ctx);
+                    self.settings_component
+                        .show_settings_button(ui, ctx, &mut self.state);
                 });
             });
         });
@@ -85,6 +122,10 @@ impl eframe::App for LifeSimulatorApp {
         egui::CentralPanel::default().show(ctx, |ui| match &self.state.current_tab {
             AppTab::Setup => {
                 self.setup_component.show(ui, &mut self.state);
+                ui.separator();
+                self.household_component.show(ui, &mut self.state);
+                ui.separator();
+                self.scenarios_component.show(ui, &mut self.state);
             }
             AppTab::Expenses => {
                 self.expenses_component.show(ui, &mut self.state);
@@ -92,13 +133,32 @@ impl eframe::App for LifeSimulatorApp {
             AppTab::Incomes => {
                 self.incomes_component.show(ui, &mut self.state);
             }
+            AppTab::Categories => {
+                self.categories_component.show(ui, &mut self.state);
+            }
+            AppTab::Import => {
+                self.import_component.show(ui, &mut self.state);
+            }
             AppTab::Simulation => {
                 self.simulation_component.show(ui, &mut self.state);
+                ui.separator();
+                self.goals_component.show(ui, &mut self.state);
             }
             AppTab::Analytics => {
                 self.analytics_component.show(ui, &mut self.state);
             }
+            AppTab::Fire => {
+                self.fire_component.show(ui, &mut self.state);
+            }
+            AppTab::Compare => {
+                self.compare_component.show(ui, &mut self.state);
+            }
         });
+
+        self.export_component.show_window(ctx, &mut self.state);
+
+        // Propagate this frame's edits from parent scenarios to their branches
+        self.state.scenarios.refresh_branches();
     }
 }
 
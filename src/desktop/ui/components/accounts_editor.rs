@@ -0,0 +1,173 @@
+use crate::domain::{Account, Allocation, GlidePath, LifeSimulator, TaxTreatment};
+use eframe::egui;
+
+fn percent_value(ui: &mut egui::Ui, label: &str, rate: &mut f64) {
+    let mut percent = *rate * 100.0;
+    ui.label(label);
+    ui.add(
+        egui::DragValue::new(&mut percent)
+            .speed(0.1)
+            .clamp_range(0.0..=99.0)
+            .suffix("%"),
+    );
+    *rate = percent / 100.0;
+}
+
+fn amount_value(ui: &mut egui::Ui, amount: &mut f64) {
+    ui.add(
+        egui::DragValue::new(amount)
+            .speed(100.0)
+            .clamp_range(0.0..=f64::MAX),
+    );
+}
+
+/// Edits the tax-advantaged accounts of a scenario in priority order and shows what they hold
+/// at `end_age`.
+pub fn accounts_editor(ui: &mut egui::Ui, simulator: &mut LifeSimulator, end_age: u32) {
+    let mut accounts = simulator.get_accounts().to_vec();
+    let retirement_age = simulator.get_assumptions().retirement_age.unwrap_or(65);
+    let mut moved_up = None;
+    let mut removed = None;
+
+    ui.label("Savings go into the accounts from the top, up to each yearly limit; the rest stays in cash.");
+    for (i, account) in accounts.iter_mut().enumerate() {
+        ui.push_id(i, |ui| {
+            ui.separator();
+            ui.horizontal(|ui| {
+                ui.text_edit_singleline(&mut account.name);
+                if i > 0 && ui.small_button("Up").clicked() {
+                    moved_up = Some(i);
+                }
+                if ui.small_button("Remove").clicked() {
+                    removed = Some(i);
+                }
+            });
+            ui.horizontal(|ui| {
+                ui.label("Balance today:");
+                amount_value(ui, &mut account.balance);
+                let mut limited = account.annual_limit.is_some();
+                ui.checkbox(&mut limited, "Yearly limit");
+                match (limited, &mut account.annual_limit) {
+                    (true, Some(limit)) => amount_value(ui, limit),
+                    (true, None) => account.annual_limit = Some(7000.0),
+                    (false, _) => account.annual_limit = None,
+                }
+            });
+            ui.horizontal(|ui| {
+                let mut deferred = matches!(account.treatment, TaxTreatment::Deferred { .. });
+                ui.radio_value(&mut deferred, true, "Tax deferred");
+                ui.radio_value(&mut deferred, false, "Tax free");
+                match (deferred, &mut account.treatment) {
+                    (
+                        true,
+                        TaxTreatment::Deferred {
+                            relief,
+                            withdrawal_tax,
+                        },
+                    ) => {
+                        percent_value(ui, "Relief:", relief);
+                        percent_value(ui, "Tax on withdrawal:", withdrawal_tax);
+                    }
+                    (true, _) => {
+                        account.treatment = TaxTreatment::Deferred {
+                            relief: 0.2,
+                            withdrawal_tax: 0.15,
+                        }
+                    }
+                    (false, _) => account.treatment = TaxTreatment::TaxFree,
+                }
+            });
+            ui.horizontal(|ui| {
+                let mut matched = account.employer_match.is_some();
+                ui.checkbox(&mut matched, "Employer match");
+                match (matched, &mut account.employer_match) {
+                    (true, Some(employer)) => {
+                        percent_value(ui, "Rate:", &mut employer.rate);
+                        percent_value(ui, "Up to (of pay):", &mut employer.up_to);
+                    }
+                    (true, None) => {
+                        *account = account.clone().with_match(0.5, 0.06);
+                    }
+                    (false, _) => account.employer_match = None,
+                }
+            });
+            ui.horizontal(|ui| {
+                let mut penalized = account.penalty_age.is_some();
+                ui.checkbox(&mut penalized, "Early-withdrawal penalty");
+                match (penalized, &mut account.penalty_age) {
+                    (true, Some(age)) => {
+                        ui.label("Before age:");
+                        ui.add(egui::DragValue::new(age).clamp_range(0..=120));
+                        percent_value(ui, "Penalty:", &mut account.early_penalty);
+                    }
+                    (true, None) => {
+                        *account = account.clone().with_penalty(60, 0.1);
+                    }
+                    (false, _) => account.penalty_age = None,
+                }
+            });
+            ui.horizontal(|ui| {
+                let mut own = account.glide_path.is_some();
+                ui.checkbox(&mut own, "Own allocation");
+                match (own, &mut account.glide_path) {
+                    (true, Some(path)) => {
+                        ui.label(format!("{} (stocks/bonds/cash %)", path));
+                        if ui.small_button("Constant 60/40").clicked() {
+                            *path = GlidePath::constant(Allocation::new(0.6, 0.4, 0.0));
+                        }
+                        if ui.small_button("Target date").clicked() {
+                            *path = GlidePath::target_date(retirement_age);
+                        }
+                        if ui.small_button("All stocks").clicked() {
+                            *path = GlidePath::constant(Allocation::new(1.0, 0.0, 0.0));
+                        }
+                    }
+                    (true, None) => {
+                        account.glide_path = Some(GlidePath::target_date(retirement_age))
+                    }
+                    (false, _) => account.glide_path = None,
+                }
+            });
+            if let Err(error) = account.validate() {
+                ui.colored_label(egui::Color32::RED, error);
+            }
+        });
+    }
+
+    ui.separator();
+    ui.horizontal(|ui| {
+        ui.label("Add:");
+        for preset in Account::presets() {
+            if ui.small_button(&preset.name).clicked() {
+                accounts.push(preset);
+            }
+        }
+    });
+
+    if let Some(index) = moved_up {
+        accounts.swap(index - 1, index);
+    }
+    if let Some(index) = removed {
+        accounts.remove(index);
+    }
+    simulator.set_accounts(accounts);
+
+    if simulator.get_accounts().is_empty() {
+        return;
+    }
+    let balances = simulator.account_balances_at(end_age);
+    let tax: f64 = (simulator.get_current_age()..end_age)
+        .filter_map(|age| simulator.year_result(age))
+        .map(|result| result.tax)
+        .sum();
+    for (account, balance) in simulator.get_accounts().iter().zip(balances) {
+        ui.label(format!(
+            "{} at age {}: {:.2} ({})",
+            account.name, end_age, balance, account.treatment
+        ));
+    }
+    ui.label(format!(
+        "Tax and penalties on withdrawals until age {}: {:.2}",
+        end_age, tax
+    ));
+}
@@ -0,0 +1,133 @@
+use crate::domain::{Allocation, AssetAllocation, AssetClass, GlidePath};
+use eframe::egui;
+use egui_plot::{Legend, Line, Plot, PlotPoints};
+
+/// Text of a glide path being typed, and the path it was last in sync with.
+#[derive(Default)]
+pub struct GlidePathEditor {
+    text: String,
+    synced: Option<GlidePath>,
+    error: Option<String>,
+}
+
+fn percent_value(ui: &mut egui::Ui, rate: &mut f64, range: std::ops::RangeInclusive<f64>) {
+    let mut percent = *rate * 100.0;
+    ui.add(
+        egui::DragValue::new(&mut percent)
+            .speed(0.1)
+            .clamp_range(range)
+            .suffix("%"),
+    );
+    *rate = percent / 100.0;
+}
+
+/// Edits the asset classes and glide path of an allocation. `retirement_age` places the
+/// target-date preset.
+pub fn allocation_editor(
+    ui: &mut egui::Ui,
+    allocation: &mut AssetAllocation,
+    editor: &mut GlidePathEditor,
+    retirement_age: u32,
+) {
+    egui::Grid::new("asset_classes").show(ui, |ui| {
+        ui.strong("Class");
+        ui.strong("Return");
+        ui.strong("Volatility");
+        ui.end_row();
+        for class in AssetClass::ALL {
+            let class_return = allocation.class_mut(class);
+            ui.label(class.to_string());
+            percent_value(ui, &mut class_return.expected_return, -10.0..=20.0);
+            percent_value(ui, &mut class_return.volatility, 0.0..=60.0);
+            ui.end_row();
+        }
+    });
+
+    ui.horizontal(|ui| {
+        if ui.small_button("Constant 60/40").clicked() {
+            allocation.glide_path = GlidePath::constant(Allocation::new(0.6, 0.4, 0.0));
+        }
+        if ui.small_button("Target date").clicked() {
+            allocation.glide_path = GlidePath::target_date(retirement_age);
+        }
+    });
+
+    if editor.synced.as_ref() != Some(&allocation.glide_path) {
+        editor.text = allocation.glide_path.to_string();
+        editor.synced = Some(allocation.glide_path.clone());
+        editor.error = None;
+    }
+    ui.label("Glide path (age:stocks/bonds/cash in %, ...):");
+    if ui.text_edit_singleline(&mut editor.text).changed() {
+        match GlidePath::parse(&editor.text) {
+            Ok(path) => {
+                allocation.glide_path = path.clone();
+                editor.synced = Some(path);
+                editor.error = None;
+            }
+            Err(e) => editor.error = Some(e),
+        }
+    }
+    if let Some(error) = &editor.error {
+        ui.colored_label(egui::Color32::RED, error);
+    }
+}
+
+/// Stacked shares of each asset class from `from_age` to `to_age`, with the expected return and
+/// volatility at `from_age`.
+pub fn allocation_chart(
+    ui: &mut egui::Ui,
+    allocation: &AssetAllocation,
+    from_age: u32,
+    to_age: u32,
+) {
+    ui.label(format!(
+        "At age {}: {} stocks/bonds/cash, expected return {:.2}%, volatility {:.2}%",
+        from_age,
+        allocation.glide_path.allocation_at(from_age),
+        allocation.return_at(from_age) * 100.0,
+        allocation.volatility_at(from_age) * 100.0
+    ));
+
+    // Each class is drawn on top of the ones below it, filled down to zero
+    let colors = [
+        egui::Color32::from_rgb(70, 130, 220),
+        egui::Color32::from_rgb(230, 160, 50),
+        egui::Color32::from_rgb(120, 180, 100),
+    ];
+    let mut stacks: Vec<(AssetClass, Vec<[f64; 2]>)> = Vec::new();
+    for class in AssetClass::ALL {
+        let points = (from_age..=to_age)
+            .map(|age| {
+                let shares = allocation.glide_path.allocation_at(age);
+                let below: f64 = AssetClass::ALL
+                    .iter()
+                    .take_while(|other| **other != class)
+                    .map(|other| shares.share(*other))
+                    .sum();
+                [age as f64, (below + shares.share(class)) * 100.0]
+            })
+            .collect();
+        stacks.push((class, points));
+    }
+
+    Plot::new("allocation_plot")
+        .legend(Legend::default())
+        .height(160.0)
+        .include_y(0.0)
+        .include_y(100.0)
+        .allow_drag(false)
+        .allow_zoom(false)
+        .x_axis_formatter(|value, _range, _digits| format!("{}", value as u32))
+        .y_axis_formatter(|value, _range, _digits| format!("{:.0}%", value))
+        .show(ui, |plot_ui| {
+            for ((class, points), color) in stacks.into_iter().zip(colors).rev() {
+                plot_ui.line(
+                    Line::new(PlotPoints::new(points))
+                        .name(class.to_string())
+                        .color(color)
+                        .fill(0.0),
+                );
+            }
+        });
+}
@@ -1,20 +1,144 @@
+use crate::desktop::ui::components::category_charts::{show_pie, show_stacked_bars};
+use crate::desktop::ui::components::category_picker::category_filter;
 use crate::desktop::ui::components::shared_state::SharedState;
+use crate::domain::Category;
 use eframe::egui;
 
-pub struct AnalyticsComponent;
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ChartKind {
+    Pie,
+    Donut,
+    StackedBars,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ChartFlow {
+    Expenses,
+    Incomes,
+}
+
+pub struct AnalyticsComponent {
+    chart_kind: ChartKind,
+    chart_flow: ChartFlow,
+    // Pie and donut charts cover a single year unless a range is selected
+    use_age_range: bool,
+    from_age: Option<u32>,
+    to_age: Option<u32>,
+}
+
+impl Default for AnalyticsComponent {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 impl AnalyticsComponent {
     pub fn new() -> Self {
-        Self
+        Self {
+            chart_kind: ChartKind::Donut,
+            chart_flow: ChartFlow::Expenses,
+            use_age_range: true,
+            from_age: None,
+            to_age: None,
+        }
+    }
+
+    fn show_chart_controls(&mut self, ui: &mut egui::Ui, min_age: u32, max_age: u32) {
+        let from_age = self.from_age.get_or_insert(min_age);
+        let to_age = self.to_age.get_or_insert(max_age);
+
+        ui.horizontal(|ui| {
+            ui.label("Chart:");
+            ui.radio_value(&mut self.chart_kind, ChartKind::Pie, "Pie");
+            ui.radio_value(&mut self.chart_kind, ChartKind::Donut, "Donut");
+            ui.radio_value(&mut self.chart_kind, ChartKind::StackedBars, "Stacked bars");
+            ui.separator();
+            ui.radio_value(&mut self.chart_flow, ChartFlow::Expenses, "Expenses");
+            ui.radio_value(&mut self.chart_flow, ChartFlow::Incomes, "Incomes");
+        });
+
+        ui.horizontal(|ui| {
+            if self.chart_kind != ChartKind::StackedBars {
+                ui.radio_value(&mut self.use_age_range, false, "Single year");
+                ui.radio_value(&mut self.use_age_range, true, "Age range");
+            }
+            if self.use_age_range || self.chart_kind == ChartKind::StackedBars {
+                ui.label("From age:");
+                ui.add(egui::DragValue::new(from_age).clamp_range(min_age..=max_age));
+                ui.label("To age:");
+                ui.add(egui::DragValue::new(to_age).clamp_range(min_age..=max_age));
+            } else {
+                ui.label("Age:");
+                ui.add(egui::DragValue::new(from_age).clamp_range(min_age..=max_age));
+            }
+        });
+    }
+
+    /// Draws the composition chart for the current selection and returns the clicked category.
+    fn show_chart(
+        &self,
+        ui: &mut egui::Ui,
+        state: &SharedState,
+        simulator: &crate::domain::LifeSimulator,
+    ) -> Option<Category> {
+        let from_age = self.from_age.unwrap_or_default();
+        let to_age = self.to_age.unwrap_or_default().max(from_age);
+        let filter = state.category_filter.as_ref();
+        let amount = |totals: &crate::domain::CategoryTotals| match self.chart_flow {
+            ChartFlow::Expenses => totals.expenses,
+            ChartFlow::Incomes => totals.incomes,
+        };
+
+        if self.chart_kind == ChartKind::StackedBars {
+            let layers: Vec<Category> = simulator
+                .category_totals_between(from_age, to_age + 1)
+                .composition(filter)
+                .into_iter()
+                .filter(|(_, totals)| amount(totals) > 0.0)
+                .map(|(category, _)| category)
+                .collect();
+            let series: Vec<(Category, Vec<(u32, f64)>)> = layers
+                .into_iter()
+                .map(|layer| {
+                    let amounts = (from_age..=to_age)
+                        .map(|age| {
+                            let year = simulator.category_totals_for_year(age);
+                            (age, amount(&year.within(&layer)))
+                        })
+                        .collect();
+                    (layer, amounts)
+                })
+                .collect();
+            return show_stacked_bars(ui, "analytics_stacked_bars", &series);
+        }
+
+        let end_age = if self.use_age_range {
+            to_age + 1
+        } else {
+            from_age + 1
+        };
+        let slices: Vec<(Category, f64)> = simulator
+            .category_totals_between(from_age, end_age)
+            .composition(filter)
+            .into_iter()
+            .map(|(category, totals)| (category, amount(&totals)))
+            .filter(|(_, value)| *value > 0.0)
+            .collect();
+        let inner_radius = if self.chart_kind == ChartKind::Donut {
+            0.5
+        } else {
+            0.0
+        };
+        show_pie(ui, "analytics_pie", &slices, inner_radius)
     }
 
     pub fn show(&mut self, ui: &mut egui::Ui, state: &mut SharedState) {
-        if state.simulator.is_none() {
+        if state.scenarios.active().is_none() {
             ui.label("Please create a person and run the simulation first.");
             return;
         }
 
-        let simulator = state.simulator.as_ref().unwrap();
+        let simulator = state.scenarios.active().unwrap();
         let history = simulator.get_balance_history();
 
         if history.is_empty() {
@@ -22,12 +146,66 @@ impl AnalyticsComponent {
             return;
         }
 
+        ui.horizontal(|ui| {
+            category_filter(
+                ui,
+                "analytics_category_filter",
+                &simulator.get_person().categories,
+                &mut state.category_filter,
+            );
+        });
+
+        let mut clicked_category: Option<Category> = None;
+        let mut clear_filter = false;
+
+        if let Some(category) = &state.category_filter {
+            let ages_range = (
+                *history.keys().min().unwrap_or(&0),
+                *history.keys().max().unwrap_or(&0),
+            );
+            let lifetime = simulator.category_totals_between(ages_range.0, ages_range.1);
+            ui.label(format!(
+                "{}: {:.2} of {:.2} total expenses ({:.1}%) between ages {} and {}",
+                category,
+                lifetime.within(category).expenses,
+                lifetime.total().expenses,
+                lifetime.expense_share(category) * 100.0,
+                ages_range.0,
+                ages_range.1
+            ));
+        }
+
+        ui.separator();
+
         // Sort ages for proper chronological display
         let mut ages: Vec<u32> = history.keys().cloned().collect();
         ages.sort();
 
-        // Create a scrollable table with analytics
+        // Create a scrollable view with the composition chart followed by the table
         egui::ScrollArea::vertical().show(ui, |ui| {
+            ui.heading("Composition");
+            self.show_chart_controls(ui, ages[0], ages[ages.len() - 1]);
+            // Clicking a slice or bar filters the table (and drills the chart down) to it
+            if let Some(clicked) = self.show_chart(ui, state, simulator) {
+                clicked_category = Some(clicked);
+            }
+            if let Some(filter) = &state.category_filter {
+                ui.horizontal(|ui| {
+                    ui.label(format!("Showing {}", filter));
+                    if ui.button("Up one level").clicked() {
+                        match filter.parent() {
+                            Some(parent) => clicked_category = Some(parent),
+                            None => clear_filter = true,
+                        }
+                    }
+                    if ui.button("Show all").clicked() {
+                        clear_filter = true;
+                    }
+                });
+            }
+
+            ui.separator();
+
             ui.horizontal(|ui| {
                 ui.heading("Age");
                 ui.heading("Capital");
@@ -42,39 +220,9 @@ impl AnalyticsComponent {
                 let balance = history.get(age).unwrap_or(&0.0);
 
                 // Calculate total expenses and income for this age (considering frequency)
-                let total_expenses: f64 = simulator
-                    .get_person()
-                    .expenses
-                    .iter()
-                    .filter(|e| {
-                        e.start_age <= *age && (e.end_age.is_none() || e.end_age.unwrap() >= *age)
-                    })
-                    .map(|e| {
-                        // Convert to yearly amount based on frequency
-                        match e.frequency {
-                            crate::domain::Frequency::Yearly => e.amount,
-                            crate::domain::Frequency::Monthly => e.amount * 12.0,
-                            crate::domain::Frequency::Daily => e.amount * 365.0,
-                        }
-                    })
-                    .sum();
-
-                let total_incomes: f64 = simulator
-                    .get_person()
-                    .incomes
-                    .iter()
-                    .filter(|i| {
-                        i.start_age <= *age && (i.end_age.is_none() || i.end_age.unwrap() >= *age)
-                    })
-                    .map(|i| {
-                        // Convert to yearly amount based on frequency
-                        match i.frequency {
-                            crate::domain::Frequency::Yearly => i.amount,
-                            crate::domain::Frequency::Monthly => i.amount * 12.0,
-                            crate::domain::Frequency::Daily => i.amount * 365.0,
-                        }
-                    })
-                    .sum();
+                let totals = simulator.yearly_totals(*age, state.category_filter.as_ref());
+                let total_expenses = totals.expenses;
+                let total_incomes = totals.incomes;
 
                 // Calculate net change from previous year if available
                 let prev_balance = if *age > 0 {
@@ -94,5 +242,11 @@ impl AnalyticsComponent {
                 });
             }
         });
+
+        if clear_filter {
+            state.category_filter = None;
+        } else if let Some(category) = clicked_category {
+            state.category_filter = Some(category);
+        }
     }
 }
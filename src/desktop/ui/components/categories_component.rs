@@ -0,0 +1,92 @@
+use crate::desktop::ui::components::shared_state::SharedState;
+use crate::domain::Category;
+use eframe::egui;
+
+pub struct CategoriesComponent {
+    new_path: String,
+    rename_target: Option<Category>,
+    rename_to: String,
+}
+
+impl Default for CategoriesComponent {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CategoriesComponent {
+    pub fn new() -> Self {
+        Self {
+            new_path: String::new(),
+            rename_target: None,
+            rename_to: String::new(),
+        }
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui, state: &mut SharedState) {
+        let Some(simulator) = state.scenarios.active_mut() else {
+            ui.label("Please create a person first in the Setup tab.");
+            return;
+        };
+
+        ui.heading("Categories");
+
+        ui.horizontal(|ui| {
+            ui.label("New category (e.g. Housing > Rent):");
+            ui.text_edit_singleline(&mut self.new_path);
+            if ui.button("Add").clicked() {
+                let category = Category::parse(&self.new_path);
+                if !category.is_uncategorized() {
+                    simulator.get_person_mut().categories.add(category);
+                    self.new_path.clear();
+                }
+            }
+        });
+
+        if let Some(target) = self.rename_target.clone() {
+            ui.horizontal(|ui| {
+                ui.label(format!("Rename \"{}\" to:", target));
+                ui.text_edit_singleline(&mut self.rename_to);
+                if ui.button("Rename").clicked() && !self.rename_to.trim().is_empty() {
+                    simulator.rename_category(&target, self.rename_to.trim());
+                    self.rename_target = None;
+                }
+                if ui.button("Cancel").clicked() {
+                    self.rename_target = None;
+                }
+            });
+        }
+
+        ui.separator();
+
+        let categories: Vec<Category> = simulator.get_person().categories.iter().cloned().collect();
+        let mut removed = None;
+
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            for category in &categories {
+                ui.horizontal(|ui| {
+                    ui.add_space(16.0 * (category.depth() - 1) as f32);
+                    ui.label(category.name());
+                    if ui.small_button("Rename").clicked() {
+                        self.rename_target = Some(category.clone());
+                        self.rename_to = category.name().to_string();
+                    }
+                    if ui.small_button("Remove").clicked() {
+                        removed = Some(category.clone());
+                    }
+                });
+            }
+        });
+
+        if let Some(category) = removed {
+            simulator.remove_category(&category);
+            if state
+                .category_filter
+                .as_ref()
+                .is_some_and(|filter| filter.is_within(&category))
+            {
+                state.category_filter = None;
+            }
+        }
+    }
+}
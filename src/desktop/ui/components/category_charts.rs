@@ -0,0 +1,174 @@
+use crate::domain::Category;
+use eframe::egui;
+use egui_plot::{Bar, BarChart, Legend, Plot, PlotPoints, Polygon};
+use std::f64::consts::TAU;
+
+// Colors cycled through for consecutive categories
+const PALETTE: [egui::Color32; 10] = [
+    egui::Color32::from_rgb(31, 119, 180),
+    egui::Color32::from_rgb(255, 127, 14),
+    egui::Color32::from_rgb(44, 160, 44),
+    egui::Color32::from_rgb(214, 39, 40),
+    egui::Color32::from_rgb(148, 103, 189),
+    egui::Color32::from_rgb(140, 86, 75),
+    egui::Color32::from_rgb(227, 119, 194),
+    egui::Color32::from_rgb(127, 127, 127),
+    egui::Color32::from_rgb(188, 189, 34),
+    egui::Color32::from_rgb(23, 190, 207),
+];
+
+// Number of straight segments used to approximate a full circle
+const CIRCLE_STEPS: usize = 120;
+
+pub fn category_color(index: usize) -> egui::Color32 {
+    PALETTE[index % PALETTE.len()]
+}
+
+/// Draws a pie chart (or a donut when `inner_radius > 0`) of the given amounts.
+/// Returns the category of the slice that was clicked, if any.
+pub fn show_pie(
+    ui: &mut egui::Ui,
+    id_source: &str,
+    slices: &[(Category, f64)],
+    inner_radius: f64,
+) -> Option<Category> {
+    let total: f64 = slices.iter().map(|(_, amount)| amount.max(0.0)).sum();
+    if total <= 0.0 {
+        ui.label("Nothing to chart for this selection.");
+        return None;
+    }
+
+    let plot = Plot::new(id_source)
+        .legend(Legend::default())
+        .data_aspect(1.0)
+        .view_aspect(1.5)
+        .show_axes([false, false])
+        .show_grid([false, false])
+        .allow_zoom(false)
+        .allow_drag(false)
+        .allow_scroll(false)
+        .include_x(-1.5)
+        .include_x(1.5)
+        .include_y(-1.1)
+        .include_y(1.1)
+        .label_formatter(|name, _| name.to_string());
+
+    plot.show(ui, |plot_ui| {
+        let mut start_angle = 0.0;
+        for (index, (category, amount)) in slices.iter().enumerate() {
+            let sweep = amount.max(0.0) / total * TAU;
+            let name = format!(
+                "{} ({:.2}, {:.1}%)",
+                category,
+                amount,
+                amount / total * 100.0
+            );
+            // egui only fills convex polygons, so every slice is drawn as thin wedges
+            let steps = ((sweep / TAU * CIRCLE_STEPS as f64).ceil() as usize).max(1);
+            for step in 0..steps {
+                let a0 = start_angle + sweep * step as f64 / steps as f64;
+                let a1 = start_angle + sweep * (step + 1) as f64 / steps as f64;
+                let mut points = vec![
+                    [a0.cos(), a0.sin()],
+                    [a1.cos(), a1.sin()],
+                    [inner_radius * a1.cos(), inner_radius * a1.sin()],
+                ];
+                if inner_radius > 0.0 {
+                    points.push([inner_radius * a0.cos(), inner_radius * a0.sin()]);
+                }
+                plot_ui.polygon(
+                    Polygon::new(PlotPoints::new(points))
+                        .name(&name)
+                        .fill_color(category_color(index))
+                        .width(0.0),
+                );
+            }
+            start_angle += sweep;
+        }
+
+        if !plot_ui.response().clicked() {
+            return None;
+        }
+        let pointer = plot_ui.pointer_coordinate()?;
+        let radius = pointer.x.hypot(pointer.y);
+        if radius > 1.0 || radius < inner_radius {
+            return None;
+        }
+        let angle = pointer.y.atan2(pointer.x).rem_euclid(TAU);
+        let mut end_angle = 0.0;
+        for (category, amount) in slices {
+            end_angle += amount.max(0.0) / total * TAU;
+            if angle <= end_angle {
+                return Some(category.clone());
+            }
+        }
+        None
+    })
+    .inner
+}
+
+/// Draws one stacked bar per age, split by category.
+/// `series` holds one `(category, [(age, amount)])` entry per stacked layer.
+/// Returns the category of the bar segment that was clicked, if any.
+pub fn show_stacked_bars(
+    ui: &mut egui::Ui,
+    id_source: &str,
+    series: &[(Category, Vec<(u32, f64)>)],
+) -> Option<Category> {
+    if series.is_empty() {
+        ui.label("Nothing to chart for this selection.");
+        return None;
+    }
+
+    let mut charts: Vec<BarChart> = Vec::new();
+    for (index, (category, amounts)) in series.iter().enumerate() {
+        let bars = amounts
+            .iter()
+            .map(|&(age, amount)| {
+                Bar::new(age as f64, amount)
+                    .width(0.8)
+                    .name(format!("{} at {}", category, age))
+            })
+            .collect();
+        let others: Vec<&BarChart> = charts.iter().collect();
+        let chart = BarChart::new(bars)
+            .name(category.to_string())
+            .color(category_color(index))
+            .stack_on(&others);
+        charts.push(chart);
+    }
+
+    Plot::new(id_source)
+        .legend(Legend::default())
+        .view_aspect(3.0)
+        .x_axis_formatter(|value, _range, _digits| format!("{}", value as u32))
+        .y_axis_formatter(|value, _range, _digits| format!("{:.2}", value))
+        .allow_zoom([true, true])
+        .allow_drag([true, true])
+        .show(ui, |plot_ui| {
+            for chart in charts {
+                plot_ui.bar_chart(chart);
+            }
+
+            if !plot_ui.response().clicked() {
+                return None;
+            }
+            let pointer = plot_ui.pointer_coordinate()?;
+            let age = pointer.x.round();
+            if (pointer.x - age).abs() > 0.4 || pointer.y < 0.0 {
+                return None;
+            }
+            let mut top = 0.0;
+            for (category, amounts) in series {
+                top += amounts
+                    .iter()
+                    .find(|(a, _)| *a as f64 == age)
+                    .map_or(0.0, |(_, amount)| *amount);
+                if pointer.y <= top {
+                    return Some(category.clone());
+                }
+            }
+            None
+        })
+        .inner
+}
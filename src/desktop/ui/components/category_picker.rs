@@ -0,0 +1,59 @@
+use crate::domain::{Category, CategoryTree};
+use eframe::egui;
+
+fn indented(category: &Category) -> String {
+    format!(
+        "{}{}",
+        "  ".repeat(category.depth().saturating_sub(1)),
+        category.name()
+    )
+}
+
+/// Combo box for choosing the category of a new item.
+pub fn category_picker(
+    ui: &mut egui::Ui,
+    id_source: &str,
+    tree: &CategoryTree,
+    selected: &mut Category,
+) {
+    egui::ComboBox::from_id_source(id_source)
+        .selected_text(selected.to_string())
+        .show_ui(ui, |ui| {
+            ui.selectable_value(selected, Category::uncategorized(), "Uncategorized");
+            for category in tree.iter() {
+                ui.selectable_value(selected, category.clone(), indented(category));
+            }
+        });
+}
+
+/// Combo box for restricting a view to one category subtree; `None` shows everything.
+pub fn category_filter(
+    ui: &mut egui::Ui,
+    id_source: &str,
+    tree: &CategoryTree,
+    filter: &mut Option<Category>,
+) {
+    ui.label("Category:");
+    egui::ComboBox::from_id_source(id_source)
+        .selected_text(
+            filter
+                .as_ref()
+                .map_or("All categories".to_string(), |c| c.to_string()),
+        )
+        .show_ui(ui, |ui| {
+            ui.selectable_value(filter, None, "All categories");
+            ui.selectable_value(filter, Some(Category::uncategorized()), "Uncategorized");
+            for category in tree.iter() {
+                ui.selectable_value(filter, Some(category.clone()), indented(category));
+            }
+        });
+}
+
+/// Splits a comma separated tag list typed by the user.
+pub fn parse_tags(input: &str) -> Vec<String> {
+    input
+        .split(',')
+        .map(|tag| tag.trim().to_string())
+        .filter(|tag| !tag.is_empty())
+        .collect()
+}
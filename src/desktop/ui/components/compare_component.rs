@@ -0,0 +1,140 @@
+use crate::desktop::ui::components::category_charts::category_color;
+use crate::desktop::ui::components::shared_state::SharedState;
+use crate::domain::{BalanceDelta, compare_balances};
+use eframe::egui;
+use egui_plot::{Legend, Line, Plot, PlotPoints};
+
+pub struct CompareComponent {
+    baseline: usize,
+    included: Vec<bool>,
+}
+
+impl Default for CompareComponent {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CompareComponent {
+    pub fn new() -> Self {
+        Self {
+            baseline: 0,
+            included: Vec::new(),
+        }
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui, state: &mut SharedState) {
+        ui.heading("Compare Scenarios");
+
+        if state.scenarios.len() < 2 {
+            ui.label("Create or open at least two scenarios to compare them.");
+            return;
+        }
+
+        self.included.resize(state.scenarios.len(), true);
+        self.baseline = self.baseline.min(state.scenarios.len() - 1);
+
+        ui.horizontal(|ui| {
+            ui.label("Baseline:");
+            egui::ComboBox::from_id_source("compare_baseline")
+                .selected_text(state.scenarios.get(self.baseline).unwrap().name.clone())
+                .show_ui(ui, |ui| {
+                    for (index, scenario) in state.scenarios.iter().enumerate() {
+                        ui.selectable_value(&mut self.baseline, index, &scenario.name);
+                    }
+                });
+        });
+
+        ui.horizontal_wrapped(|ui| {
+            ui.label("Show:");
+            for (index, scenario) in state.scenarios.iter().enumerate() {
+                ui.checkbox(&mut self.included[index], &scenario.name);
+            }
+        });
+
+        // Project every included scenario against the baseline
+        let mut baseline = state
+            .scenarios
+            .get(self.baseline)
+            .unwrap()
+            .simulator
+            .clone();
+        // Every scenario is projected as far as the baseline's settings go
+        let end_age = baseline.get_simulation().end_age();
+        let mut series: Vec<(String, usize, Vec<BalanceDelta>)> = Vec::new();
+        for (index, scenario) in state.scenarios.iter_mut().enumerate() {
+            if index == self.baseline || !self.included[index] {
+                continue;
+            }
+            let deltas = compare_balances(&mut baseline, &mut scenario.simulator, end_age);
+            series.push((scenario.name.clone(), index, deltas));
+        }
+        let baseline_name = state.scenarios.get(self.baseline).unwrap().name.clone();
+        let baseline_points: Vec<[f64; 2]> = {
+            let start_age = baseline.get_current_age();
+            (start_age..=end_age)
+                .map(|age| [age as f64, baseline.calculate_balance_at_age(age)])
+                .collect()
+        };
+
+        ui.separator();
+
+        Plot::new("compare_plot")
+            .legend(Legend::default())
+            .view_aspect(3.0)
+            .x_axis_formatter(|value, _range, _digits| format!("{}", value as u32))
+            .y_axis_formatter(|value, _range, _digits| format!("{:.2}", value))
+            .label_formatter(|name, value| format!("{}: ({:.0}, {:.2})", name, value.x, value.y))
+            .show(ui, |plot_ui| {
+                plot_ui.line(
+                    Line::new(PlotPoints::new(baseline_points.clone()))
+                        .name(&baseline_name)
+                        .color(category_color(self.baseline))
+                        .width(2.0),
+                );
+                for (name, index, deltas) in &series {
+                    let points: PlotPoints = deltas
+                        .iter()
+                        .map(|delta| [delta.age as f64, delta.other])
+                        .collect();
+                    plot_ui.line(Line::new(points).name(name).color(category_color(*index)));
+                }
+            });
+
+        ui.separator();
+        ui.heading(format!("Difference vs {}", baseline_name));
+
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            egui::Grid::new("compare_table")
+                .striped(true)
+                .show(ui, |ui| {
+                    ui.strong("Age");
+                    ui.strong(&baseline_name);
+                    for (name, _, _) in &series {
+                        ui.strong(name);
+                        ui.strong("Delta");
+                    }
+                    ui.end_row();
+
+                    for [age, baseline_balance] in &baseline_points {
+                        let age = *age as u32;
+                        ui.label(age.to_string());
+                        ui.label(format!("{:.2}", baseline_balance));
+                        for (_, _, deltas) in &series {
+                            match deltas.iter().find(|delta| delta.age == age) {
+                                Some(delta) => {
+                                    ui.label(format!("{:.2}", delta.other));
+                                    ui.label(format!("{:+.2}", delta.delta()));
+                                }
+                                None => {
+                                    ui.label("-");
+                                    ui.label("-");
+                                }
+                            }
+                        }
+                        ui.end_row();
+                    }
+                });
+        });
+    }
+}
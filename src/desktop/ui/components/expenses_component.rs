@@ -1,5 +1,7 @@
+use crate::desktop::ui::components::category_picker::{category_picker, parse_tags};
+use crate::desktop::ui::components::household_component::{ItemOwner, owner_picker};
 use crate::desktop::ui::components::shared_state::SharedState;
-use crate::domain::{Expense, Frequency};
+use crate::domain::{BranchChange, Category, Expense, Frequency};
 use eframe::egui;
 
 pub struct ExpensesComponent {
@@ -8,6 +10,15 @@ pub struct ExpensesComponent {
     expense_frequency: Frequency,
     expense_start_age: String,
     expense_end_age: String,
+    expense_category: Category,
+    expense_tags: String,
+    expense_owner: ItemOwner,
+}
+
+impl Default for ExpensesComponent {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl ExpensesComponent {
@@ -18,48 +29,84 @@ impl ExpensesComponent {
             expense_frequency: Frequency::Yearly,
             expense_start_age: String::new(),
             expense_end_age: String::new(),
+            expense_category: Category::uncategorized(),
+            expense_tags: String::new(),
+            expense_owner: ItemOwner::Main,
         }
     }
 
-    pub fn add_expense(&mut self, state: &mut SharedState) {
-        if let Some(ref mut simulator) = state.simulator {
-            if let (Ok(amount_val), Ok(start_age_val)) = (
-                self.expense_amount.parse::<f64>(),
-                self.expense_start_age.parse::<u32>(),
-            ) {
-                let end_age = if !self.expense_end_age.is_empty() {
-                    self.expense_end_age.parse::<u32>().ok()
-                } else {
-                    None
-                };
-
-                let expense = Expense::new(
-                    self.expense_name.clone(),
-                    amount_val,
-                    self.expense_frequency.clone(),
-                    start_age_val,
-                    end_age,
-                );
+    /// Builds the expense described by the input fields, if they are valid.
+    fn build_expense(&self) -> Option<Expense> {
+        let amount_val = self.expense_amount.parse::<f64>().ok()?;
+        let start_age_val = self.expense_start_age.parse::<u32>().ok()?;
+        let end_age = if !self.expense_end_age.is_empty() {
+            self.expense_end_age.parse::<u32>().ok()
+        } else {
+            None
+        };
+
+        Some(
+            Expense::new(
+                self.expense_name.clone(),
+                amount_val,
+                self.expense_frequency.clone(),
+                start_age_val,
+                end_age,
+            )
+            .with_category(self.expense_category.clone())
+            .with_tags(parse_tags(&self.expense_tags)),
+        )
+    }
 
-                simulator.add_expense(expense);
+    fn clear_inputs(&mut self) {
+        // Clear the input fields
+        self.expense_name.clear();
+        self.expense_amount.clear();
+        self.expense_start_age.clear();
+        self.expense_end_age.clear();
+        self.expense_tags.clear();
+    }
 
-                // Clear the input fields
-                self.expense_name.clear();
-                self.expense_amount.clear();
-                self.expense_start_age.clear();
-                self.expense_end_age.clear();
+    pub fn add_expense(&mut self, state: &mut SharedState) {
+        if state.scenarios.active().is_some()
+            && let Some(expense) = self.build_expense()
+        {
+            match self.expense_owner {
+                ItemOwner::Main => state.scenarios.add_expense(expense),
+                ItemOwner::Member(index) => {
+                    if let Some(simulator) = state.scenarios.active_mut() {
+                        simulator.add_member_expense(index, expense);
+                    }
+                }
+                ItemOwner::Shared => {
+                    if let Some(simulator) = state.scenarios.active_mut() {
+                        simulator.add_shared_expense(expense);
+                    }
+                }
             }
+            self.clear_inputs();
         }
     }
 
     pub fn show(&mut self, ui: &mut egui::Ui, state: &mut SharedState) {
-        if state.simulator.is_none() {
+        if state.scenarios.active().is_none() {
             ui.label("Please create a person first in the Setup tab.");
             return;
         }
 
         ui.heading("Add Expenses");
 
+        if let Some(scenario) = state.scenarios.active_scenario() {
+            owner_picker(
+                ui,
+                "expense_owner",
+                &scenario.simulator,
+                scenario.branch.is_some(),
+                true,
+                &mut self.expense_owner,
+            );
+        }
+
         ui.horizontal(|ui| {
             ui.label("Expense Name:");
             ui.text_edit_singleline(&mut self.expense_name);
@@ -87,27 +134,119 @@ impl ExpensesComponent {
             ui.text_edit_singleline(&mut self.expense_end_age);
         });
 
+        if let Some(simulator) = state.scenarios.active() {
+            ui.horizontal(|ui| {
+                ui.label("Category:");
+                category_picker(
+                    ui,
+                    "expense_category",
+                    &simulator.get_person().categories,
+                    &mut self.expense_category,
+                );
+            });
+        }
+
+        ui.horizontal(|ui| {
+            ui.label("Tags (comma separated):");
+            ui.text_edit_singleline(&mut self.expense_tags);
+        });
+
         if ui.button("Add Expense").clicked() {
             self.add_expense(state);
         }
 
         // Show existing expenses
-        if let Some(simulator) = &state.simulator {
+        if let Some(scenario) = state.scenarios.active_scenario() {
             ui.separator();
             ui.heading("Current Expenses:");
-            for (i, expense) in simulator.get_person().expenses.iter().enumerate() {
+            let mut change = None;
+            for (i, expense) in scenario.simulator.get_person().expenses.iter().enumerate() {
+                ui.horizontal(|ui| {
+                    ui.label(format!(
+                        "{}. {}: {:.2} ({:?}) - Age {} to {} [{}]{}",
+                        i + 1,
+                        expense.name,
+                        expense.amount,
+                        expense.frequency,
+                        expense.start_age,
+                        expense
+                            .end_age
+                            .map_or("ongoing".to_string(), |age| age.to_string()),
+                        expense.category,
+                        if expense.tags.is_empty() {
+                            String::new()
+                        } else {
+                            format!(" #{}", expense.tags.join(" #"))
+                        }
+                    ));
+
+                    // Branches override inherited items only from the fork age on
+                    if let Some(branch) = &scenario.branch {
+                        if ui
+                            .small_button(format!("Stop at {}", branch.fork_age))
+                            .clicked()
+                        {
+                            change = Some(BranchChange::ReplaceExpense {
+                                name: expense.name.clone(),
+                                with: None,
+                            });
+                        }
+                        if ui
+                            .small_button(format!("Replace from {} with form", branch.fork_age))
+                            .on_hover_text("Uses the values entered above")
+                            .clicked()
+                            && let Some(replacement) = self.build_expense()
+                        {
+                            change = Some(BranchChange::ReplaceExpense {
+                                name: expense.name.clone(),
+                                with: Some(replacement),
+                            });
+                        }
+                    }
+                });
+            }
+
+            // Items of other household members, timed by their own ages
+            let household = scenario.simulator.get_household();
+            for member in &household.members {
+                for expense in &member.person.expenses {
+                    ui.label(format!(
+                        "{}: {}: {:.2} ({:?}) - their age {} to {} [{}]",
+                        member.person.name,
+                        expense.name,
+                        expense.amount,
+                        expense.frequency,
+                        expense.start_age,
+                        expense
+                            .end_age
+                            .map_or("ongoing".to_string(), |age| age.to_string()),
+                        expense.category
+                    ));
+                }
+            }
+            let child_expenses = household.children.iter().flat_map(|child| child.expenses());
+            for expense in household
+                .shared_expenses
+                .iter()
+                .cloned()
+                .chain(child_expenses)
+            {
                 ui.label(format!(
-                    "{}. {}: {:.2} ({:?}) - Age {} to {}",
-                    i + 1,
+                    "Household: {}: {:.2} ({:?}) - Age {} to {} [{}]",
                     expense.name,
                     expense.amount,
                     expense.frequency,
                     expense.start_age,
                     expense
                         .end_age
-                        .map_or("ongoing".to_string(), |age| age.to_string())
+                        .map_or("ongoing".to_string(), |age| age.to_string()),
+                    expense.category
                 ));
             }
+
+            if let Some(change) = change {
+                state.scenarios.push_branch_change(change);
+            }
         }
     }
 }
@@ -0,0 +1,104 @@
+use crate::desktop::ui::components::shared_state::SharedState;
+use crate::infrastructure::export::{ReportFormat, markdown_report};
+use eframe::egui;
+use std::fs;
+
+pub struct ExportComponent {
+    /// Report being exported from the File menu, if its window is open.
+    report_format: Option<ReportFormat>,
+    file_path: String,
+    status: Option<String>,
+}
+
+impl Default for ExportComponent {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ExportComponent {
+    pub fn new() -> Self {
+        Self {
+            report_format: None,
+            file_path: String::new(),
+            status: None,
+        }
+    }
+
+    /// File menu for the top panel.
+    pub fn show_file_menu(&mut self, ui: &mut egui::Ui, state: &mut SharedState) {
+        ui.menu_button("File", |ui| {
+            let enabled = state.scenarios.active().is_some();
+            for format in ReportFormat::ALL {
+                if ui
+                    .add_enabled(
+                        enabled,
+                        egui::Button::new(format!("Export {} report...", format.label())),
+                    )
+                    .clicked()
+                {
+                    let name = state
+                        .scenarios
+                        .active_scenario()
+                        .map_or("report".to_string(), |scenario| scenario.name.clone());
+                    self.file_path = format!("{}.{}", name, format.extension());
+                    self.report_format = Some(format);
+                    self.status = None;
+                    ui.close_menu();
+                }
+            }
+            ui.separator();
+            // Summary for pasting into a wiki or chat
+            if ui
+                .add_enabled(enabled, egui::Button::new("Copy as Markdown"))
+                .clicked()
+                && let Some(scenario) = state.scenarios.active_scenario_mut()
+            {
+                let end_age = scenario.simulator.get_simulation().end_age();
+                let markdown = markdown_report(scenario, end_age);
+                ui.ctx().output_mut(|output| output.copied_text = markdown);
+                ui.close_menu();
+            }
+        });
+    }
+
+    /// Window asking where to write the chosen report.
+    pub fn show_window(&mut self, ctx: &egui::Context, state: &mut SharedState) {
+        let Some(format) = self.report_format else {
+            return;
+        };
+        let mut open = true;
+        egui::Window::new(format!("Export {} report", format.label()))
+            .open(&mut open)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("File:");
+                    ui.text_edit_singleline(&mut self.file_path);
+                });
+                let end_age = state
+                    .scenarios
+                    .active()
+                    .map_or(0, |simulator| simulator.get_simulation().end_age());
+                ui.label(format!(
+                    "Projects up to age {} (change it in Settings)",
+                    end_age
+                ));
+                if ui.button("Export").clicked()
+                    && let Some(scenario) = state.scenarios.active_scenario_mut()
+                {
+                    let report = format.render(scenario, end_age);
+                    self.status = Some(match fs::write(&self.file_path, report) {
+                        Ok(()) => format!("Saved {}", self.file_path),
+                        Err(e) => format!("Failed to write file: {}", e),
+                    });
+                }
+                if let Some(status) = &self.status {
+                    ui.label(status);
+                }
+            });
+        if !open {
+            self.report_format = None;
+        }
+    }
+}
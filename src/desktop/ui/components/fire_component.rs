@@ -0,0 +1,129 @@
+use crate::desktop::ui::components::shared_state::SharedState;
+use crate::domain::{FireVariant, fire_report};
+use eframe::egui;
+use egui_plot::{Legend, Line, Plot, PlotPoints, VLine};
+
+pub struct FireComponent {
+    variant: FireVariant,
+    withdrawal_rate: f64,
+}
+
+impl Default for FireComponent {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FireComponent {
+    pub fn new() -> Self {
+        Self {
+            variant: FireVariant::Regular,
+            withdrawal_rate: 4.0,
+        }
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui, state: &mut SharedState) {
+        ui.heading("Financial Independence (FIRE)");
+
+        let Some(simulator) = state.scenarios.active_mut() else {
+            ui.label("Please create a person first in the Setup tab.");
+            return;
+        };
+
+        ui.horizontal(|ui| {
+            ui.label("Variant:");
+            for variant in FireVariant::ALL {
+                ui.radio_value(&mut self.variant, variant, variant.to_string());
+            }
+        });
+        ui.add(
+            egui::Slider::new(&mut self.withdrawal_rate, 1.0..=10.0)
+                .text("Safe withdrawal rate")
+                .suffix("%"),
+        );
+        let end_age = simulator.get_simulation().end_age();
+        ui.label(format!("Up to age {} (change it in Settings)", end_age));
+
+        let report = fire_report(
+            simulator,
+            self.variant,
+            self.withdrawal_rate / 100.0,
+            end_age,
+        );
+
+        ui.separator();
+        if let Some(fire_number) = report.current_fire_number() {
+            ui.label(format!("FIRE number today: {:.2}", fire_number));
+        }
+        if let Some(rate) = report.average_savings_rate() {
+            ui.label(format!("Average savings rate: {:.1}%", rate * 100.0));
+        }
+        match report.fire_age {
+            Some(age) => ui.strong(format!("Financially independent at age {}", age)),
+            None => ui.strong(format!("Not financially independent by age {}", end_age)),
+        };
+
+        let balance_points: PlotPoints = report
+            .years
+            .iter()
+            .map(|year| [year.age as f64, year.balance])
+            .collect();
+        let fire_points: PlotPoints = report
+            .years
+            .iter()
+            .map(|year| [year.age as f64, year.fire_number])
+            .collect();
+
+        Plot::new("fire_plot")
+            .legend(Legend::default())
+            .view_aspect(3.0)
+            .x_axis_formatter(|value, _range, _digits| format!("{}", value as u32))
+            .y_axis_formatter(|value, _range, _digits| format!("{:.2}", value))
+            .label_formatter(|name, value| format!("{}: ({:.0}, {:.2})", name, value.x, value.y))
+            .show(ui, |plot_ui| {
+                plot_ui.line(Line::new(balance_points).name("Balance").width(2.0));
+                plot_ui.line(
+                    Line::new(fire_points)
+                        .name(format!("{} FIRE number", self.variant))
+                        .color(egui::Color32::GOLD)
+                        .style(egui_plot::LineStyle::dashed_dense()),
+                );
+                if let Some(age) = report.fire_age {
+                    plot_ui.vline(
+                        VLine::new(age as f64)
+                            .name("FIRE age")
+                            .color(egui::Color32::GREEN),
+                    );
+                }
+            });
+
+        egui::CollapsingHeader::new("Savings rate per year").show(ui, |ui| {
+            egui::ScrollArea::vertical()
+                .max_height(240.0)
+                .show(ui, |ui| {
+                    egui::Grid::new("fire_table").striped(true).show(ui, |ui| {
+                        ui.strong("Age");
+                        ui.strong("Incomes");
+                        ui.strong("Expenses");
+                        ui.strong("Savings rate");
+                        ui.strong("FIRE number");
+                        ui.strong("Balance");
+                        ui.end_row();
+                        for year in &report.years {
+                            ui.label(year.age.to_string());
+                            ui.label(format!("{:.2}", year.incomes));
+                            ui.label(format!("{:.2}", year.expenses));
+                            ui.label(
+                                year.savings_rate().map_or("-".to_string(), |rate| {
+                                    format!("{:.1}%", rate * 100.0)
+                                }),
+                            );
+                            ui.label(format!("{:.2}", year.fire_number));
+                            ui.label(format!("{:.2}", year.balance));
+                            ui.end_row();
+                        }
+                    });
+                });
+        });
+    }
+}
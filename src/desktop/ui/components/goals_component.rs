@@ -0,0 +1,108 @@
+use crate::desktop::ui::components::shared_state::SharedState;
+use crate::domain::{Goal, evaluate_goals};
+use eframe::egui;
+
+pub struct GoalsComponent {
+    goal_name: String,
+    goal_amount: String,
+    goal_age: String,
+}
+
+impl Default for GoalsComponent {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GoalsComponent {
+    pub fn new() -> Self {
+        Self {
+            goal_name: String::new(),
+            goal_amount: String::new(),
+            goal_age: String::new(),
+        }
+    }
+
+    fn build_goal(&self) -> Option<Goal> {
+        let name = self.goal_name.trim();
+        if name.is_empty() {
+            return None;
+        }
+        let amount = self.goal_amount.parse::<f64>().ok()?;
+        let by_age = self.goal_age.parse::<u32>().ok()?;
+        Some(Goal::new(name.to_string(), amount, by_age))
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui, state: &mut SharedState) {
+        let Some(scenario) = state.scenarios.active_scenario_mut() else {
+            return;
+        };
+
+        ui.heading("Goals");
+
+        ui.horizontal(|ui| {
+            ui.label("Goal:");
+            ui.text_edit_singleline(&mut self.goal_name);
+            ui.label("Target amount:");
+            ui.add(egui::TextEdit::singleline(&mut self.goal_amount).desired_width(100.0));
+            ui.label("By age:");
+            ui.add(egui::TextEdit::singleline(&mut self.goal_age).desired_width(50.0));
+            if ui.button("Add Goal").clicked()
+                && let Some(goal) = self.build_goal()
+            {
+                scenario.goals.push(goal);
+                self.goal_name.clear();
+                self.goal_amount.clear();
+                self.goal_age.clear();
+            }
+        });
+
+        if scenario.goals.is_empty() {
+            ui.label("No goals yet, e.g. \"Emergency fund\", 50000 by 30.");
+            return;
+        }
+
+        let progress = evaluate_goals(&mut scenario.simulator, &scenario.goals);
+        let mut removed = None;
+        egui::Grid::new("goals_table").striped(true).show(ui, |ui| {
+            ui.strong("Goal");
+            ui.strong("Target");
+            ui.strong("By age");
+            ui.strong("Projected");
+            ui.strong("Status");
+            ui.strong("Shortfall");
+            ui.strong("Extra saving / month");
+            ui.end_row();
+
+            for (index, goal) in progress.iter().enumerate() {
+                ui.label(&goal.goal.name);
+                ui.label(format!("{:.2}", goal.goal.target_amount));
+                ui.label(goal.goal.by_age.to_string());
+                ui.add(
+                    egui::ProgressBar::new(goal.fraction() as f32)
+                        .desired_width(120.0)
+                        .text(format!("{:.2}", goal.projected)),
+                );
+                if goal.is_met() {
+                    ui.colored_label(egui::Color32::GREEN, "Met");
+                } else {
+                    ui.colored_label(egui::Color32::RED, "Missed");
+                }
+                ui.label(format!("{:.2}", goal.shortfall()));
+                ui.label(match goal.required_monthly_saving {
+                    _ if goal.is_met() => "-".to_string(),
+                    Some(amount) => format!("{:.2}", amount),
+                    None => "too late".to_string(),
+                });
+                if ui.small_button("Remove").clicked() {
+                    removed = Some(index);
+                }
+                ui.end_row();
+            }
+        });
+
+        if let Some(index) = removed {
+            scenario.goals.remove(index);
+        }
+    }
+}
@@ -0,0 +1,333 @@
+use crate::desktop::ui::components::shared_state::SharedState;
+use crate::domain::{
+    Child, ChildStage, ChildTemplate, Household, LifeSimulator, Member, Person, Role,
+};
+use eframe::egui;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Average length of a year in seconds, accounting for leap years
+const SECONDS_PER_YEAR: u64 = 31_556_952;
+
+fn current_year() -> i32 {
+    let seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs());
+    1970 + (seconds / SECONDS_PER_YEAR) as i32
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum BirthInput {
+    MyAge,
+    Year,
+}
+
+/// Whose item is being added in the Expenses and Incomes tabs.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ItemOwner {
+    #[default]
+    Main,
+    Member(usize),
+    Shared,
+}
+
+impl ItemOwner {
+    fn label(&self, simulator: &LifeSimulator) -> String {
+        let household = simulator.get_household();
+        match self {
+            ItemOwner::Main => format!("{} (me)", simulator.get_person().name),
+            ItemOwner::Member(index) => household
+                .members
+                .get(*index)
+                .map_or("Unknown member".to_string(), |member| {
+                    format!("{} ({})", member.person.name, member.role)
+                }),
+            ItemOwner::Shared => "Shared (household)".to_string(),
+        }
+    }
+}
+
+/// Combo box for choosing whose item is added. Ages entered for a member are that member's
+/// own ages; shared items use the main person's ages. Branches inherit the parent's
+/// household, so only the main person can be picked there.
+pub fn owner_picker(
+    ui: &mut egui::Ui,
+    id_source: &str,
+    simulator: &LifeSimulator,
+    is_branch: bool,
+    allow_shared: bool,
+    owner: &mut ItemOwner,
+) {
+    let household = simulator.get_household();
+    if is_branch || (household.members.is_empty() && !allow_shared) {
+        *owner = ItemOwner::Main;
+        return;
+    }
+    if let ItemOwner::Member(index) = owner
+        && *index >= household.members.len()
+    {
+        *owner = ItemOwner::Main;
+    }
+
+    ui.horizontal(|ui| {
+        ui.label("For:");
+        egui::ComboBox::from_id_source(id_source)
+            .selected_text(owner.label(simulator))
+            .show_ui(ui, |ui| {
+                let mut owners = vec![ItemOwner::Main];
+                owners.extend((0..household.members.len()).map(ItemOwner::Member));
+                if allow_shared {
+                    owners.push(ItemOwner::Shared);
+                }
+                for choice in owners {
+                    ui.selectable_value(owner, choice, choice.label(simulator));
+                }
+            });
+    });
+}
+
+pub struct HouseholdComponent {
+    role: Role,
+    name: String,
+    age: String,
+    capital: String,
+    child_name: String,
+    birth_input: BirthInput,
+    birth: String,
+    child_benefits: bool,
+    child_template: ChildTemplate,
+}
+
+impl Default for HouseholdComponent {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HouseholdComponent {
+    pub fn new() -> Self {
+        Self {
+            role: Role::Partner,
+            name: String::new(),
+            age: String::new(),
+            capital: String::new(),
+            child_name: String::new(),
+            birth_input: BirthInput::MyAge,
+            birth: String::new(),
+            child_benefits: true,
+            child_template: ChildTemplate::default(),
+        }
+    }
+
+    fn build_child(&self, simulator: &LifeSimulator) -> Option<Child> {
+        let name = self.child_name.trim();
+        if name.is_empty() {
+            return None;
+        }
+        let template = self.child_template.clone();
+        let child = match self.birth_input {
+            BirthInput::MyAge => Child::new(name.to_string(), self.birth.parse().ok()?, template),
+            BirthInput::Year => Child::born_in_year(
+                name.to_string(),
+                self.birth.parse().ok()?,
+                current_year(),
+                simulator.get_current_age(),
+                template,
+            ),
+        };
+        Some(child.with_benefits(self.child_benefits))
+    }
+
+    fn build_member(&self) -> Option<Member> {
+        let age = self.age.parse::<u32>().ok()?;
+        let capital = if self.capital.trim().is_empty() {
+            0.0
+        } else {
+            self.capital.parse::<f64>().ok()?
+        };
+        Some(Member::new(
+            self.role,
+            Person::new(self.name.clone(), age, capital),
+        ))
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui, state: &mut SharedState) {
+        let Some(scenario) = state.scenarios.active_scenario() else {
+            return;
+        };
+
+        ui.heading("Household");
+
+        if let Some(branch) = &scenario.branch {
+            ui.label(format!(
+                "This branch shares the household of \"{}\"; edit it there.",
+                branch.parent
+            ));
+            Self::show_members(ui, scenario.simulator.get_household(), None);
+            return;
+        }
+
+        ui.horizontal(|ui| {
+            ui.radio_value(&mut self.role, Role::Partner, "Partner");
+            ui.radio_value(&mut self.role, Role::Child, "Child");
+            ui.label("Name:");
+            ui.text_edit_singleline(&mut self.name);
+        });
+        ui.horizontal(|ui| {
+            ui.label("Age:");
+            ui.add(egui::TextEdit::singleline(&mut self.age).desired_width(50.0));
+            ui.label("Capital (optional):");
+            ui.add(egui::TextEdit::singleline(&mut self.capital).desired_width(100.0));
+            if ui.button("Add to household").clicked()
+                && let Some(member) = self.build_member()
+                && let Some(simulator) = state.scenarios.active_mut()
+            {
+                simulator.add_member(member);
+                self.name.clear();
+                self.age.clear();
+                self.capital.clear();
+            }
+        });
+
+        let mut removed = None;
+        if let Some(simulator) = state.scenarios.active() {
+            Self::show_members(ui, simulator.get_household(), Some(&mut removed));
+        }
+        if let Some(index) = removed
+            && let Some(simulator) = state.scenarios.active_mut()
+        {
+            simulator.remove_member(index);
+        }
+
+        ui.add_space(8.0);
+        self.show_children(ui, state);
+    }
+
+    fn show_children(&mut self, ui: &mut egui::Ui, state: &mut SharedState) {
+        ui.label("Children with costs from a template:");
+        ui.horizontal(|ui| {
+            ui.label("Name:");
+            ui.add(egui::TextEdit::singleline(&mut self.child_name).desired_width(100.0));
+            ui.label("Born when");
+            ui.radio_value(&mut self.birth_input, BirthInput::MyAge, "I am age");
+            ui.radio_value(&mut self.birth_input, BirthInput::Year, "in year");
+            ui.add(egui::TextEdit::singleline(&mut self.birth).desired_width(50.0));
+            ui.checkbox(&mut self.child_benefits, "Child benefits");
+        });
+
+        egui::CollapsingHeader::new("Child template")
+            .id_source("child_template")
+            .show(ui, |ui| {
+                Self::stage_editor(ui, "child_stages", &mut self.child_template.stages);
+                ui.label("Benefits:");
+                Self::stage_editor(ui, "child_benefits", &mut self.child_template.benefits);
+                ui.horizontal(|ui| {
+                    ui.label("Support ends at age:");
+                    ui.add(
+                        egui::DragValue::new(&mut self.child_template.support_ends_at)
+                            .clamp_range(0..=40),
+                    );
+                    if ui.button("Reset template").clicked() {
+                        self.child_template = ChildTemplate::default();
+                    }
+                });
+            });
+
+        if ui.button("Add child").clicked()
+            && let Some(simulator) = state.scenarios.active_mut()
+            && let Some(child) = self.build_child(simulator)
+        {
+            simulator.add_child(child);
+            self.child_name.clear();
+            self.birth.clear();
+        }
+
+        let Some(simulator) = state.scenarios.active() else {
+            return;
+        };
+        let mut removed = None;
+        for (index, child) in simulator.get_household().children.iter().enumerate() {
+            ui.horizontal(|ui| {
+                ui.label(format!(
+                    "{}: born when you are {}, {} cost streams{}",
+                    child.name,
+                    child.born_at,
+                    child.expenses().len(),
+                    if child.benefits {
+                        format!(", {} benefits", child.incomes().len())
+                    } else {
+                        String::new()
+                    }
+                ));
+                if ui.small_button("Remove").clicked() {
+                    removed = Some(index);
+                }
+            });
+        }
+        if let Some(index) = removed
+            && let Some(simulator) = state.scenarios.active_mut()
+        {
+            simulator.remove_child(index);
+        }
+    }
+
+    /// Editable table of template stages, by the child's age.
+    fn stage_editor(ui: &mut egui::Ui, id_source: &str, stages: &mut Vec<ChildStage>) {
+        let mut removed = None;
+        egui::Grid::new(id_source).striped(true).show(ui, |ui| {
+            ui.strong("Stage");
+            ui.strong("From age");
+            ui.strong("To age");
+            ui.strong("Per month");
+            ui.end_row();
+            for (index, stage) in stages.iter_mut().enumerate() {
+                ui.add(egui::TextEdit::singleline(&mut stage.name).desired_width(120.0));
+                ui.add(egui::DragValue::new(&mut stage.from_age).clamp_range(0..=40));
+                ui.add(egui::DragValue::new(&mut stage.to_age).clamp_range(0..=40));
+                ui.add(
+                    egui::DragValue::new(&mut stage.monthly_amount)
+                        .speed(10.0)
+                        .clamp_range(0.0..=f64::MAX),
+                );
+                if ui.small_button("Remove").clicked() {
+                    removed = Some(index);
+                }
+                ui.end_row();
+            }
+        });
+        if let Some(index) = removed {
+            stages.remove(index);
+        }
+        if ui.small_button("Add stage").clicked() {
+            stages.push(ChildStage::new("New stage", 0, 18, 0.0));
+        }
+    }
+
+    fn show_members(
+        ui: &mut egui::Ui,
+        household: &Household,
+        mut removed: Option<&mut Option<usize>>,
+    ) {
+        if household.members.is_empty() {
+            ui.label("Add a partner or children to plan with a joint balance.");
+            return;
+        }
+        for (index, member) in household.members.iter().enumerate() {
+            ui.horizontal(|ui| {
+                ui.label(format!(
+                    "{}: {}, age {}, capital {:.2}, {} expenses, {} incomes",
+                    member.role,
+                    member.person.name,
+                    member.person.age,
+                    member.person.capital,
+                    member.person.expenses.len(),
+                    member.person.incomes.len()
+                ));
+                if let Some(removed) = removed.as_deref_mut()
+                    && ui.small_button("Remove").clicked()
+                {
+                    *removed = Some(index);
+                }
+            });
+        }
+    }
+}
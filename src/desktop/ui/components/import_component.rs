@@ -0,0 +1,382 @@
+use crate::desktop::ui::components::category_picker::category_picker;
+use crate::desktop::ui::components::shared_state::SharedState;
+use crate::domain::{
+    CategoryTree, LifeSimulator, Person, RecurringItem, Scenario, Transaction, detect_recurring,
+};
+use crate::infrastructure::import::{
+    AmountColumns, Column, CsvMapping, DateFormat, ImportFormat, import_statement,
+};
+use eframe::egui;
+use std::fs;
+use std::path::Path;
+
+pub struct ImportComponent {
+    file_path: String,
+    text: Option<String>,
+    format: ImportFormat,
+    mapping: CsvMapping,
+    date_column: String,
+    description_column: String,
+    amount_column: String,
+    credit_column: String,
+    debit_credit: bool,
+    positive_is_expense: bool,
+    transactions: Vec<Transaction>,
+    /// Account balance reported by the file, if any.
+    balance: Option<f64>,
+    /// Detected items and whether the user accepted them.
+    proposals: Vec<(RecurringItem, bool)>,
+    status: Option<String>,
+    person_name: String,
+    person_age: String,
+    person_capital: String,
+}
+
+impl Default for ImportComponent {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ImportComponent {
+    pub fn new() -> Self {
+        let mut component = Self {
+            file_path: String::from("transactions.csv"),
+            text: None,
+            format: ImportFormat::Csv,
+            mapping: CsvMapping::default(),
+            date_column: String::new(),
+            description_column: String::new(),
+            amount_column: String::new(),
+            credit_column: String::new(),
+            debit_credit: false,
+            positive_is_expense: false,
+            transactions: Vec::new(),
+            balance: None,
+            proposals: Vec::new(),
+            status: None,
+            person_name: String::new(),
+            person_age: String::new(),
+            person_capital: String::new(),
+        };
+        component.load_mapping(CsvMapping::default());
+        component
+    }
+
+    // Copies a mapping into the editable fields
+    fn load_mapping(&mut self, mapping: CsvMapping) {
+        let column_text = |column: &Column| match column {
+            Column::Index(index) => index.to_string(),
+            Column::Name(name) => name.clone(),
+        };
+        self.date_column = column_text(&mapping.date_column);
+        self.description_column = column_text(&mapping.description_column);
+        match &mapping.amount {
+            AmountColumns::Signed {
+                column,
+                positive_is_expense,
+            } => {
+                self.debit_credit = false;
+                self.amount_column = column_text(column);
+                self.positive_is_expense = *positive_is_expense;
+            }
+            AmountColumns::DebitCredit { debit, credit } => {
+                self.debit_credit = true;
+                self.amount_column = column_text(debit);
+                self.credit_column = column_text(credit);
+            }
+        }
+        self.mapping = mapping;
+    }
+
+    // Builds the mapping described by the editable fields
+    fn build_mapping(&self) -> CsvMapping {
+        let amount = if self.debit_credit {
+            AmountColumns::DebitCredit {
+                debit: Column::parse(&self.amount_column),
+                credit: Column::parse(&self.credit_column),
+            }
+        } else {
+            AmountColumns::Signed {
+                column: Column::parse(&self.amount_column),
+                positive_is_expense: self.positive_is_expense,
+            }
+        };
+        CsvMapping {
+            date_column: Column::parse(&self.date_column),
+            description_column: Column::parse(&self.description_column),
+            amount,
+            ..self.mapping.clone()
+        }
+    }
+
+    fn analyze(&mut self) {
+        let Some(text) = &self.text else {
+            return;
+        };
+        self.mapping = self.build_mapping();
+        match import_statement(text, self.format, &self.mapping) {
+            Ok(statement) => {
+                self.proposals = detect_recurring(&statement.transactions)
+                    .into_iter()
+                    .map(|item| (item, true))
+                    .collect();
+                self.status = Some(format!(
+                    "Read {} transactions, found {} recurring items",
+                    statement.transactions.len(),
+                    self.proposals.len()
+                ));
+                self.transactions = statement.transactions;
+                self.balance = statement.balance;
+                if let Some(balance) = self.balance {
+                    self.person_capital = format!("{:.2}", balance);
+                }
+            }
+            Err(e) => {
+                self.transactions.clear();
+                self.proposals.clear();
+                self.balance = None;
+                self.status = Some(e);
+            }
+        }
+    }
+
+    fn load_file(&mut self) {
+        match fs::read_to_string(&self.file_path) {
+            Ok(text) => {
+                self.format = ImportFormat::from_path(Path::new(&self.file_path));
+                match self.format {
+                    ImportFormat::Csv => self.load_mapping(CsvMapping::detect(&text)),
+                    // QIF files are mostly written with US dates
+                    _ => self.mapping.date_format = DateFormat::MonthDayYear,
+                }
+                self.text = Some(text);
+                self.analyze();
+            }
+            Err(e) => self.status = Some(format!("Failed to read file: {}", e)),
+        }
+    }
+
+    // Starts a new scenario whose person holds the accepted items
+    fn create_scenario(&mut self, state: &mut SharedState) {
+        let (Ok(age), Ok(capital)) = (
+            self.person_age.parse::<u32>(),
+            self.person_capital.parse::<f64>(),
+        ) else {
+            self.status = Some("Enter an age and a starting capital".to_string());
+            return;
+        };
+        let mut person = Person::new(self.person_name.clone(), age, capital);
+        for (item, _) in self.proposals.iter().filter(|(_, accepted)| *accepted) {
+            if item.is_income {
+                person.add_income(item.to_income(age));
+            } else {
+                person.add_expense(item.to_expense(age));
+            }
+        }
+        let name = format!("Imported from {}", self.file_path);
+        state
+            .scenarios
+            .add(Scenario::new(name, LifeSimulator::new(person)));
+        state.category_filter = None;
+        self.status = Some("Created a scenario from the imported items".to_string());
+    }
+
+    fn show_mapping(&mut self, ui: &mut egui::Ui) {
+        ui.collapsing("Column mapping", |ui| {
+            ui.label("Columns are header names or positions starting at 0.");
+            ui.horizontal(|ui| {
+                ui.label("Delimiter:");
+                ui.radio_value(&mut self.mapping.delimiter, ',', "Comma");
+                ui.radio_value(&mut self.mapping.delimiter, ';', "Semicolon");
+                ui.radio_value(&mut self.mapping.delimiter, '\t', "Tab");
+                ui.checkbox(&mut self.mapping.has_header, "First row is a header");
+            });
+            ui.horizontal(|ui| {
+                ui.label("Date:");
+                ui.add(egui::TextEdit::singleline(&mut self.date_column).desired_width(100.0));
+                date_format_picker(ui, "import_date_format", &mut self.mapping.date_format);
+                ui.label("Description:");
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.description_column).desired_width(100.0),
+                );
+            });
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut self.debit_credit, "Separate debit and credit columns");
+                if self.debit_credit {
+                    ui.label("Debit:");
+                    ui.add(egui::TextEdit::singleline(&mut self.amount_column).desired_width(80.0));
+                    ui.label("Credit:");
+                    ui.add(egui::TextEdit::singleline(&mut self.credit_column).desired_width(80.0));
+                } else {
+                    ui.label("Amount:");
+                    ui.add(egui::TextEdit::singleline(&mut self.amount_column).desired_width(80.0));
+                    ui.checkbox(
+                        &mut self.positive_is_expense,
+                        "Positive amounts are expenses",
+                    );
+                }
+            });
+            ui.checkbox(&mut self.mapping.decimal_comma, "Decimal comma (1.234,56)");
+            if ui.button("Apply mapping").clicked() {
+                self.analyze();
+            }
+        });
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui, state: &mut SharedState) {
+        ui.heading("Import Bank Transactions");
+
+        ui.horizontal(|ui| {
+            ui.label("File (CSV, OFX, QIF or ledger journal):");
+            ui.text_edit_singleline(&mut self.file_path);
+            if ui.button("Load").clicked() {
+                self.load_file();
+            }
+        });
+
+        if let Some(status) = &self.status {
+            ui.label(status);
+        }
+
+        if self.text.is_none() {
+            ui.label("Load a bank export to detect recurring expenses and incomes.");
+            return;
+        }
+
+        ui.horizontal(|ui| {
+            let before = (self.format, self.mapping.date_format);
+            ui.label("Read as:");
+            for option in ImportFormat::ALL {
+                ui.radio_value(&mut self.format, option, option.label());
+            }
+            if self.format == ImportFormat::Qif {
+                ui.label("Dates:");
+                date_format_picker(ui, "import_qif_dates", &mut self.mapping.date_format);
+            }
+            if (self.format, self.mapping.date_format) != before {
+                self.analyze();
+            }
+        });
+        if let Some(balance) = self.balance {
+            ui.label(format!("Balance in file: {:.2}", balance));
+        }
+
+        if self.format == ImportFormat::Csv {
+            self.show_mapping(ui);
+        }
+
+        if let (Some(first), Some(last)) = (
+            self.transactions.iter().map(|t| t.date).min(),
+            self.transactions.iter().map(|t| t.date).max(),
+        ) {
+            ui.label(format!("Transactions from {} to {}", first, last));
+        }
+
+        ui.separator();
+        if self.proposals.is_empty() {
+            ui.label("No recurring transactions found.");
+            return;
+        }
+
+        let current_age = state
+            .scenarios
+            .active()
+            .map(|simulator| simulator.get_current_age());
+        let categories = state
+            .scenarios
+            .active()
+            .map_or_else(CategoryTree::with_defaults, |simulator| {
+                simulator.get_person().categories.clone()
+            });
+
+        ui.label("Confirm the items to keep. Names and categories can be edited.");
+        egui::ScrollArea::vertical()
+            .id_source("import_proposals")
+            .max_height(400.0)
+            .show(ui, |ui| {
+                egui::Grid::new("import_table")
+                    .striped(true)
+                    .show(ui, |ui| {
+                        ui.strong("Add");
+                        ui.strong("Name");
+                        ui.strong("Type");
+                        ui.strong("Amount");
+                        ui.strong("Frequency");
+                        ui.strong("Seen");
+                        ui.strong("Category");
+                        ui.end_row();
+
+                        for (index, (item, accepted)) in self.proposals.iter_mut().enumerate() {
+                            ui.checkbox(accepted, "");
+                            ui.text_edit_singleline(&mut item.name);
+                            ui.label(if item.is_income { "Income" } else { "Expense" });
+                            ui.label(format!("{:.2}", item.amount));
+                            ui.label(format!("{:?}", item.frequency));
+                            ui.label(format!("{}x", item.occurrences));
+                            category_picker(
+                                ui,
+                                &format!("import_category_{}", index),
+                                &categories,
+                                &mut item.category,
+                            );
+                            ui.end_row();
+                        }
+                    });
+            });
+
+        ui.horizontal(|ui| {
+            if ui.button("Select all").clicked() {
+                self.proposals
+                    .iter_mut()
+                    .for_each(|(_, accepted)| *accepted = true);
+            }
+            if ui.button("Select none").clicked() {
+                self.proposals
+                    .iter_mut()
+                    .for_each(|(_, accepted)| *accepted = false);
+            }
+            if let Some(current_age) = current_age
+                && ui
+                    .button(format!("Add selected from age {}", current_age))
+                    .clicked()
+            {
+                let mut added = 0;
+                for (item, _) in self.proposals.iter().filter(|(_, accepted)| *accepted) {
+                    if item.is_income {
+                        state.scenarios.add_income(item.to_income(current_age));
+                    } else {
+                        state.scenarios.add_expense(item.to_expense(current_age));
+                    }
+                    added += 1;
+                }
+                self.proposals.retain(|(_, accepted)| !*accepted);
+                self.status = Some(format!("Added {} items to the active scenario", added));
+            }
+        });
+
+        ui.separator();
+        ui.label("Or start a new scenario from the selected items:");
+        ui.horizontal(|ui| {
+            ui.label("Name:");
+            ui.add(egui::TextEdit::singleline(&mut self.person_name).desired_width(120.0));
+            ui.label("Age:");
+            ui.add(egui::TextEdit::singleline(&mut self.person_age).desired_width(50.0));
+            ui.label("Current capital:");
+            ui.add(egui::TextEdit::singleline(&mut self.person_capital).desired_width(100.0));
+            if ui.button("Create scenario").clicked() {
+                self.create_scenario(state);
+            }
+        });
+    }
+}
+
+fn date_format_picker(ui: &mut egui::Ui, id_source: &str, selected: &mut DateFormat) {
+    egui::ComboBox::from_id_source(id_source)
+        .selected_text(selected.label())
+        .show_ui(ui, |ui| {
+            for format in DateFormat::ALL {
+                ui.selectable_value(selected, format, format.label());
+            }
+        });
+}
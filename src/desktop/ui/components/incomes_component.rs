@@ -1,5 +1,7 @@
+use crate::desktop::ui::components::category_picker::{category_picker, parse_tags};
+use crate::desktop::ui::components::household_component::{ItemOwner, owner_picker};
 use crate::desktop::ui::components::shared_state::SharedState;
-use crate::domain::{Frequency, person::Income};
+use crate::domain::{BranchChange, Category, Frequency, Income, Pension, PensionRules};
 use eframe::egui;
 
 pub struct IncomesComponent {
@@ -8,6 +10,20 @@ pub struct IncomesComponent {
     income_frequency: Frequency,
     income_start_age: String,
     income_end_age: String,
+    income_category: Category,
+    income_tags: String,
+    income_owner: ItemOwner,
+    income_earned: bool,
+    pension_name: String,
+    pension_rules: usize,
+    pension_claiming_age: u32,
+    pension_history: String,
+}
+
+impl Default for IncomesComponent {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl IncomesComponent {
@@ -18,47 +34,86 @@ impl IncomesComponent {
             income_frequency: Frequency::Yearly,
             income_start_age: String::new(),
             income_end_age: String::new(),
+            income_category: Category::uncategorized(),
+            income_tags: String::new(),
+            income_owner: ItemOwner::Main,
+            income_earned: true,
+            pension_name: "State pension".to_string(),
+            pension_rules: 0,
+            pension_claiming_age: PensionRules::builtin()
+                .first()
+                .map_or(67, |rules| rules.normal_age),
+            pension_history: String::new(),
         }
     }
 
-    pub fn add_income(&mut self, state: &mut SharedState) {
-        if let Some(ref mut simulator) = state.simulator {
-            if let (Ok(amount_val), Ok(start_age_val)) = (
-                self.income_amount.parse::<f64>(),
-                self.income_start_age.parse::<u32>(),
-            ) {
-                let end_age = if !self.income_end_age.is_empty() {
-                    self.income_end_age.parse::<u32>().ok()
-                } else {
-                    None
-                };
-
-                let income = Income::new(
-                    self.income_name.clone(),
-                    amount_val,
-                    self.income_frequency.clone(),
-                    start_age_val,
-                    end_age,
-                );
+    /// Builds the income described by the input fields, if they are valid.
+    fn build_income(&self) -> Option<Income> {
+        let amount_val = self.income_amount.parse::<f64>().ok()?;
+        let start_age_val = self.income_start_age.parse::<u32>().ok()?;
+        let end_age = if !self.income_end_age.is_empty() {
+            self.income_end_age.parse::<u32>().ok()
+        } else {
+            None
+        };
+
+        Some(
+            Income::new(
+                self.income_name.clone(),
+                amount_val,
+                self.income_frequency.clone(),
+                start_age_val,
+                end_age,
+            )
+            .with_category(self.income_category.clone())
+            .with_tags(parse_tags(&self.income_tags))
+            .with_earned(self.income_earned),
+        )
+    }
 
-                simulator.add_income(income);
+    fn clear_inputs(&mut self) {
+        self.income_name.clear();
+        self.income_amount.clear();
+        self.income_start_age.clear();
+        self.income_end_age.clear();
+        self.income_tags.clear();
+    }
 
-                self.income_name.clear();
-                self.income_amount.clear();
-                self.income_start_age.clear();
-                self.income_end_age.clear();
-            };
+    pub fn add_income(&mut self, state: &mut SharedState) {
+        if state.scenarios.active().is_some()
+            && let Some(income) = self.build_income()
+        {
+            match self.income_owner {
+                ItemOwner::Member(index) => {
+                    if let Some(simulator) = state.scenarios.active_mut() {
+                        simulator.add_member_income(index, income);
+                    }
+                }
+                _ => state.scenarios.add_income(income),
+            }
+            self.clear_inputs();
         }
     }
 
     pub fn show(&mut self, ui: &mut egui::Ui, state: &mut SharedState) {
-        if state.simulator.is_none() {
+        if state.scenarios.active().is_none() {
             ui.label("Please create a person first in the Setup tab.");
             return;
         }
 
         ui.heading("Add Incomes");
 
+        if let Some(scenario) = state.scenarios.active_scenario() {
+            owner_picker(
+                ui,
+                "income_owner",
+                &scenario.simulator,
+                scenario.branch.is_some(),
+                false,
+                &mut self.income_owner,
+            );
+        }
+
         ui.horizontal(|ui| {
             ui.label("Incomes Name:");
             ui.text_edit_singleline(&mut self.income_name);
@@ -86,26 +141,226 @@ impl IncomesComponent {
             ui.text_edit_singleline(&mut self.income_end_age);
         });
 
+        if let Some(simulator) = state.scenarios.active() {
+            ui.horizontal(|ui| {
+                ui.label("Category:");
+                category_picker(
+                    ui,
+                    "income_category",
+                    &simulator.get_person().categories,
+                    &mut self.income_category,
+                );
+            });
+        }
+
+        ui.horizontal(|ui| {
+            ui.label("Tags (comma separated):");
+            ui.text_edit_singleline(&mut self.income_tags);
+        });
+
+        ui.checkbox(
+            &mut self.income_earned,
+            "Earned income (stops at retirement)",
+        );
+
         if ui.button("Add Incomes").clicked() {
             self.add_income(state);
         }
 
-        if let Some(simulator) = &state.simulator {
+        if let Some(scenario) = state.scenarios.active_scenario() {
             ui.separator();
             ui.heading("Current Incomes:");
-            for (i, income) in simulator.get_person().incomes.iter().enumerate() {
+            let mut change = None;
+            for (i, income) in scenario.simulator.get_person().incomes.iter().enumerate() {
+                ui.horizontal(|ui| {
+                    ui.label(format!(
+                        "{}. {}: {:.2} ({:?}) - Age {} to {} [{}]{}{}",
+                        i + 1,
+                        income.name,
+                        income.amount,
+                        income.frequency,
+                        income.start_age,
+                        income
+                            .end_age
+                            .map_or("ongoing".to_string(), |age| age.to_string()),
+                        income.category,
+                        if income.tags.is_empty() {
+                            String::new()
+                        } else {
+                            format!(" #{}", income.tags.join(" #"))
+                        },
+                        if income.earned { " (earned)" } else { "" }
+                    ));
+
+                    // Branches override inherited items only from the fork age on
+                    if let Some(branch) = &scenario.branch {
+                        if ui
+                            .small_button(format!("Stop at {}", branch.fork_age))
+                            .clicked()
+                        {
+                            change = Some(BranchChange::ReplaceIncome {
+                                name: income.name.clone(),
+                                with: None,
+                            });
+                        }
+                        if ui
+                            .small_button(format!("Replace from {} with form", branch.fork_age))
+                            .on_hover_text("Uses the values entered above")
+                            .clicked()
+                            && let Some(replacement) = self.build_income()
+                        {
+                            change = Some(BranchChange::ReplaceIncome {
+                                name: income.name.clone(),
+                                with: Some(replacement),
+                            });
+                        }
+                    }
+                });
+            }
+
+            // Items of other household members, timed by their own ages
+            let household = scenario.simulator.get_household();
+            for member in &household.members {
+                for income in &member.person.incomes {
+                    ui.label(format!(
+                        "{}: {}: {:.2} ({:?}) - their age {} to {} [{}]",
+                        member.person.name,
+                        income.name,
+                        income.amount,
+                        income.frequency,
+                        income.start_age,
+                        income
+                            .end_age
+                            .map_or("ongoing".to_string(), |age| age.to_string()),
+                        income.category
+                    ));
+                }
+            }
+
+            for income in household.children.iter().flat_map(|child| child.incomes()) {
                 ui.label(format!(
-                    "{}. {}: {:.2} ({:?}) - Age {} to {}",
-                    i + 1,
+                    "Household: {}: {:.2} ({:?}) - Age {} to {} [{}]",
                     income.name,
                     income.amount,
                     income.frequency,
                     income.start_age,
                     income
                         .end_age
-                        .map_or("ongoing".to_string(), |age| age.to_string())
+                        .map_or("ongoing".to_string(), |age| age.to_string()),
+                    income.category
+                ));
+            }
+
+            if let Some(change) = change {
+                state.scenarios.push_branch_change(change);
+            }
+        }
+
+        self.show_pensions(ui, state);
+    }
+
+    /// The pension described by the input fields, or why it cannot be built.
+    fn build_pension(&self) -> Result<Pension, String> {
+        let rules = PensionRules::builtin()
+            .get(self.pension_rules)
+            .ok_or("No pension rules are available")?;
+        let pension = Pension::new(self.pension_name.clone(), rules.clone())
+            .with_claiming_age(self.pension_claiming_age)
+            .with_history(Pension::parse_history(&self.pension_history)?);
+        pension.validate()?;
+        Ok(pension)
+    }
+
+    fn show_pensions(&mut self, ui: &mut egui::Ui, state: &mut SharedState) {
+        let Some(scenario) = state.scenarios.active_scenario() else {
+            return;
+        };
+        let simulator = &scenario.simulator;
+        let person = simulator.get_person();
+
+        ui.separator();
+        ui.heading("Pensions");
+        let mut removed = None;
+        for (i, pension) in person.pensions.iter().enumerate() {
+            ui.horizontal(|ui| {
+                let income = simulator.pension_income(person, pension);
+                ui.label(format!(
+                    "{}: {:.2} a year from age {} ({}, {})",
+                    pension.name,
+                    income.amount,
+                    pension.claiming_age,
+                    pension.rules.name,
+                    pension.rules.indexation
                 ));
+                if ui.small_button("Remove").clicked() {
+                    removed = Some(i);
+                }
+            });
+        }
+
+        ui.label(
+            "The amount is worked out from the earnings history below and the earned incomes \
+             until retirement.",
+        );
+        ui.horizontal(|ui| {
+            ui.label("Name:");
+            ui.text_edit_singleline(&mut self.pension_name);
+        });
+        let tables = PensionRules::builtin();
+        ui.horizontal(|ui| {
+            ui.label("Rules:");
+            let selected = tables
+                .get(self.pension_rules)
+                .map_or("", |rules| rules.name.as_str());
+            egui::ComboBox::from_id_source("pension_rules")
+                .selected_text(selected)
+                .show_ui(ui, |ui| {
+                    for (i, rules) in tables.iter().enumerate() {
+                        if ui
+                            .selectable_value(&mut self.pension_rules, i, &rules.name)
+                            .changed()
+                        {
+                            self.pension_claiming_age = rules.normal_age;
+                        }
+                    }
+                });
+        });
+        if let Some(rules) = tables.get(self.pension_rules) {
+            ui.small(&rules.source);
+            let ages = rules.claiming_ages(rules.normal_age + 5);
+            ui.horizontal(|ui| {
+                ui.label("Claiming age:");
+                ui.add(egui::DragValue::new(&mut self.pension_claiming_age).clamp_range(ages));
+                ui.label(format!("(normal age {})", rules.normal_age));
+            });
+        }
+        ui.horizontal(|ui| {
+            ui.label("Past earnings (age:amount, e.g. 25-34:40000):");
+            ui.text_edit_singleline(&mut self.pension_history);
+        });
+
+        let pension = self.build_pension();
+        match &pension {
+            Ok(pension) => {
+                let income = simulator.pension_income(person, pension);
+                ui.label(format!(
+                    "Yearly benefit from age {}: {:.2} in today's money",
+                    pension.claiming_age, income.amount
+                ));
+            }
+            Err(error) => {
+                ui.colored_label(egui::Color32::RED, error);
             }
         }
+
+        if ui.button("Add pension").clicked()
+            && let Ok(pension) = pension
+        {
+            state.scenarios.add_pension(pension);
+            self.pension_history.clear();
+        }
+        if let Some(index) = removed {
+            state.scenarios.remove_pension(index);
+        }
     }
 }
@@ -1,6 +1,19 @@
+pub mod accounts_editor;
+pub mod allocation_editor;
 pub mod analytics_component;
+pub mod categories_component;
+pub mod category_charts;
+pub mod category_picker;
+pub mod compare_component;
 pub mod expenses_component;
+pub mod export_component;
+pub mod fire_component;
+pub mod goals_component;
+pub mod household_component;
+pub mod import_component;
 pub mod incomes_component;
+pub mod property_editor;
+pub mod scenarios_component;
 pub mod settings_component;
 pub mod setup_component;
 pub mod shared_state;
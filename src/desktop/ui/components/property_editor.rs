@@ -0,0 +1,158 @@
+use crate::domain::{LifeSimulator, Mortgage, Property, buy_vs_rent};
+use eframe::egui;
+
+fn percent_value(ui: &mut egui::Ui, label: &str, rate: &mut f64) {
+    let mut percent = *rate * 100.0;
+    ui.label(label);
+    ui.add(
+        egui::DragValue::new(&mut percent)
+            .speed(0.1)
+            .clamp_range(0.0..=100.0)
+            .suffix("%"),
+    );
+    *rate = percent / 100.0;
+}
+
+fn amount_value(ui: &mut egui::Ui, amount: &mut f64) {
+    ui.add(
+        egui::DragValue::new(amount)
+            .speed(1000.0)
+            .clamp_range(0.0..=f64::MAX),
+    );
+}
+
+/// Edits the properties of a scenario and shows their equity at `end_age`. With a `rent_instead`
+/// above zero, buying is compared with renting at that yearly rent.
+pub fn property_editor(
+    ui: &mut egui::Ui,
+    simulator: &mut LifeSimulator,
+    rent_instead: &mut f64,
+    end_age: u32,
+) {
+    let current_age = simulator.get_current_age();
+    let mut properties = simulator.get_properties().to_vec();
+    let mut removed = None;
+
+    ui.label(
+        "Values are in today's money; a property bought before today is taken at the price paid.",
+    );
+    for (i, property) in properties.iter_mut().enumerate() {
+        ui.push_id(i, |ui| {
+            ui.separator();
+            ui.horizontal(|ui| {
+                ui.text_edit_singleline(&mut property.name);
+                if ui.small_button("Remove").clicked() {
+                    removed = Some(i);
+                }
+            });
+            ui.horizontal(|ui| {
+                ui.label("Price:");
+                amount_value(ui, &mut property.purchase_price);
+                ui.label("Bought at age:");
+                ui.add(egui::DragValue::new(&mut property.purchase_age).clamp_range(0..=120));
+                percent_value(ui, "Appreciation:", &mut property.appreciation);
+            });
+            ui.horizontal(|ui| {
+                let mut mortgaged = property.mortgage.is_some();
+                ui.checkbox(&mut mortgaged, "Mortgage");
+                match (mortgaged, &mut property.mortgage) {
+                    (true, Some(mortgage)) => {
+                        percent_value(ui, "Down payment:", &mut property.down_payment);
+                        percent_value(ui, "Interest:", &mut mortgage.interest_rate);
+                        ui.label("Years:");
+                        ui.add(egui::DragValue::new(&mut mortgage.years).clamp_range(1..=40));
+                    }
+                    (true, None) => {
+                        *property = property.clone().with_mortgage(0.2, Mortgage::new(0.04, 25));
+                    }
+                    (false, _) => property.mortgage = None,
+                }
+            });
+            ui.horizontal(|ui| {
+                percent_value(ui, "Maintenance:", &mut property.maintenance);
+                percent_value(ui, "Tax:", &mut property.property_tax);
+                percent_value(ui, "Insurance:", &mut property.insurance);
+                ui.label("of the value a year");
+            });
+            ui.horizontal(|ui| {
+                let mut let_out = property.rental_income.is_some();
+                ui.checkbox(&mut let_out, "Let out for a yearly rent of");
+                match (let_out, &mut property.rental_income) {
+                    (true, Some(rent)) => amount_value(ui, rent),
+                    (true, None) => property.rental_income = Some(12000.0),
+                    (false, _) => property.rental_income = None,
+                }
+            });
+            ui.horizontal(|ui| {
+                let mut sold = property.sale_age.is_some();
+                ui.checkbox(&mut sold, "Sell at age");
+                match (sold, &mut property.sale_age) {
+                    (true, Some(age)) => {
+                        ui.add(egui::DragValue::new(age).clamp_range(0..=120));
+                        percent_value(ui, "Selling costs:", &mut property.selling_costs);
+                    }
+                    (true, None) => {
+                        *property = property.clone().with_sale(property.purchase_age + 20, 0.06);
+                    }
+                    (false, _) => property.sale_age = None,
+                }
+            });
+            if let Err(error) = property.validate() {
+                ui.colored_label(egui::Color32::RED, error);
+            }
+        });
+    }
+
+    ui.separator();
+    if ui.button("Add property").clicked() {
+        properties.push(
+            Property::new("Home", 300000.0, current_age)
+                .with_mortgage(0.2, Mortgage::new(0.04, 25))
+                .with_appreciation(0.03)
+                .with_costs(0.01, 0.005, 0.003),
+        );
+    }
+    if let Some(index) = removed {
+        properties.remove(index);
+    }
+    simulator.set_properties(properties);
+
+    if simulator.get_properties().is_empty() {
+        return;
+    }
+    ui.label(format!(
+        "Equity at age {}: {:.2} (net worth {:.2})",
+        end_age,
+        simulator.property_equity_at(end_age),
+        simulator.net_worth_at(end_age)
+    ));
+    ui.horizontal(|ui| {
+        ui.label("Compare with renting at a yearly rent of");
+        amount_value(ui, rent_instead);
+    });
+    if *rent_instead <= 0.0 {
+        return;
+    }
+    match buy_vs_rent(simulator, *rent_instead, end_age) {
+        Ok(deltas) => {
+            if let Some(last) = deltas.last() {
+                let verdict = if last.delta() >= 0.0 {
+                    "ahead of"
+                } else {
+                    "behind"
+                };
+                ui.label(format!(
+                    "At age {} buying ends {:.2} {} renting ({:.2} against {:.2})",
+                    last.age,
+                    last.delta().abs(),
+                    verdict,
+                    last.other,
+                    last.baseline
+                ));
+            }
+        }
+        Err(error) => {
+            ui.label(error);
+        }
+    }
+}
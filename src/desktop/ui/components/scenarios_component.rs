@@ -0,0 +1,217 @@
+use crate::desktop::ui::components::shared_state::SharedState;
+use crate::domain::BranchChange;
+use crate::infrastructure::persistence::{load_scenario, save_scenario};
+use eframe::egui;
+use std::path::Path;
+
+pub struct ScenariosComponent {
+    file_path: String,
+    rename_index: Option<usize>,
+    rename_to: String,
+    fork_name: String,
+    fork_age: u32,
+    status: Option<String>,
+}
+
+impl Default for ScenariosComponent {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ScenariosComponent {
+    pub fn new() -> Self {
+        Self {
+            file_path: String::from("scenario.json"),
+            rename_index: None,
+            rename_to: String::new(),
+            fork_name: String::new(),
+            fork_age: 45,
+            status: None,
+        }
+    }
+
+    /// Compact switcher for the top panel.
+    pub fn show_selector(&mut self, ui: &mut egui::Ui, state: &mut SharedState) {
+        let Some(active) = state.scenarios.active_scenario() else {
+            return;
+        };
+        let mut selected = state.scenarios.active_index().unwrap_or_default();
+
+        ui.label("Scenario:");
+        egui::ComboBox::from_id_source("scenario_selector")
+            .selected_text(active.name.clone())
+            .show_ui(ui, |ui| {
+                for (index, scenario) in state.scenarios.iter().enumerate() {
+                    ui.selectable_value(&mut selected, index, &scenario.name);
+                }
+            });
+
+        if Some(selected) != state.scenarios.active_index() {
+            state.scenarios.select(selected);
+            state.category_filter = None;
+        }
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui, state: &mut SharedState) {
+        ui.heading("Scenarios");
+
+        ui.horizontal(|ui| {
+            ui.label("File:");
+            ui.text_edit_singleline(&mut self.file_path);
+            if ui.button("Open").clicked() {
+                self.status = Some(match load_scenario(Path::new(&self.file_path)) {
+                    Ok(scenario) => {
+                        let name = scenario.name.clone();
+                        state.scenarios.add(scenario);
+                        format!("Opened \"{}\"", name)
+                    }
+                    Err(e) => e,
+                });
+            }
+            if let Some(scenario) = state.scenarios.active_scenario()
+                && ui.button("Save active").clicked()
+            {
+                self.status = Some(match save_scenario(Path::new(&self.file_path), scenario) {
+                    Ok(()) => format!("Saved \"{}\" to {}", scenario.name, self.file_path),
+                    Err(e) => e,
+                });
+            }
+        });
+
+        if let Some(status) = &self.status {
+            ui.label(status);
+        }
+
+        let mut select = None;
+        let mut duplicate = None;
+        let mut remove = None;
+
+        for (index, scenario) in state.scenarios.iter().enumerate() {
+            ui.horizontal(|ui| {
+                let is_active = state.scenarios.active_index() == Some(index);
+                if ui.radio(is_active, &scenario.name).clicked() {
+                    select = Some(index);
+                }
+                if ui.small_button("Rename").clicked() {
+                    self.rename_index = Some(index);
+                    self.rename_to = scenario.name.clone();
+                }
+                if ui.small_button("Duplicate").clicked() {
+                    duplicate = Some(index);
+                }
+                if ui.small_button("Delete").clicked() {
+                    remove = Some(index);
+                }
+            });
+        }
+
+        if let Some(index) = self.rename_index {
+            ui.horizontal(|ui| {
+                ui.label("New name:");
+                ui.text_edit_singleline(&mut self.rename_to);
+                if ui.button("Rename").clicked() {
+                    state.scenarios.rename(index, &self.rename_to);
+                    self.rename_index = None;
+                }
+                if ui.button("Cancel").clicked() {
+                    self.rename_index = None;
+                }
+            });
+        }
+
+        if let Some(index) = state.scenarios.active_index() {
+            ui.horizontal(|ui| {
+                ui.label("Fork active scenario as:");
+                ui.text_edit_singleline(&mut self.fork_name);
+                ui.label("at age:");
+                ui.add(egui::DragValue::new(&mut self.fork_age).clamp_range(0..=120));
+                if ui.button("Fork").clicked() {
+                    let name = if self.fork_name.trim().is_empty() {
+                        format!("Fork at {}", self.fork_age)
+                    } else {
+                        self.fork_name.clone()
+                    };
+                    state.scenarios.fork(index, &name, self.fork_age);
+                    self.fork_name.clear();
+                    state.category_filter = None;
+                }
+            });
+        }
+
+        self.show_branch_changes(ui, state);
+
+        if let Some(index) = select {
+            state.scenarios.select(index);
+        }
+        if let Some(index) = duplicate {
+            state.scenarios.duplicate(index);
+        }
+        if let Some(index) = remove {
+            state.scenarios.remove(index);
+            self.rename_index = None;
+        }
+        if select.is_some() || duplicate.is_some() || remove.is_some() {
+            state.category_filter = None;
+        }
+    }
+
+    /// Lists how the active branch differs from its parent, with a way to revert each change.
+    fn show_branch_changes(&mut self, ui: &mut egui::Ui, state: &mut SharedState) {
+        let Some(branch) = state
+            .scenarios
+            .active_scenario()
+            .and_then(|scenario| scenario.branch.as_ref())
+        else {
+            return;
+        };
+
+        ui.separator();
+        ui.label(format!(
+            "Branch of \"{}\" from age {}. Parent edits show up here unless overridden below.",
+            branch.parent, branch.fork_age
+        ));
+
+        let mut reverted = None;
+        for (position, change) in branch.changes.iter().enumerate() {
+            let description = match change {
+                BranchChange::AddExpense(expense) => format!("Added expense {}", expense.name),
+                BranchChange::AddIncome(income) => format!("Added income {}", income.name),
+                BranchChange::ReplaceExpense { name, with: None }
+                | BranchChange::ReplaceIncome { name, with: None } => format!("Stopped {}", name),
+                BranchChange::ReplaceExpense {
+                    name,
+                    with: Some(expense),
+                } => format!(
+                    "Replaced {} with {:.2} ({:?})",
+                    name, expense.amount, expense.frequency
+                ),
+                BranchChange::ReplaceIncome {
+                    name,
+                    with: Some(income),
+                } => format!(
+                    "Replaced {} with {:.2} ({:?})",
+                    name, income.amount, income.frequency
+                ),
+                BranchChange::AddPension(pension) => format!("Added pension {}", pension.name),
+                BranchChange::RemovePension { name } => format!("Removed pension {}", name),
+                BranchChange::Categories(_) => "Edited the categories".to_string(),
+                BranchChange::Household(_) => "Edited the household".to_string(),
+            };
+            ui.horizontal(|ui| {
+                ui.label(description);
+                if ui.small_button("Revert").clicked() {
+                    reverted = Some(position);
+                }
+            });
+        }
+
+        if branch.changes.is_empty() {
+            ui.label("No changes yet. Use the Expenses and Incomes tabs to add or override items.");
+        }
+
+        if let Some(position) = reverted {
+            state.scenarios.remove_branch_change(position);
+        }
+    }
+}
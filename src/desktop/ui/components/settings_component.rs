@@ -1,3 +1,5 @@
+use crate::desktop::ui::components::shared_state::SharedState;
+use crate::domain::{Horizon, LifeTable, Mortality, Sex, SimulationConfig, TimeStep};
 use eframe::egui;
 
 // Define scaling factors
@@ -21,6 +23,21 @@ pub struct SettingsComponent {
     current_scale: f32,
     current_theme: Theme,
     is_open: bool,
+    survival_curve: CurveEditor,
+}
+
+// Text of a custom survival curve being typed, and the curve it was last in sync with
+#[derive(Default)]
+struct CurveEditor {
+    text: String,
+    synced: Option<Mortality>,
+    error: Option<String>,
+}
+
+impl Default for SettingsComponent {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl SettingsComponent {
@@ -29,6 +46,7 @@ impl SettingsComponent {
             current_scale: 1.0,         // Default to 100% scale
             current_theme: Theme::Dark, // Default to dark theme
             is_open: false,
+            survival_curve: CurveEditor::default(),
         }
     }
 
@@ -58,7 +76,12 @@ impl SettingsComponent {
         }
     }
 
-    pub fn show_settings_button(&mut self, ui: &mut egui::Ui, ctx: &egui::Context) {
+    pub fn show_settings_button(
+        &mut self,
+        ui: &mut egui::Ui,
+        ctx: &egui::Context,
+        state: &mut SharedState,
+    ) {
         if ui.button("⚙ Settings").clicked() {
             self.is_open = !self.is_open;
         }
@@ -101,6 +124,9 @@ impl SettingsComponent {
                     }
                 });
 
+                ui.separator();
+                show_simulation_settings(ui, state, &mut self.survival_curve);
+
                 if ui.button("Close").clicked() {
                     self.is_open = false;
                 }
@@ -108,3 +134,174 @@ impl SettingsComponent {
         }
     }
 }
+
+/// Simulation settings of the active scenario, which are saved with it.
+fn show_simulation_settings(ui: &mut egui::Ui, state: &mut SharedState, curve: &mut CurveEditor) {
+    ui.label("Simulation:");
+    let Some(simulator) = state.scenarios.active() else {
+        ui.label("Create a person first to change how the scenario is projected.");
+        return;
+    };
+    let current_age = simulator.get_current_age();
+    let mut config = simulator.get_simulation().clone();
+
+    ui.horizontal(|ui| {
+        let mut custom_start = config.start_age.is_some();
+        ui.checkbox(&mut custom_start, "Start at age");
+        match (custom_start, &mut config.start_age) {
+            (true, Some(age)) => {
+                ui.add(egui::DragValue::new(age).clamp_range(current_age..=120));
+            }
+            (true, None) => config.start_age = Some(current_age),
+            (false, _) => config.start_age = None,
+        }
+    });
+
+    ui.horizontal(|ui| {
+        let mut kind = horizon_kind(&config.horizon);
+        for (index, label) in ["End age", "Life expectancy", "Mortality"]
+            .iter()
+            .enumerate()
+        {
+            ui.radio_value(&mut kind, index, *label);
+        }
+        if kind != horizon_kind(&config.horizon) {
+            config.horizon = switch_horizon(&config.horizon, kind, current_age);
+        }
+    });
+    match &mut config.horizon {
+        Horizon::EndAge(age) => {
+            ui.add(egui::DragValue::new(age).clamp_range((current_age + 1)..=120));
+        }
+        Horizon::LifeExpectancy { age, margin } => {
+            ui.horizontal(|ui| {
+                ui.add(egui::DragValue::new(age).clamp_range((current_age + 1)..=120));
+                ui.label("plus");
+                ui.add(egui::DragValue::new(margin).clamp_range(0..=30));
+                ui.label(format!("years (until age {})", *age + *margin));
+            });
+        }
+        Horizon::Mortality(mortality) => show_mortality(ui, mortality, curve),
+    }
+    if let Some(mortality) = config.mortality()
+        && let Ok(expectancy) = mortality.life_expectancy(current_age)
+    {
+        ui.label(format!(
+            "Life expectancy {:.1}; projected until age {}",
+            expectancy,
+            config.end_age()
+        ));
+    }
+
+    ui.horizontal(|ui| {
+        ui.label("Time step:");
+        for time_step in TimeStep::ALL {
+            ui.radio_value(&mut config.time_step, time_step, time_step.to_string());
+        }
+    });
+    ui.horizontal(|ui| {
+        ui.label("Table rows every");
+        ui.add(egui::DragValue::new(&mut config.granularity).clamp_range(1..=10));
+        ui.label("years");
+    });
+
+    if &config != simulator.get_simulation() {
+        apply_simulation(state, config);
+    }
+}
+
+fn apply_simulation(state: &mut SharedState, config: SimulationConfig) {
+    let Some(simulator) = state.scenarios.active_mut() else {
+        return;
+    };
+    if config.validate(simulator.get_current_age()).is_ok() {
+        simulator.set_simulation(config);
+    }
+}
+
+fn horizon_kind(horizon: &Horizon) -> usize {
+    match horizon {
+        Horizon::EndAge(_) => 0,
+        Horizon::LifeExpectancy { .. } => 1,
+        Horizon::Mortality(_) => 2,
+    }
+}
+
+// Keeps roughly the same end age when switching between kinds of horizon
+fn switch_horizon(horizon: &Horizon, kind: usize, current_age: u32) -> Horizon {
+    match (kind, horizon) {
+        (0, horizon) => Horizon::EndAge(horizon.end_age()),
+        (1, Horizon::Mortality(mortality)) => Horizon::LifeExpectancy {
+            age: mortality
+                .life_expectancy(current_age)
+                .map_or(horizon.end_age(), |expectancy| expectancy.round() as u32),
+            margin: Horizon::DEFAULT_MARGIN,
+        },
+        (1, horizon) => Horizon::LifeExpectancy {
+            age: horizon.end_age(),
+            margin: Horizon::DEFAULT_MARGIN,
+        },
+        _ => Horizon::Mortality(Mortality::default()),
+    }
+}
+
+fn show_mortality(ui: &mut egui::Ui, mortality: &mut Mortality, curve: &mut CurveEditor) {
+    let tables = LifeTable::builtin();
+    ui.horizontal(|ui| {
+        let mut custom = matches!(mortality, Mortality::Curve { .. });
+        ui.radio_value(&mut custom, false, "Life table");
+        ui.radio_value(&mut custom, true, "Custom survival curve");
+        match (custom, &mortality) {
+            (false, Mortality::Curve { .. }) => *mortality = Mortality::default(),
+            (true, Mortality::Table { .. }) => {
+                *mortality = Mortality::parse_curve("65:1, 85:0.5, 100:0.05").unwrap()
+            }
+            _ => {}
+        }
+    });
+
+    match mortality {
+        Mortality::Table { table, sex } => {
+            ui.horizontal(|ui| {
+                let selected = tables
+                    .iter()
+                    .find(|candidate| candidate.id == *table)
+                    .map_or(table.clone(), |candidate| candidate.name.clone());
+                egui::ComboBox::from_id_source("life_table")
+                    .selected_text(selected)
+                    .show_ui(ui, |ui| {
+                        for candidate in tables {
+                            ui.selectable_value(table, candidate.id.clone(), &candidate.name);
+                        }
+                    });
+                for option in Sex::ALL {
+                    ui.radio_value(sex, option, option.to_string());
+                }
+            });
+            if let Some(source) = tables.iter().find(|candidate| candidate.id == *table) {
+                ui.small(&source.source);
+            }
+        }
+        Mortality::Curve { .. } => {
+            if curve.synced.as_ref() != Some(mortality) {
+                curve.text = mortality.to_string();
+                curve.synced = Some(mortality.clone());
+                curve.error = None;
+            }
+            ui.label("Chance of being alive by age (age:chance, ...):");
+            if ui.text_edit_singleline(&mut curve.text).changed() {
+                match Mortality::parse_curve(&curve.text) {
+                    Ok(parsed) => {
+                        *mortality = parsed.clone();
+                        curve.synced = Some(parsed);
+                        curve.error = None;
+                    }
+                    Err(e) => curve.error = Some(e),
+                }
+            }
+            if let Some(error) = &curve.error {
+                ui.colored_label(egui::Color32::RED, error);
+            }
+        }
+    }
+}
@@ -1,19 +1,74 @@
 use crate::desktop::ui::components::shared_state::SharedState;
-use crate::domain::{LifeSimulator, Person};
+use crate::domain::{LifeSimulator, Person, Scenario};
+use crate::infrastructure::persistence::{ScenarioTemplate, available_templates};
 use eframe::egui;
 
 pub struct SetupComponent {
+    scenario_name: String,
     name: String,
     age: String,
     start_capital: String,
+    templates: Vec<(ScenarioTemplate, String)>,
+    selected_template: usize,
+    template_status: Option<String>,
+}
+
+impl Default for SetupComponent {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl SetupComponent {
     pub fn new() -> Self {
-        Self {
+        let mut component = Self {
+            scenario_name: String::new(),
             name: String::new(),
             age: String::new(),
             start_capital: String::new(),
+            templates: Vec::new(),
+            selected_template: 0,
+            template_status: None,
+        };
+        component.reload_templates();
+        component
+    }
+
+    // Loads the built-in and user templates together with their titles
+    fn reload_templates(&mut self) {
+        match available_templates() {
+            Ok(templates) => {
+                self.templates = templates
+                    .into_iter()
+                    .map(|template| {
+                        let title = template.title();
+                        (template, title)
+                    })
+                    .collect();
+                self.template_status = None;
+            }
+            Err(e) => self.template_status = Some(e),
+        }
+        self.selected_template = self
+            .selected_template
+            .min(self.templates.len().saturating_sub(1));
+    }
+
+    pub fn create_from_template(&mut self, state: &mut SharedState) {
+        let Some((template, _)) = self.templates.get(self.selected_template) else {
+            return;
+        };
+        match template.instantiate() {
+            Ok(mut scenario) => {
+                if !self.scenario_name.trim().is_empty() {
+                    scenario.name = self.scenario_name.clone();
+                    self.scenario_name.clear();
+                }
+                state.scenarios.add(scenario);
+                state.category_filter = None;
+                self.template_status = None;
+            }
+            Err(e) => self.template_status = Some(e),
         }
     }
 
@@ -22,13 +77,26 @@ impl SetupComponent {
             (self.age.parse::<u32>(), self.start_capital.parse::<f64>())
         {
             let person = Person::new(self.name.clone(), age_val, income_val);
-            state.simulator = Some(LifeSimulator::new(person));
+            let name = if self.scenario_name.trim().is_empty() {
+                format!("Scenario {}", state.scenarios.len() + 1)
+            } else {
+                self.scenario_name.clone()
+            };
+            state
+                .scenarios
+                .add(Scenario::new(name, LifeSimulator::new(person)));
+            self.scenario_name.clear();
         }
     }
 
     pub fn show(&mut self, ui: &mut egui::Ui, state: &mut SharedState) {
         ui.heading("Life Simulator Setup");
 
+        ui.horizontal(|ui| {
+            ui.label("Scenario Name:");
+            ui.text_edit_singleline(&mut self.scenario_name);
+        });
+
         ui.horizontal(|ui| {
             ui.label("Name:");
             ui.text_edit_singleline(&mut self.name);
@@ -48,9 +116,40 @@ impl SetupComponent {
             self.create_person(state);
         }
 
-        if let Some(simulator) = &state.simulator {
-            let person = simulator.get_person();
+        if !self.templates.is_empty() {
+            ui.horizontal(|ui| {
+                ui.label("Or start from:");
+                egui::ComboBox::from_id_source("scenario_template")
+                    .selected_text(
+                        self.templates
+                            .get(self.selected_template)
+                            .map_or("", |(_, title)| title.as_str()),
+                    )
+                    .show_ui(ui, |ui| {
+                        for (index, (_, title)) in self.templates.iter().enumerate() {
+                            ui.selectable_value(&mut self.selected_template, index, title);
+                        }
+                    });
+                if ui.button("New from template").clicked() {
+                    self.create_from_template(state);
+                }
+                if ui
+                    .small_button("Reload")
+                    .on_hover_text("Picks up template files added to the templates directory")
+                    .clicked()
+                {
+                    self.reload_templates();
+                }
+            });
+        }
+        if let Some(status) = &self.template_status {
+            ui.colored_label(egui::Color32::RED, status);
+        }
+
+        if let Some(scenario) = state.scenarios.active_scenario() {
+            let person = scenario.simulator.get_person();
             ui.separator();
+            ui.label(format!("Active Scenario: {}", scenario.name));
             ui.label(format!(
                 "Current Person: {}, Age: {}, Capital: {:.2}",
                 person.name, person.age, person.capital
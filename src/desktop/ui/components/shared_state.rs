@@ -1,4 +1,4 @@
-use crate::domain::LifeSimulator;
+use crate::domain::{Category, ScenarioSet};
 
 #[derive(Default, PartialEq)]
 pub enum AppTab {
@@ -6,21 +6,28 @@ pub enum AppTab {
     Setup,
     Expenses,
     Incomes,
+    Categories,
+    Import,
     Simulation,
     Analytics,
+    Fire,
+    Compare,
 }
 
 #[derive(Default)]
 pub struct SharedState {
-    pub simulator: Option<LifeSimulator>,
+    pub scenarios: ScenarioSet,
     pub current_tab: AppTab,
+    /// Category subtree the Analytics table and Simulation plot are restricted to.
+    pub category_filter: Option<Category>,
 }
 
 impl SharedState {
     pub fn new() -> Self {
         Self {
-            simulator: None,
+            scenarios: ScenarioSet::new(),
             current_tab: AppTab::default(),
+            category_filter: None,
         }
     }
 }
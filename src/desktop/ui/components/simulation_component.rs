@@ -1,142 +1,686 @@
+use crate::desktop::ui::components::accounts_editor::accounts_editor;
+use crate::desktop::ui::components::allocation_editor::{
+    GlidePathEditor, allocation_chart, allocation_editor,
+};
+use crate::desktop::ui::components::category_picker::category_filter;
+use crate::desktop::ui::components::property_editor::property_editor;
 use crate::desktop::ui::components::shared_state::SharedState;
+use crate::domain::{
+    AssetAllocation, BalanceAlert, CreditLine, GlidePath, ItemRef, SimulationConfig, WhatIf,
+    WhatIfOutcome, WithdrawalStrategy, balance_alert, evaluate_goals, longevity_report,
+    retirement_report,
+};
+use crate::infrastructure::export::{Chart, ChartFormat, SeriesKind};
 use eframe::egui;
-use egui_plot::{Legend, Line, Plot, PlotPoints};
+use egui_plot::{HLine, Legend, Line, MarkerShape, Plot, PlotPoints, Points, Polygon};
+use std::fs;
+use std::path::Path;
 
-pub struct SimulationComponent;
+pub struct SimulationComponent {
+    // Last age shown: the scenario's end age, or the what-if's while one is explored
+    end_age: u32,
+    what_if: Option<WhatIf>,
+    // Scenario and settings the what-if was started from, so changing either resets it
+    what_if_source: Option<(String, SimulationConfig)>,
+    outcome: Option<WhatIfOutcome>,
+    // What-if and scenario revision the outcome was projected for
+    projected: Option<(WhatIf, u64)>,
+    // Plot lines that are drawn and exported
+    visible: Vec<SeriesKind>,
+    chart_path: String,
+    chart_status: Option<String>,
+    glide_path: GlidePathEditor,
+    // Yearly rent that buying the properties is compared with; 0 skips the comparison
+    rent_instead: f64,
+}
+
+impl Default for SimulationComponent {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 impl SimulationComponent {
     pub fn new() -> Self {
-        Self
+        Self {
+            end_age: SimulationConfig::default().end_age(),
+            what_if: None,
+            what_if_source: None,
+            outcome: None,
+            projected: None,
+            visible: SeriesKind::ALL.to_vec(),
+            chart_path: "chart.png".to_string(),
+            chart_status: None,
+            glide_path: GlidePathEditor::default(),
+            rent_instead: 0.0,
+        }
     }
 
     pub fn calculate_balance(&mut self, state: &mut SharedState) -> Option<f64> {
-        if let Some(ref mut simulator) = state.simulator.as_mut() {
-            Some(simulator.calculate_balance_at_age(100))
+        let end_age = self.end_age;
+        state
+            .scenarios
+            .active_mut()
+            .map(|simulator| simulator.calculate_balance_at_age(end_age))
+    }
+
+    fn show_what_if(&mut self, ui: &mut egui::Ui, state: &mut SharedState) {
+        let Some(scenario) = state.scenarios.active_scenario() else {
+            return;
+        };
+        let simulator = &scenario.simulator;
+        let person = simulator.get_person();
+
+        let source = (scenario.name.clone(), simulator.get_simulation().clone());
+        if self.what_if_source.as_ref() != Some(&source) {
+            self.what_if = None;
+            self.what_if_source = Some(source);
+            self.projected = None;
+        }
+        let end_age = simulator.get_simulation().end_age();
+        let what_if = self
+            .what_if
+            .get_or_insert_with(|| WhatIf::from_simulator(simulator, end_age));
+
+        ui.heading("What-if");
+
+        // An asset allocation sets the return by age instead of the slider
+        let mut allocated = what_if.assumptions.allocation.is_some();
+        let mut return_percent = what_if.assumptions.annual_return * 100.0;
+        ui.add_enabled(
+            !allocated,
+            egui::Slider::new(&mut return_percent, -5.0..=15.0)
+                .text("Return rate")
+                .suffix("%"),
+        );
+        what_if.assumptions.annual_return = return_percent / 100.0;
+
+        ui.checkbox(&mut allocated, "Asset allocation with a glide path");
+        let retirement_age = what_if.assumptions.retirement_age.unwrap_or(65);
+        match (allocated, &mut what_if.assumptions.allocation) {
+            (true, Some(allocation)) => {
+                egui::CollapsingHeader::new("Asset allocation")
+                    .default_open(true)
+                    .show(ui, |ui| {
+                        allocation_editor(ui, allocation, &mut self.glide_path, retirement_age);
+                        allocation_chart(ui, allocation, person.age, what_if.end_age);
+                    });
+            }
+            (true, None) => {
+                what_if.assumptions.allocation = Some(AssetAllocation {
+                    glide_path: GlidePath::target_date(retirement_age),
+                    ..AssetAllocation::default()
+                })
+            }
+            (false, _) => what_if.assumptions.allocation = None,
+        }
+
+        let mut inflation_percent = what_if.assumptions.inflation * 100.0;
+        ui.add(
+            egui::Slider::new(&mut inflation_percent, 0.0..=10.0)
+                .text("Inflation")
+                .suffix("%"),
+        );
+        what_if.assumptions.inflation = inflation_percent / 100.0;
+
+        let mut retires = what_if.assumptions.retirement_age.is_some();
+        ui.checkbox(&mut retires, "Retire (earned incomes stop)");
+        if retires {
+            let retirement_age = what_if
+                .assumptions
+                .retirement_age
+                .get_or_insert(65.max(person.age));
+            ui.add(
+                egui::Slider::new(retirement_age, person.age..=what_if.end_age.max(person.age))
+                    .text("Retirement age"),
+            );
+            withdrawal_strategy_picker(ui, &mut what_if.assumptions.withdrawal);
         } else {
-            None
+            what_if.assumptions.retirement_age = None;
+        }
+
+        ui.add(egui::Slider::new(&mut what_if.end_age, (person.age + 1)..=120).text("End age"));
+
+        // Pick one item and try it with a different amount
+        let item_label = |item: Option<ItemRef>| match item {
+            Some(ItemRef::Expense(index)) => person
+                .expenses
+                .get(index)
+                .map_or("-".to_string(), |e| format!("Expense: {}", e.name)),
+            Some(ItemRef::Income(index)) => person
+                .incomes
+                .get(index)
+                .map_or("-".to_string(), |i| format!("Income: {}", i.name)),
+            None => "No item".to_string(),
+        };
+        let mut selected = what_if.item_amount.map(|(item, _)| item);
+        egui::ComboBox::from_id_source("what_if_item")
+            .selected_text(item_label(selected))
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut selected, None, item_label(None));
+                for index in 0..person.expenses.len() {
+                    let item = Some(ItemRef::Expense(index));
+                    ui.selectable_value(&mut selected, item, item_label(item));
+                }
+                for index in 0..person.incomes.len() {
+                    let item = Some(ItemRef::Income(index));
+                    ui.selectable_value(&mut selected, item, item_label(item));
+                }
+            });
+        let original_amount = |item: ItemRef| match item {
+            ItemRef::Expense(index) => person.expenses.get(index).map_or(0.0, |e| e.amount),
+            ItemRef::Income(index) => person.incomes.get(index).map_or(0.0, |i| i.amount),
+        };
+        what_if.item_amount = match (selected, what_if.item_amount) {
+            (Some(item), Some((current, amount))) if item == current => Some((item, amount)),
+            (Some(item), _) => Some((item, original_amount(item))),
+            (None, _) => None,
+        };
+        if let Some((item, amount)) = &mut what_if.item_amount {
+            let original = original_amount(*item);
+            let max = (original * 3.0).max(100.0);
+            ui.add(egui::Slider::new(amount, 0.0..=max).text("Amount per period"));
+        }
+
+        // Re-simulate only when the what-if or the saved scenario it is compared with changes
+        let projected = (what_if.clone(), simulator.revision());
+        if self.outcome.is_none() || self.projected.as_ref() != Some(&projected) {
+            self.outcome = Some(what_if.evaluate(simulator));
+            self.projected = Some(projected);
+        }
+        let Some(outcome) = &self.outcome else {
+            return;
+        };
+        let baseline_end = outcome.baseline.last().map_or(0.0, |(_, b)| *b);
+        let what_if_end = outcome.what_if.last().map_or(0.0, |(_, b)| *b);
+
+        ui.separator();
+        ui.label(format!(
+            "Baseline at {}: {:.2}",
+            what_if.end_age, baseline_end
+        ));
+        ui.label(format!(
+            "What-if at {}: {:.2}",
+            what_if.end_age, what_if_end
+        ));
+        ui.colored_label(
+            if outcome.final_delta() >= 0.0 {
+                egui::Color32::GREEN
+            } else {
+                egui::Color32::RED
+            },
+            format!("Delta: {:+.2}", outcome.final_delta()),
+        );
+        if let Some(retirement_age) = what_if.assumptions.retirement_age
+            && let Some(delta) = outcome.delta_at(retirement_age)
+        {
+            ui.label(format!(
+                "Delta at retirement ({}): {:+.2}",
+                retirement_age, delta
+            ));
+        }
+        if let Some((age, lowest)) = outcome.lowest_balance() {
+            ui.label(format!(
+                "Lowest what-if balance: {:.2} at age {}",
+                lowest, age
+            ));
+        }
+
+        let mut reset = false;
+        let mut save = false;
+        ui.horizontal(|ui| {
+            reset = ui.button("Reset").clicked();
+            save = ui.button("Save as baseline").clicked();
+        });
+
+        let what_if = what_if.clone();
+        self.end_age = what_if.end_age;
+
+        if save {
+            state.scenarios.save_what_if(&what_if);
+            self.what_if = None;
+        } else if reset {
+            self.what_if = None;
         }
     }
 
     pub fn show(&mut self, ui: &mut egui::Ui, state: &mut SharedState) {
         ui.heading("Simulation");
 
-        if state.simulator.is_none() {
+        if state.scenarios.active().is_none() {
             ui.label("Please create a person and add expenses first.");
             return;
         }
 
+        egui::SidePanel::right("what_if_panel")
+            .resizable(true)
+            .default_width(320.0)
+            .show_inside(ui, |ui| {
+                self.show_what_if(ui, state);
+            });
+
         // Calculate balance automatically when simulator exists
-        if ui.button("Calculate").clicked() {
-            if let Some(balance) = self.calculate_balance(state) {
-                ui.label(format!("Projected balance at age 100: {:.2}", balance));
-            }
+        if let Some(balance) = self.calculate_balance(state) {
+            ui.label(format!(
+                "Projected balance at age {}: {:.2}",
+                self.end_age, balance
+            ));
+        }
+
+        self.show_longevity(ui, state);
+
+        let alert = state
+            .scenarios
+            .active_mut()
+            .map(|simulator| balance_alert(simulator, self.end_age));
+        if let Some(alert) = &alert {
+            self.show_alert_banner(ui, alert);
+        }
+        if let Some(simulator) = state.scenarios.active_mut() {
+            let mut cash_buffer = simulator.get_cash_buffer();
+            ui.horizontal(|ui| {
+                ui.label("Minimum cash buffer:");
+                ui.add(
+                    egui::DragValue::new(&mut cash_buffer)
+                        .speed(100.0)
+                        .clamp_range(0.0..=f64::MAX),
+                );
+            });
+            simulator.set_cash_buffer(cash_buffer);
+
+            let mut credit_line = simulator.get_credit_line().cloned();
+            ui.horizontal(|ui| {
+                let mut enabled = credit_line.is_some();
+                ui.checkbox(&mut enabled, "Borrow on a credit line when short");
+                match (enabled, &mut credit_line) {
+                    (true, Some(line)) => {
+                        ui.label("Limit:");
+                        ui.add(
+                            egui::DragValue::new(&mut line.limit)
+                                .speed(100.0)
+                                .clamp_range(0.0..=f64::MAX),
+                        );
+                        let mut rate_percent = line.interest_rate * 100.0;
+                        ui.label("Interest:");
+                        ui.add(
+                            egui::DragValue::new(&mut rate_percent)
+                                .speed(0.1)
+                                .clamp_range(0.0..=100.0)
+                                .suffix("%"),
+                        );
+                        line.interest_rate = rate_percent / 100.0;
+                    }
+                    (true, None) => credit_line = Some(CreditLine::new(10000.0, 0.08)),
+                    (false, _) => credit_line = None,
+                }
+            });
+            simulator.set_credit_line(credit_line);
+
+            let accounts = simulator.get_accounts().len();
+            egui::CollapsingHeader::new(format!("Tax-advantaged accounts ({})", accounts))
+                .default_open(false)
+                .show(ui, |ui| accounts_editor(ui, simulator, self.end_age));
+
+            let properties = simulator.get_properties().len();
+            egui::CollapsingHeader::new(format!("Property ({})", properties))
+                .default_open(false)
+                .show(ui, |ui| {
+                    property_editor(ui, simulator, &mut self.rent_instead, self.end_age)
+                });
         }
 
+        let goals = state
+            .scenarios
+            .active_scenario_mut()
+            .map(|scenario| evaluate_goals(&mut scenario.simulator, &scenario.goals))
+            .unwrap_or_default();
+
         // Show balance history
-        if let Some(simulator) = &state.simulator {
-            ui.separator();
-            ui.heading("Balance History:");
-
-            let history = simulator.get_balance_history();
-            if !history.is_empty() {
-                let mut ages: Vec<u32> = history.keys().cloned().collect();
-                ages.sort();
-
-                let points: PlotPoints = ages
-                    .iter()
-                    .map(|&age| [age as f64, *history.get(&age).unwrap_or(&0.0)])
-                    .collect();
-
-                let line = Line::new(points).name("Balance over time");
-
-                // Calculate actual expenses and incomes over time (considering frequency)
-                let mut expense_points = Vec::new();
-                let mut income_points = Vec::new();
-
-                for &age in &ages {
-                    // Calculate actual yearly expenses at this age
-                    let total_yearly_expenses: f64 = simulator
-                        .get_person()
-                        .expenses
-                        .iter()
-                        .filter(|e| {
-                            e.start_age <= age && (e.end_age.is_none() || e.end_age.unwrap() >= age)
-                        })
-                        .map(|e| {
-                            // Convert to yearly amount based on frequency
-                            match e.frequency {
-                                crate::domain::Frequency::Yearly => e.amount,
-                                crate::domain::Frequency::Monthly => e.amount * 12.0,
-                                crate::domain::Frequency::Daily => e.amount * 365.0,
+        ui.separator();
+        ui.heading("Balance History:");
+        if let Some(simulator) = state.scenarios.active() {
+            ui.horizontal(|ui| {
+                category_filter(
+                    ui,
+                    "simulation_category_filter",
+                    &simulator.get_person().categories,
+                    &mut state.category_filter,
+                );
+            });
+        }
+
+        // The plot and its exports are drawn from the same chart
+        let end_age = self.end_age;
+        let Some((mut chart, first_age)) = state.scenarios.active_scenario_mut().map(|scenario| {
+            let simulator = &mut scenario.simulator;
+            let first_age = simulator
+                .get_simulation()
+                .first_age(simulator.get_current_age());
+            let chart = Chart::balance(
+                &scenario.name,
+                simulator,
+                end_age,
+                state.category_filter.as_ref(),
+            );
+            (chart, first_age)
+        }) else {
+            return;
+        };
+        // Overlay the what-if projection when it differs from the baseline
+        if let Some(outcome) = &self.outcome
+            && outcome.baseline != outcome.what_if
+        {
+            chart = chart.with_what_if(&outcome.what_if);
+        }
+        let chart = chart.starting_at(first_age);
+
+        ui.horizontal(|ui| {
+            ui.label("Show:");
+            for series in &chart.series {
+                let mut shown = self.visible.contains(&series.kind);
+                if ui.checkbox(&mut shown, series.kind.label()).changed() {
+                    if shown {
+                        self.visible.push(series.kind);
+                    } else {
+                        self.visible.retain(|kind| *kind != series.kind);
+                    }
+                }
+            }
+        });
+        let chart = chart.retain(&self.visible);
+
+        // Create a scroll area for the plot to allow proper sizing
+        let view = egui::ScrollArea::both()
+            .show(ui, |ui| {
+                ui.set_height(400.0); // Set a reasonable default height
+
+                let response = Plot::new("balance_plot")
+                    .legend(Legend::default())
+                    .view_aspect(3.0) // Wider aspect ratio
+                    .show_x(true)
+                    .show_y(true)
+                    .x_axis_formatter(|value, _range, _digits| format!("{}", value as u32))
+                    .y_axis_formatter(|value, _range, _digits| format!("{:.2}", value))
+                    .label_formatter(|name, value| {
+                        format!("{}: ({:.0}, {:.2})", name, value.x, value.y)
+                    })
+                    .allow_zoom([true, true]) // Allow zooming on both axes
+                    .allow_drag([true, true]) // Allow dragging on both axes
+                    .show(ui, |plot_ui| {
+                        for series in &chart.series {
+                            let [r, g, b] = series.color;
+                            let line = Line::new(PlotPoints::new(series.points.clone()))
+                                .name(&series.name)
+                                .color(egui::Color32::from_rgb(r, g, b));
+                            plot_ui.line(match series.kind {
+                                SeriesKind::Interest => {
+                                    line.style(egui_plot::LineStyle::dotted_dense())
+                                }
+                                _ if series.dashed => {
+                                    line.style(egui_plot::LineStyle::dashed_dense())
+                                }
+                                SeriesKind::WhatIf => line.width(2.0),
+                                _ => line,
+                            });
+                        }
+
+                        // Shade every period spent below the cash buffer
+                        if let Some(alert) = &alert {
+                            if alert.cash_buffer > 0.0 {
+                                plot_ui.hline(
+                                    HLine::new(alert.cash_buffer)
+                                        .name("Cash buffer")
+                                        .color(egui::Color32::LIGHT_RED)
+                                        .style(egui_plot::LineStyle::dashed_loose()),
+                                );
                             }
-                        })
-                        .sum();
-
-                    // Calculate actual yearly incomes at this age
-                    let total_yearly_incomes: f64 = simulator
-                        .get_person()
-                        .incomes
-                        .iter()
-                        .filter(|i| {
-                            i.start_age <= age && (i.end_age.is_none() || i.end_age.unwrap() >= age)
-                        })
-                        .map(|i| {
-                            // Convert to yearly amount based on frequency
-                            match i.frequency {
-                                crate::domain::Frequency::Yearly => i.amount,
-                                crate::domain::Frequency::Monthly => i.amount * 12.0,
-                                crate::domain::Frequency::Daily => i.amount * 365.0,
+                            for period in &alert.periods {
+                                let start = period.start_age as f64 - 0.5;
+                                let end = period
+                                    .recovered_at
+                                    .map_or(alert.end_age as f64 + 0.5, |age| age as f64 - 0.5);
+                                let bottom = period.lowest_balance.min(0.0);
+                                plot_ui.polygon(
+                                    Polygon::new(PlotPoints::new(vec![
+                                        [start, alert.cash_buffer],
+                                        [end, alert.cash_buffer],
+                                        [end, bottom],
+                                        [start, bottom],
+                                    ]))
+                                    .name("Below cash buffer")
+                                    .fill_color(egui::Color32::from_rgba_unmultiplied(
+                                        220, 40, 40, 40,
+                                    ))
+                                    .width(0.0),
+                                );
                             }
+                        }
+
+                        // Mark every goal at its target, green once the projection meets it
+                        for goal in &goals {
+                            plot_ui.points(
+                                Points::new(vec![[
+                                    goal.goal.by_age as f64,
+                                    goal.goal.target_amount,
+                                ]])
+                                .name(format!("Goal: {}", goal.goal.name))
+                                .shape(MarkerShape::Diamond)
+                                .radius(6.0)
+                                .color(if goal.is_met() {
+                                    egui::Color32::GREEN
+                                } else {
+                                    egui::Color32::RED
+                                }),
+                            );
+                        }
+                    });
+                let bounds = response.transform.bounds();
+                (
+                    [bounds.min()[0], bounds.max()[0]],
+                    [bounds.min()[1], bounds.max()[1]],
+                )
+            })
+            .inner;
+
+        self.show_chart_export(ui, chart.with_view(Some(view.0), Some(view.1)));
+
+        self.show_retirement(ui, state);
+    }
+
+    /// Saves the chart as shown, zoom included, to an SVG or PNG file.
+    fn show_chart_export(&mut self, ui: &mut egui::Ui, chart: Chart) {
+        ui.horizontal(|ui| {
+            ui.label("Export chart to:");
+            ui.text_edit_singleline(&mut self.chart_path);
+            if ui.button("Save").clicked() {
+                let path = Path::new(&self.chart_path);
+                self.chart_status = Some(
+                    ChartFormat::from_path(path)
+                        .and_then(|format| {
+                            fs::write(path, format.render(&chart, 960, 400, 2))
+                                .map_err(|e| format!("Failed to write file: {}", e))
                         })
-                        .sum();
+                        .map_or_else(|e| e, |()| format!("Saved {}", self.chart_path)),
+                );
+            }
+            if let Some(status) = &self.chart_status {
+                ui.label(status);
+            }
+        });
+    }
 
-                    expense_points.push([age as f64, total_yearly_expenses]);
-                    income_points.push([age as f64, total_yearly_incomes]);
+    /// Life expectancy, expected bequest and chance of outliving the money, when the horizon
+    /// follows a mortality table.
+    fn show_longevity(&self, ui: &mut egui::Ui, state: &mut SharedState) {
+        let Some(simulator) = state.scenarios.active_mut() else {
+            return;
+        };
+        let Some(mortality) = simulator.get_simulation().mortality().cloned() else {
+            return;
+        };
+        match longevity_report(simulator, &mortality) {
+            Ok(report) => {
+                ui.label(format!(
+                    "Life expectancy {:.1}, expected bequest {:.2}",
+                    report.life_expectancy, report.expected_bequest
+                ));
+                match report.depletion_age {
+                    Some(age) => ui.colored_label(
+                        egui::Color32::from_rgb(255, 165, 0),
+                        format!(
+                            "Money runs out at age {}: a {:.1}% chance of outliving it",
+                            age,
+                            report.ruin_probability * 100.0
+                        ),
+                    ),
+                    None => ui.label("The money lasts a lifetime"),
+                };
+            }
+            Err(e) => {
+                ui.colored_label(egui::Color32::RED, e);
+            }
+        }
+    }
+
+    /// Prominent warning when the projected balance drops below the cash buffer.
+    fn show_alert_banner(&self, ui: &mut egui::Ui, alert: &BalanceAlert) {
+        let Some(first_age) = alert.first_age() else {
+            return;
+        };
+        let threshold = if alert.cash_buffer > 0.0 {
+            format!("below the cash buffer of {:.2}", alert.cash_buffer)
+        } else {
+            "negative".to_string()
+        };
+        let duration = match alert.periods.first().and_then(|p| p.recovered_at) {
+            Some(age) => format!("until age {}", age),
+            None => format!("through age {}", alert.end_age),
+        };
+
+        egui::Frame::none()
+            .fill(egui::Color32::from_rgb(120, 20, 20))
+            .inner_margin(egui::Margin::same(8.0))
+            .rounding(4.0)
+            .show(ui, |ui| {
+                ui.colored_label(
+                    egui::Color32::WHITE,
+                    egui::RichText::new(format!(
+                        "Warning: the balance goes {} at age {} and stays there {} ({} years below in total)",
+                        threshold,
+                        first_age,
+                        duration,
+                        alert.years_below()
+                    ))
+                    .strong(),
+                );
+                for period in &alert.periods {
+                    ui.colored_label(
+                        egui::Color32::WHITE,
+                        format!(
+                            "Lowest balance {:.2} at age {}",
+                            period.lowest_balance, period.lowest_age
+                        ),
+                    );
                 }
+            });
+    }
 
-                let expense_plot_points: PlotPoints = expense_points.into();
-                let income_plot_points: PlotPoints = income_points.into();
-
-                let expense_line = Line::new(expense_plot_points)
-                    .name("Total Expenses")
-                    .color(egui::Color32::RED)
-                    .style(egui_plot::LineStyle::dashed_dense());
-
-                let income_line = Line::new(income_plot_points)
-                    .name("Total Income")
-                    .color(egui::Color32::GREEN)
-                    .style(egui_plot::LineStyle::dashed_dense());
-
-                // Create a scroll area for the plot to allow proper sizing
-                egui::ScrollArea::both().show(ui, |ui| {
-                    ui.set_height(400.0); // Set a reasonable default height
-
-                    Plot::new("balance_plot")
-                        .legend(Legend::default())
-                        .view_aspect(3.0) // Wider aspect ratio
-                        .show_x(true)
-                        .show_y(true)
-                        .x_axis_formatter(|value, _range, _digits| format!("{}", value as u32))
-                        .y_axis_formatter(|value, _range, _digits| format!("{:.2}", value))
-                        .label_formatter(|name, value| {
-                            format!("{}: ({:.0}, {:.2})", name, value.x, value.y)
-                        })
-                        .allow_zoom([true, true]) // Allow zooming on both axes
-                        .allow_drag([true, true]) // Allow dragging on both axes
-                        .show(ui, |plot_ui| {
-                            // Add the main balance line (Capital over time)
-                            plot_ui.line(line);
-
-                            // Add the expense line
-                            plot_ui.line(expense_line);
-
-                            // Add the income line
-                            plot_ui.line(income_line);
-                        });
+    /// Table of the yearly withdrawals of the active scenario once retired.
+    fn show_retirement(&mut self, ui: &mut egui::Ui, state: &mut SharedState) {
+        let end_age = self.end_age;
+        let Some(report) = state
+            .scenarios
+            .active_mut()
+            .and_then(|simulator| retirement_report(simulator, end_age))
+        else {
+            return;
+        };
+
+        ui.separator();
+        egui::CollapsingHeader::new(format!("Retirement from age {}", report.retirement_age))
+            .default_open(false)
+            .show(ui, |ui| {
+                ui.label(match report.survival_age {
+                    Some(age) => format!("The portfolio runs out at age {}.", age),
+                    None => format!("The portfolio lasts past age {}.", end_age),
                 });
-            } else {
-                ui.label("No balance history yet. Calculate some balances first.");
+                ui.label(format!(
+                    "Total withdrawn: {:.2}, years with unpaid expenses: {}, years spending more than the expenses: {}",
+                    report.total_withdrawn(),
+                    report.shortfall_years(),
+                    report.excess_years()
+                ));
+                egui::ScrollArea::vertical()
+                    .id_source("retirement_table_scroll")
+                    .max_height(240.0)
+                    .show(ui, |ui| {
+                        egui::Grid::new("retirement_table")
+                            .striped(true)
+                            .show(ui, |ui| {
+                                ui.strong("Age");
+                                ui.strong("Portfolio");
+                                ui.strong("Need");
+                                ui.strong("Withdrawal");
+                                ui.strong("Unpaid");
+                                ui.strong("Extra spent");
+                                ui.end_row();
+                                for year in &report.years {
+                                    ui.label(year.age.to_string());
+                                    ui.label(format!("{:.2}", year.balance));
+                                    ui.label(format!("{:.2}", year.need));
+                                    ui.label(format!("{:.2}", year.withdrawal));
+                                    ui.label(format!("{:.2}", year.shortfall));
+                                    ui.label(format!("{:.2}", year.excess()));
+                                    ui.end_row();
+                                }
+                            });
+                    });
+            });
+    }
+}
+
+/// Lets the user pick a withdrawal strategy and tune its parameters.
+fn withdrawal_strategy_picker(ui: &mut egui::Ui, strategy: &mut WithdrawalStrategy) {
+    egui::ComboBox::from_id_source("withdrawal_strategy")
+        .selected_text(strategy.name())
+        .show_ui(ui, |ui| {
+            for preset in WithdrawalStrategy::presets() {
+                let selected = preset.name() == strategy.name();
+                if ui.selectable_label(selected, preset.name()).clicked() && !selected {
+                    *strategy = preset;
+                }
             }
+        });
+
+    let percent_slider = |ui: &mut egui::Ui, rate: &mut f64, text: &str| {
+        let mut percent = *rate * 100.0;
+        ui.add(
+            egui::Slider::new(&mut percent, 0.0..=20.0)
+                .text(text)
+                .suffix("%"),
+        );
+        *rate = percent / 100.0;
+    };
+    match strategy {
+        WithdrawalStrategy::CoverExpenses => {}
+        WithdrawalStrategy::FixedRate { rate }
+        | WithdrawalStrategy::PercentOfPortfolio { rate } => {
+            percent_slider(ui, rate, "Withdrawal rate")
+        }
+        WithdrawalStrategy::ConstantDollar { amount } => {
+            ui.add(egui::Slider::new(amount, 0.0..=200000.0).text("Yearly withdrawal"));
+        }
+        WithdrawalStrategy::Guardrails {
+            initial_rate,
+            band,
+            adjustment,
+        } => {
+            percent_slider(ui, initial_rate, "Initial rate");
+            percent_slider(ui, band, "Guardrail band");
+            percent_slider(ui, adjustment, "Adjustment");
+        }
+        WithdrawalStrategy::Vpw { end_age } => {
+            ui.add(egui::Slider::new(end_age, 60..=120).text("Plan until age"));
         }
     }
 }
@@ -0,0 +1,184 @@
+use crate::domain::entities::allocation::GlidePath;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// How money paid into and taken out of an account is taxed.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum TaxTreatment {
+    /// Paid in before tax, so each unit saved from net pay puts `1 / (1 - relief)` in the account,
+    /// and taxed at `withdrawal_tax` when taken out (401(k), traditional IRA, pension pots).
+    Deferred { relief: f64, withdrawal_tax: f64 },
+    /// Paid in after tax; growth and withdrawals are tax free (Roth IRA, ISA).
+    TaxFree,
+}
+
+impl fmt::Display for TaxTreatment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TaxTreatment::Deferred {
+                relief,
+                withdrawal_tax,
+            } => write!(
+                f,
+                "tax deferred ({:.0}% relief, {:.0}% on withdrawal)",
+                relief * 100.0,
+                withdrawal_tax * 100.0
+            ),
+            TaxTreatment::TaxFree => write!(f, "tax free"),
+        }
+    }
+}
+
+/// Employer contributions of `rate` times what the employee pays in, counting contributions up
+/// to `up_to` of the earned income.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EmployerMatch {
+    pub rate: f64,
+    pub up_to: f64,
+}
+
+impl EmployerMatch {
+    pub fn matched(&self, contribution: f64, earned_income: f64) -> f64 {
+        self.rate * contribution.min(self.up_to * earned_income).max(0.0)
+    }
+}
+
+/// A tax-advantaged savings account. Savings are paid into the accounts in the order they are
+/// listed, and shortfalls are drawn from them before borrowing.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Account {
+    pub name: String,
+    pub treatment: TaxTreatment,
+    /// Balance today.
+    #[serde(default)]
+    pub balance: f64,
+    /// Most that can be paid in each year in today's money, tax relief included; `None` has no
+    /// limit. Employer contributions do not count towards it.
+    #[serde(default)]
+    pub annual_limit: Option<f64>,
+    #[serde(default)]
+    pub employer_match: Option<EmployerMatch>,
+    /// Age before which withdrawals pay `early_penalty` on top of any tax.
+    #[serde(default)]
+    pub penalty_age: Option<u32>,
+    #[serde(default)]
+    pub early_penalty: f64,
+    /// Allocation this account follows instead of the scenario's.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub glide_path: Option<GlidePath>,
+}
+
+impl Account {
+    pub fn new(name: &str, treatment: TaxTreatment) -> Self {
+        Account {
+            name: name.to_string(),
+            treatment,
+            balance: 0.0,
+            annual_limit: None,
+            employer_match: None,
+            penalty_age: None,
+            early_penalty: 0.0,
+            glide_path: None,
+        }
+    }
+
+    pub fn with_balance(mut self, balance: f64) -> Self {
+        self.balance = balance;
+        self
+    }
+
+    pub fn with_limit(mut self, annual_limit: f64) -> Self {
+        self.annual_limit = Some(annual_limit);
+        self
+    }
+
+    pub fn with_match(mut self, rate: f64, up_to: f64) -> Self {
+        self.employer_match = Some(EmployerMatch { rate, up_to });
+        self
+    }
+
+    pub fn with_penalty(mut self, penalty_age: u32, early_penalty: f64) -> Self {
+        self.penalty_age = Some(penalty_age);
+        self.early_penalty = early_penalty;
+        self
+    }
+
+    /// Common account types with their 2024 limits and a typical tax rate.
+    pub fn presets() -> Vec<Account> {
+        let deferred = |relief, withdrawal_tax| TaxTreatment::Deferred {
+            relief,
+            withdrawal_tax,
+        };
+        vec![
+            Account::new("401(k)", deferred(0.22, 0.12))
+                .with_limit(23000.0)
+                .with_match(0.5, 0.06)
+                .with_penalty(60, 0.1),
+            Account::new("Traditional IRA", deferred(0.22, 0.12))
+                .with_limit(7000.0)
+                .with_penalty(60, 0.1),
+            Account::new("Roth IRA", TaxTreatment::TaxFree)
+                .with_limit(7000.0)
+                .with_penalty(60, 0.1),
+            Account::new("ISA", TaxTreatment::TaxFree).with_limit(20000.0),
+            Account::new("Pension pot", deferred(0.2, 0.15))
+                .with_limit(60000.0)
+                .with_penalty(57, 0.55),
+        ]
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if let TaxTreatment::Deferred {
+            relief,
+            withdrawal_tax,
+        } = self.treatment
+            && (!(0.0..1.0).contains(&relief) || !(0.0..1.0).contains(&withdrawal_tax))
+        {
+            return Err(format!("{}: tax rates must be from 0 to 100%", self.name));
+        }
+        if self.annual_limit.is_some_and(|limit| limit < 0.0) {
+            return Err(format!(
+                "{}: the yearly limit cannot be negative",
+                self.name
+            ));
+        }
+        Ok(())
+    }
+
+    fn relief(&self) -> f64 {
+        match self.treatment {
+            TaxTreatment::Deferred { relief, .. } => relief,
+            TaxTreatment::TaxFree => 0.0,
+        }
+    }
+
+    /// Pays in as much of `savings` (net pay) as the limit allows, at `price_level` relative to
+    /// today. Returns the net pay used and the amount paid in.
+    pub fn contribute(&self, savings: f64, price_level: f64) -> (f64, f64) {
+        let relief = self.relief();
+        let limit = self
+            .annual_limit
+            .map_or(f64::INFINITY, |limit| limit * price_level);
+        let paid_in = (savings.max(0.0) / (1.0 - relief)).min(limit);
+        (paid_in * (1.0 - relief), paid_in)
+    }
+
+    pub fn is_penalized_at(&self, age: u32) -> bool {
+        self.penalty_age
+            .is_some_and(|penalty_age| age < penalty_age)
+    }
+
+    /// Share of a withdrawal at `age` left after tax and any early-withdrawal penalty.
+    pub fn net_share(&self, age: u32) -> f64 {
+        let tax = match self.treatment {
+            TaxTreatment::Deferred { withdrawal_tax, .. } => withdrawal_tax,
+            TaxTreatment::TaxFree => 0.0,
+        };
+        let penalty = if self.is_penalized_at(age) {
+            self.early_penalty
+        } else {
+            0.0
+        };
+        (1.0 - tax - penalty).max(0.0)
+    }
+}
@@ -0,0 +1,274 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AssetClass {
+    Stocks,
+    Bonds,
+    Cash,
+}
+
+impl AssetClass {
+    pub const ALL: [AssetClass; 3] = [AssetClass::Stocks, AssetClass::Bonds, AssetClass::Cash];
+}
+
+impl fmt::Display for AssetClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AssetClass::Stocks => write!(f, "Stocks"),
+            AssetClass::Bonds => write!(f, "Bonds"),
+            AssetClass::Cash => write!(f, "Cash"),
+        }
+    }
+}
+
+/// Expected yearly return of an asset class and its volatility (standard deviation of the
+/// yearly return).
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ClassReturn {
+    pub expected_return: f64,
+    pub volatility: f64,
+}
+
+/// Shares of the portfolio held in each asset class, adding up to one.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Allocation {
+    pub stocks: f64,
+    pub bonds: f64,
+    pub cash: f64,
+}
+
+impl Allocation {
+    /// An allocation from shares in any proportion, scaled to add up to one.
+    pub fn new(stocks: f64, bonds: f64, cash: f64) -> Self {
+        let total = stocks + bonds + cash;
+        if total <= 0.0 {
+            return Allocation {
+                stocks: 0.0,
+                bonds: 0.0,
+                cash: 1.0,
+            };
+        }
+        Allocation {
+            stocks: stocks / total,
+            bonds: bonds / total,
+            cash: cash / total,
+        }
+    }
+
+    pub fn share(&self, class: AssetClass) -> f64 {
+        match class {
+            AssetClass::Stocks => self.stocks,
+            AssetClass::Bonds => self.bonds,
+            AssetClass::Cash => self.cash,
+        }
+    }
+
+    // Straight line from `self` at 0 to `other` at 1
+    fn towards(&self, other: &Allocation, t: f64) -> Allocation {
+        Allocation {
+            stocks: self.stocks + (other.stocks - self.stocks) * t,
+            bonds: self.bonds + (other.bonds - self.bonds) * t,
+            cash: self.cash + (other.cash - self.cash) * t,
+        }
+    }
+}
+
+impl fmt::Display for Allocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}/{}/{}",
+            (self.stocks * 100.0).round(),
+            (self.bonds * 100.0).round(),
+            (self.cash * 100.0).round()
+        )
+    }
+}
+
+/// Allocation by age: the listed allocations hold at their ages and shift in a straight line
+/// in between; before the first age and after the last one the nearest allocation holds.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GlidePath {
+    pub points: Vec<(u32, Allocation)>,
+}
+
+impl GlidePath {
+    pub fn constant(allocation: Allocation) -> Self {
+        GlidePath {
+            points: vec![(0, allocation)],
+        }
+    }
+
+    /// A target-date path that moves from mostly stocks towards bonds until retiring at
+    /// `retirement_age`, and keeps becoming more cautious for fifteen years after.
+    pub fn target_date(retirement_age: u32) -> Self {
+        GlidePath {
+            points: vec![
+                (
+                    retirement_age.saturating_sub(30),
+                    Allocation::new(0.9, 0.1, 0.0),
+                ),
+                (retirement_age, Allocation::new(0.5, 0.45, 0.05)),
+                (retirement_age + 15, Allocation::new(0.3, 0.6, 0.1)),
+            ],
+        }
+    }
+
+    /// Reads a path written as `age:stocks/bonds/cash` in percent, e.g. `30:90/10/0, 65:50/45/5`.
+    pub fn parse(text: &str) -> Result<GlidePath, String> {
+        let mut points = Vec::new();
+        for pair in text
+            .split(',')
+            .map(str::trim)
+            .filter(|pair| !pair.is_empty())
+        {
+            let (age, shares) = pair
+                .split_once(':')
+                .ok_or_else(|| format!("Expected age:stocks/bonds/cash, got '{}'", pair))?;
+            let age = age
+                .trim()
+                .parse::<u32>()
+                .map_err(|_| format!("Invalid age '{}'", age.trim()))?;
+            let shares = shares
+                .split('/')
+                .map(|share| {
+                    share
+                        .trim()
+                        .parse::<f64>()
+                        .map_err(|_| format!("Invalid share '{}'", share.trim()))
+                })
+                .collect::<Result<Vec<f64>, String>>()?;
+            let [stocks, bonds, cash] = shares[..] else {
+                return Err(format!("Expected stocks/bonds/cash, got '{}'", pair));
+            };
+            points.push((age, Allocation::new(stocks, bonds, cash)));
+        }
+        let path = GlidePath { points };
+        path.validate()?;
+        Ok(path)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.points.is_empty() {
+            return Err("The glide path needs at least one age".to_string());
+        }
+        if self.points.windows(2).any(|pair| pair[1].0 <= pair[0].0) {
+            return Err("Glide path ages must increase".to_string());
+        }
+        let negative = |allocation: &Allocation| {
+            AssetClass::ALL
+                .iter()
+                .any(|class| allocation.share(*class) < 0.0)
+        };
+        if self
+            .points
+            .iter()
+            .any(|(_, allocation)| negative(allocation))
+        {
+            return Err("Glide path shares cannot be negative".to_string());
+        }
+        Ok(())
+    }
+
+    pub fn allocation_at(&self, age: u32) -> Allocation {
+        let Some((first_age, first)) = self.points.first() else {
+            return Allocation::new(0.0, 0.0, 1.0);
+        };
+        if age <= *first_age {
+            return *first;
+        }
+        match self.points.iter().position(|(at, _)| *at >= age) {
+            Some(next) => {
+                let (from_age, from) = self.points[next - 1];
+                let (to_age, to) = self.points[next];
+                from.towards(&to, (age - from_age) as f64 / (to_age - from_age) as f64)
+            }
+            None => self.points[self.points.len() - 1].1,
+        }
+    }
+}
+
+impl fmt::Display for GlidePath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let points: Vec<String> = self
+            .points
+            .iter()
+            .map(|(age, allocation)| format!("{}:{}", age, allocation))
+            .collect();
+        write!(f, "{}", points.join(", "))
+    }
+}
+
+/// The asset classes a portfolio is spread over and the glide path it follows. The portfolio is
+/// rebalanced to the path every year, so each year earns the blend of the class returns.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AssetAllocation {
+    pub stocks: ClassReturn,
+    pub bonds: ClassReturn,
+    pub cash: ClassReturn,
+    pub glide_path: GlidePath,
+}
+
+impl Default for AssetAllocation {
+    fn default() -> Self {
+        AssetAllocation {
+            stocks: ClassReturn {
+                expected_return: 0.07,
+                volatility: 0.18,
+            },
+            bonds: ClassReturn {
+                expected_return: 0.03,
+                volatility: 0.06,
+            },
+            cash: ClassReturn {
+                expected_return: 0.015,
+                volatility: 0.01,
+            },
+            glide_path: GlidePath::constant(Allocation::new(0.6, 0.4, 0.0)),
+        }
+    }
+}
+
+impl AssetAllocation {
+    pub fn class(&self, class: AssetClass) -> &ClassReturn {
+        match class {
+            AssetClass::Stocks => &self.stocks,
+            AssetClass::Bonds => &self.bonds,
+            AssetClass::Cash => &self.cash,
+        }
+    }
+
+    pub fn class_mut(&mut self, class: AssetClass) -> &mut ClassReturn {
+        match class {
+            AssetClass::Stocks => &mut self.stocks,
+            AssetClass::Bonds => &mut self.bonds,
+            AssetClass::Cash => &mut self.cash,
+        }
+    }
+
+    /// Expected yearly return of `allocation`.
+    pub fn blended_return(&self, allocation: &Allocation) -> f64 {
+        AssetClass::ALL
+            .iter()
+            .map(|class| allocation.share(*class) * self.class(*class).expected_return)
+            .sum()
+    }
+
+    /// Volatility of `allocation`, taking the asset classes as uncorrelated.
+    pub fn blended_volatility(&self, allocation: &Allocation) -> f64 {
+        AssetClass::ALL
+            .iter()
+            .map(|class| (allocation.share(*class) * self.class(*class).volatility).powi(2))
+            .sum::<f64>()
+            .sqrt()
+    }
+
+    pub fn return_at(&self, age: u32) -> f64 {
+        self.blended_return(&self.glide_path.allocation_at(age))
+    }
+
+    pub fn volatility_at(&self, age: u32) -> f64 {
+        self.blended_volatility(&self.glide_path.allocation_at(age))
+    }
+}
@@ -0,0 +1,168 @@
+use crate::domain::entities::allocation::{AssetAllocation, GlidePath};
+use serde::{Deserialize, Serialize};
+
+/// Economic assumptions applied on top of the person's items. The defaults reproduce a plain
+/// cash projection: no growth, no inflation and no retirement.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct Assumptions {
+    /// Yearly return earned on a positive balance, e.g. 0.05 for 5%.
+    #[serde(default)]
+    pub annual_return: f64,
+    /// Asset classes and glide path that set the return instead of `annual_return`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allocation: Option<AssetAllocation>,
+    /// Yearly growth of every item amount, which are entered in today's money.
+    #[serde(default)]
+    pub inflation: f64,
+    /// Age from which earned incomes stop.
+    #[serde(default)]
+    pub retirement_age: Option<u32>,
+    /// How living expenses are drawn from the portfolio once retired.
+    #[serde(default)]
+    pub withdrawal: WithdrawalStrategy,
+}
+
+/// Rule that decides how much is taken out of the portfolio each year of retirement.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub enum WithdrawalStrategy {
+    /// Withdraw exactly what the expenses need, the same as before retiring.
+    #[default]
+    CoverExpenses,
+    /// `rate` of the portfolio at retirement, then raised with inflation (the 4% rule).
+    FixedRate { rate: f64 },
+    /// The same amount every year, regardless of inflation or returns.
+    ConstantDollar { amount: f64 },
+    /// `rate` of whatever the portfolio holds at the start of each year.
+    PercentOfPortfolio { rate: f64 },
+    /// Guyton-Klinger: starts at `initial_rate` and is raised with inflation, but cut or raised
+    /// by `adjustment` whenever the current rate leaves the `band` around the initial one.
+    Guardrails {
+        initial_rate: f64,
+        band: f64,
+        adjustment: f64,
+    },
+    /// Variable percentage withdrawal: spreads the portfolio over the years left until `end_age`
+    /// as an annuity at the expected return.
+    Vpw { end_age: u32 },
+}
+
+impl WithdrawalStrategy {
+    pub const NAMES: [&'static str; 6] = [
+        "Cover expenses",
+        "Fixed 4% rule",
+        "Constant dollar",
+        "Percent of portfolio",
+        "Guardrails (Guyton-Klinger)",
+        "VPW",
+    ];
+
+    /// Each strategy with its usual parameters, in the order of `NAMES`.
+    pub fn presets() -> [WithdrawalStrategy; 6] {
+        [
+            WithdrawalStrategy::CoverExpenses,
+            WithdrawalStrategy::FixedRate { rate: 0.04 },
+            WithdrawalStrategy::ConstantDollar { amount: 40000.0 },
+            WithdrawalStrategy::PercentOfPortfolio { rate: 0.04 },
+            WithdrawalStrategy::Guardrails {
+                initial_rate: 0.05,
+                band: 0.2,
+                adjustment: 0.1,
+            },
+            WithdrawalStrategy::Vpw { end_age: 100 },
+        ]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            WithdrawalStrategy::CoverExpenses => Self::NAMES[0],
+            WithdrawalStrategy::FixedRate { .. } => Self::NAMES[1],
+            WithdrawalStrategy::ConstantDollar { .. } => Self::NAMES[2],
+            WithdrawalStrategy::PercentOfPortfolio { .. } => Self::NAMES[3],
+            WithdrawalStrategy::Guardrails { .. } => Self::NAMES[4],
+            WithdrawalStrategy::Vpw { .. } => Self::NAMES[5],
+        }
+    }
+
+    /// Amount to withdraw at `age` given the portfolio `balance` at the start of the year,
+    /// what the year's expenses `need` after other incomes, and last year's withdrawal
+    /// (`None` in the first year of retirement).
+    pub fn withdrawal(
+        &self,
+        age: u32,
+        balance: f64,
+        need: f64,
+        previous: Option<f64>,
+        assumptions: &Assumptions,
+    ) -> f64 {
+        let withdrawal = match self {
+            WithdrawalStrategy::CoverExpenses => return need.max(0.0),
+            WithdrawalStrategy::FixedRate { rate } => match previous {
+                Some(previous) => previous * (1.0 + assumptions.inflation),
+                None => balance * rate,
+            },
+            WithdrawalStrategy::ConstantDollar { amount } => *amount,
+            WithdrawalStrategy::PercentOfPortfolio { rate } => balance * rate,
+            WithdrawalStrategy::Guardrails {
+                initial_rate,
+                band,
+                adjustment,
+            } => match previous {
+                None => balance * initial_rate,
+                Some(previous) => {
+                    let withdrawal = previous * (1.0 + assumptions.inflation);
+                    let current_rate = withdrawal / balance.max(f64::EPSILON);
+                    if current_rate > initial_rate * (1.0 + band) {
+                        withdrawal * (1.0 - adjustment)
+                    } else if current_rate < initial_rate * (1.0 - band) {
+                        withdrawal * (1.0 + adjustment)
+                    } else {
+                        withdrawal
+                    }
+                }
+            },
+            WithdrawalStrategy::Vpw { end_age } => {
+                let years = end_age.saturating_sub(age).max(1) as i32;
+                let r = assumptions.return_at(age);
+                let rate = if r == 0.0 {
+                    1.0 / years as f64
+                } else {
+                    r / (1.0 - (1.0 + r).powi(-years))
+                };
+                balance * rate
+            }
+        };
+        // Only what the portfolio still holds can be taken out
+        withdrawal.clamp(0.0, balance.max(0.0))
+    }
+}
+
+impl Assumptions {
+    /// Price level `years` after the start of the simulation, relative to today.
+    pub fn inflation_factor(&self, years: u32) -> f64 {
+        (1.0 + self.inflation).powi(years as i32)
+    }
+
+    /// Expected return of the portfolio in the year starting at `age`.
+    pub fn return_at(&self, age: u32) -> f64 {
+        self.allocation
+            .as_ref()
+            .map_or(self.annual_return, |allocation| allocation.return_at(age))
+    }
+
+    /// Expected return at `age` of an account with its own glide path, blended from the asset
+    /// classes of the allocation or the default ones.
+    pub fn account_return_at(&self, glide_path: Option<&GlidePath>, age: u32) -> f64 {
+        match glide_path {
+            Some(path) => {
+                let classes = self.allocation.clone().unwrap_or_default();
+                classes.blended_return(&path.allocation_at(age))
+            }
+            None => self.return_at(age),
+        }
+    }
+
+    pub fn is_retired_at(&self, age: u32) -> bool {
+        self.retirement_age
+            .is_some_and(|retirement_age| age >= retirement_age)
+    }
+}
@@ -0,0 +1,251 @@
+use crate::domain::entities::{
+    category::CategoryTree,
+    household::Household,
+    pension::Pension,
+    person::{Expense, Income, Person},
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+/// One difference between a branch and its parent. Changes only take effect from the fork age:
+/// before it the branch always follows the parent.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum BranchChange {
+    AddExpense(Expense),
+    AddIncome(Income),
+    /// Replaces the parent's expenses with this name from the fork age; `None` stops them.
+    ReplaceExpense {
+        name: String,
+        with: Option<Expense>,
+    },
+    /// Replaces the parent's incomes with this name from the fork age; `None` stops them.
+    ReplaceIncome {
+        name: String,
+        with: Option<Income>,
+    },
+    AddPension(Pension),
+    /// Removes the pensions with this name, the parent's and any the branch added before.
+    RemovePension {
+        name: String,
+    },
+    /// The branch keeps this category tree instead of following its parent's.
+    Categories(CategoryTree),
+    /// The branch keeps this household instead of sharing its parent's.
+    Household(Household),
+}
+
+/// Links a scenario to the scenario it was forked from.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Branch {
+    pub parent: String,
+    pub fork_age: u32,
+    pub changes: Vec<BranchChange>,
+}
+
+impl Branch {
+    pub fn new(parent: String, fork_age: u32) -> Self {
+        Branch {
+            parent,
+            fork_age,
+            changes: Vec::new(),
+        }
+    }
+
+    /// Records a change, dropping any earlier replacement of the same item and what the branch
+    /// added under its name, which the replacement overrides anyway.
+    pub fn push(&mut self, change: BranchChange) {
+        match &change {
+            BranchChange::ReplaceExpense { name, .. } => self.changes.retain(|c| match c {
+                BranchChange::ReplaceExpense { name: n, .. } => n != name,
+                BranchChange::AddExpense(e) => &e.name != name,
+                _ => true,
+            }),
+            BranchChange::ReplaceIncome { name, .. } => self.changes.retain(|c| match c {
+                BranchChange::ReplaceIncome { name: n, .. } => n != name,
+                BranchChange::AddIncome(i) => &i.name != name,
+                _ => true,
+            }),
+            BranchChange::Categories(_) => self
+                .changes
+                .retain(|c| !matches!(c, BranchChange::Categories(_))),
+            BranchChange::Household(_) => self
+                .changes
+                .retain(|c| !matches!(c, BranchChange::Household(_))),
+            _ => {}
+        }
+        self.changes.push(change);
+    }
+
+    pub fn overrides_expense(&self, name: &str) -> bool {
+        self.changes
+            .iter()
+            .any(|c| matches!(c, BranchChange::ReplaceExpense { name: n, .. } if n == name))
+    }
+
+    pub fn overrides_income(&self, name: &str) -> bool {
+        self.changes
+            .iter()
+            .any(|c| matches!(c, BranchChange::ReplaceIncome { name: n, .. } if n == name))
+    }
+
+    /// The household the branch keeps for itself, if it no longer shares its parent's.
+    pub fn own_household(&self) -> Option<&Household> {
+        self.changes.iter().rev().find_map(|c| match c {
+            BranchChange::Household(household) => Some(household),
+            _ => None,
+        })
+    }
+
+    fn own_categories(&self) -> Option<&CategoryTree> {
+        self.changes.iter().rev().find_map(|c| match c {
+            BranchChange::Categories(categories) => Some(categories),
+            _ => None,
+        })
+    }
+
+    fn adds_expense(&self, name: &str) -> bool {
+        self.changes
+            .iter()
+            .any(|c| matches!(c, BranchChange::AddExpense(e) if e.name == name))
+    }
+
+    fn adds_income(&self, name: &str) -> bool {
+        self.changes
+            .iter()
+            .any(|c| matches!(c, BranchChange::AddIncome(i) if i.name == name))
+    }
+
+    /// Records the edits made directly to the branch's person, which went from `before` (as the
+    /// branch was last built) to `after`, so that rebuilding from `parent` keeps them. Items are
+    /// compared by name: a new name is added, a known one is replaced and a removed one stopped.
+    /// When several items share a known name, they are all stopped and each is added back.
+    pub fn record_edits(&mut self, parent: &Person, before: &Person, after: &Person) {
+        for (name, mut items) in changed_items(&before.expenses, &after.expenses, |e| &e.name) {
+            let known = parent.expenses.iter().any(|e| e.name == name)
+                || self.adds_expense(&name)
+                || self.overrides_expense(&name);
+            if known || items.is_empty() {
+                let with = if items.len() == 1 { items.pop() } else { None };
+                self.push(BranchChange::ReplaceExpense { name, with });
+            }
+            for expense in items {
+                self.push(BranchChange::AddExpense(expense));
+            }
+        }
+
+        for (name, mut items) in changed_items(&before.incomes, &after.incomes, |i| &i.name) {
+            let known = parent.incomes.iter().any(|i| i.name == name)
+                || self.adds_income(&name)
+                || self.overrides_income(&name);
+            if known || items.is_empty() {
+                let with = if items.len() == 1 { items.pop() } else { None };
+                self.push(BranchChange::ReplaceIncome { name, with });
+            }
+            for income in items {
+                self.push(BranchChange::AddIncome(income));
+            }
+        }
+
+        // Categories that only came along with new items are not an edit of the tree
+        let mut expected = before.categories.clone();
+        let items = after.expenses.iter().map(|e| e.category.clone());
+        for category in items.chain(after.incomes.iter().map(|i| i.category.clone())) {
+            expected.add(category);
+        }
+        if after.categories != expected {
+            self.push(BranchChange::Categories(after.categories.clone()));
+        }
+    }
+
+    /// Builds the branch's person from the current state of its parent.
+    pub fn apply(&self, parent: &Person) -> Person {
+        let mut person = Person::new(parent.name.clone(), parent.age, parent.capital);
+        person.categories = self.own_categories().unwrap_or(&parent.categories).clone();
+        person.pensions = parent.pensions.clone();
+
+        for expense in &parent.expenses {
+            if self.overrides_expense(&expense.name) {
+                if let Some(until_fork) = self.until_fork(expense.start_age, expense.end_age) {
+                    let mut expense = expense.clone();
+                    expense.end_age = Some(until_fork);
+                    person.add_expense(expense);
+                }
+            } else {
+                person.add_expense(expense.clone());
+            }
+        }
+
+        for income in &parent.incomes {
+            if self.overrides_income(&income.name) {
+                if let Some(until_fork) = self.until_fork(income.start_age, income.end_age) {
+                    let mut income = income.clone();
+                    income.end_age = Some(until_fork);
+                    person.add_income(income);
+                }
+            } else {
+                person.add_income(income.clone());
+            }
+        }
+
+        // A replacement also replaces what the branch itself added under that name
+        let mut expenses: Vec<Expense> = Vec::new();
+        let mut incomes: Vec<Income> = Vec::new();
+        for change in &self.changes {
+            match change {
+                BranchChange::AddExpense(expense) => expenses.push(expense.clone()),
+                BranchChange::ReplaceExpense { name, with } => {
+                    expenses.retain(|e| &e.name != name);
+                    expenses.extend(with.clone());
+                }
+                BranchChange::AddIncome(income) => incomes.push(income.clone()),
+                BranchChange::ReplaceIncome { name, with } => {
+                    incomes.retain(|i| &i.name != name);
+                    incomes.extend(with.clone());
+                }
+                BranchChange::AddPension(pension) => person.add_pension(pension.clone()),
+                BranchChange::RemovePension { name } => {
+                    person.pensions.retain(|p| &p.name != name);
+                }
+                BranchChange::Categories(_) | BranchChange::Household(_) => {}
+            }
+        }
+        for mut expense in expenses {
+            expense.start_age = expense.start_age.max(self.fork_age);
+            person.add_expense(expense);
+        }
+        for mut income in incomes {
+            income.start_age = income.start_age.max(self.fork_age);
+            person.add_income(income);
+        }
+
+        person
+    }
+
+    // End age for the part of a replaced item that still runs before the fork, if any
+    fn until_fork(&self, start_age: u32, end_age: Option<u32>) -> Option<u32> {
+        let end_age = end_age.map_or(self.fork_age, |end| end.min(self.fork_age));
+        (start_age < end_age).then_some(end_age)
+    }
+}
+
+// Names whose items differ between `before` and `after`, with the items they have in `after`
+fn changed_items<T: Clone + PartialEq>(
+    before: &[T],
+    after: &[T],
+    name: impl Fn(&T) -> &String,
+) -> Vec<(String, Vec<T>)> {
+    let names: BTreeSet<&String> = before.iter().chain(after).map(&name).collect();
+    names
+        .into_iter()
+        .filter_map(|n| {
+            let was: Vec<&T> = before.iter().filter(|item| name(item) == n).collect();
+            let is: Vec<T> = after
+                .iter()
+                .filter(|item| name(item) == n)
+                .cloned()
+                .collect();
+            (was.len() != is.len() || was.iter().zip(&is).any(|(a, b)| *a != b))
+                .then(|| (n.clone(), is))
+        })
+        .collect()
+}
@@ -0,0 +1,326 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// A node in the category hierarchy, addressed by its full path from the root
+/// (e.g. `Housing > Rent`). An empty path means "Uncategorized".
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Default, Serialize, Deserialize)]
+#[serde(into = "String", from = "String")]
+pub struct Category {
+    segments: Vec<String>,
+}
+
+impl Category {
+    pub const SEPARATOR: &'static str = " > ";
+
+    pub fn new(segments: Vec<String>) -> Self {
+        Category {
+            segments: segments
+                .into_iter()
+                .map(|segment| segment.trim().to_string())
+                .filter(|segment| !segment.is_empty())
+                .collect(),
+        }
+    }
+
+    /// Parses a path such as `"Housing > Rent"` (or `"Housing>Rent"`).
+    pub fn parse(path: &str) -> Self {
+        Category::new(path.split('>').map(|segment| segment.to_string()).collect())
+    }
+
+    pub fn uncategorized() -> Self {
+        Category::default()
+    }
+
+    pub fn is_uncategorized(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    pub fn segments(&self) -> &[String] {
+        &self.segments
+    }
+
+    pub fn depth(&self) -> usize {
+        self.segments.len()
+    }
+
+    /// The last segment of the path, e.g. `Rent` for `Housing > Rent`.
+    pub fn name(&self) -> &str {
+        self.segments
+            .last()
+            .map_or("Uncategorized", |name| name.as_str())
+    }
+
+    pub fn parent(&self) -> Option<Category> {
+        if self.segments.len() > 1 {
+            Some(Category {
+                segments: self.segments[..self.segments.len() - 1].to_vec(),
+            })
+        } else {
+            None
+        }
+    }
+
+    pub fn child(&self, name: &str) -> Category {
+        let mut segments = self.segments.clone();
+        segments.push(name.to_string());
+        Category::new(segments)
+    }
+
+    /// Truncates the path to at most `depth` segments (`Housing > Rent` at depth 1 is `Housing`).
+    pub fn truncated(&self, depth: usize) -> Category {
+        Category {
+            segments: self.segments.iter().take(depth).cloned().collect(),
+        }
+    }
+
+    /// True if this category is `ancestor` itself or one of its descendants.
+    pub fn is_within(&self, ancestor: &Category) -> bool {
+        if ancestor.is_uncategorized() {
+            return self.is_uncategorized();
+        }
+        self.segments.starts_with(&ancestor.segments)
+    }
+
+    /// This category and all of its ancestors, starting from the root.
+    pub fn lineage(&self) -> Vec<Category> {
+        (1..=self.segments.len())
+            .map(|depth| self.truncated(depth))
+            .collect()
+    }
+}
+
+impl fmt::Display for Category {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_uncategorized() {
+            write!(f, "Uncategorized")
+        } else {
+            write!(f, "{}", self.segments.join(Self::SEPARATOR))
+        }
+    }
+}
+
+impl From<String> for Category {
+    fn from(path: String) -> Self {
+        Category::parse(&path)
+    }
+}
+
+impl From<Category> for String {
+    fn from(category: Category) -> Self {
+        category.segments.join(Category::SEPARATOR)
+    }
+}
+
+/// The user-editable set of categories. Every ancestor of a stored category is stored as well,
+/// so the tree can be walked from its roots.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct CategoryTree {
+    categories: Vec<Category>,
+}
+
+impl CategoryTree {
+    pub fn new() -> Self {
+        CategoryTree {
+            categories: Vec::new(),
+        }
+    }
+
+    pub fn with_defaults() -> Self {
+        let mut tree = CategoryTree::new();
+        for path in [
+            "Housing > Rent",
+            "Housing > Mortgage",
+            "Housing > Utilities",
+            "Transport > Fuel",
+            "Transport > Public Transport",
+            "Food > Groceries",
+            "Food > Dining Out",
+            "Health",
+            "Leisure",
+            "Work > Salary",
+            "Work > Bonus",
+            "Investments",
+        ] {
+            tree.add(Category::parse(path));
+        }
+        tree
+    }
+
+    /// Adds a category together with any missing ancestors.
+    pub fn add(&mut self, category: Category) {
+        for node in category.lineage() {
+            if !self.categories.contains(&node) {
+                self.categories.push(node);
+            }
+        }
+        self.categories.sort();
+    }
+
+    /// Renames the last segment of `category`, moving its whole subtree along with it.
+    /// Returns the new path of the renamed category.
+    pub fn rename(&mut self, category: &Category, new_name: &str) -> Category {
+        let renamed = match category.parent() {
+            Some(parent) => parent.child(new_name),
+            None => Category::parse(new_name),
+        };
+        for node in self.categories.iter_mut() {
+            if let Some(moved) = Self::reparent(node, category, &renamed) {
+                *node = moved;
+            }
+        }
+        self.categories.sort();
+        self.categories.dedup();
+        renamed
+    }
+
+    /// Removes a category and its whole subtree.
+    pub fn remove(&mut self, category: &Category) {
+        self.categories.retain(|node| !node.is_within(category));
+    }
+
+    pub fn contains(&self, category: &Category) -> bool {
+        self.categories.contains(category)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Category> {
+        self.categories.iter()
+    }
+
+    pub fn children(&self, parent: &Category) -> Vec<&Category> {
+        self.categories
+            .iter()
+            .filter(|node| node.parent().as_ref() == Some(parent))
+            .collect()
+    }
+
+    pub fn roots(&self) -> Vec<&Category> {
+        self.categories
+            .iter()
+            .filter(|node| node.depth() == 1)
+            .collect()
+    }
+
+    /// Maps `node` from under `from` to under `to`, or `None` if it is outside `from`.
+    pub(crate) fn reparent(node: &Category, from: &Category, to: &Category) -> Option<Category> {
+        if from.is_uncategorized() || !node.is_within(from) {
+            return None;
+        }
+        let mut segments = to.segments.clone();
+        segments.extend_from_slice(&node.segments[from.depth()..]);
+        Some(Category::new(segments))
+    }
+}
+
+/// Yearly (or multi-year) amounts attributed to a single category.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct CategoryTotals {
+    pub expenses: f64,
+    pub incomes: f64,
+}
+
+impl CategoryTotals {
+    pub fn net(&self) -> f64 {
+        self.incomes - self.expenses
+    }
+
+    pub fn add(&mut self, other: &CategoryTotals) {
+        self.expenses += other.expenses;
+        self.incomes += other.incomes;
+    }
+}
+
+/// Totals keyed by the exact category of each item.
+#[derive(Debug, Clone, Default)]
+pub struct CategoryBreakdown {
+    totals: BTreeMap<Category, CategoryTotals>,
+}
+
+impl CategoryBreakdown {
+    pub fn new() -> Self {
+        CategoryBreakdown {
+            totals: BTreeMap::new(),
+        }
+    }
+
+    pub fn add_expense(&mut self, category: &Category, amount: f64) {
+        self.totals.entry(category.clone()).or_default().expenses += amount;
+    }
+
+    pub fn add_income(&mut self, category: &Category, amount: f64) {
+        self.totals.entry(category.clone()).or_default().incomes += amount;
+    }
+
+    pub fn merge(&mut self, other: &CategoryBreakdown) {
+        for (category, totals) in &other.totals {
+            self.totals.entry(category.clone()).or_default().add(totals);
+        }
+    }
+
+    /// Sum of every category that is `category` or one of its descendants.
+    pub fn within(&self, category: &Category) -> CategoryTotals {
+        let mut sum = CategoryTotals::default();
+        for (_, totals) in self.totals.iter().filter(|(c, _)| c.is_within(category)) {
+            sum.add(totals);
+        }
+        sum
+    }
+
+    pub fn total(&self) -> CategoryTotals {
+        let mut sum = CategoryTotals::default();
+        for totals in self.totals.values() {
+            sum.add(totals);
+        }
+        sum
+    }
+
+    /// Collapses every category to at most `depth` levels, e.g. depth 1 gives totals per root.
+    pub fn rolled_up(&self, depth: usize) -> CategoryBreakdown {
+        let mut rolled = CategoryBreakdown::new();
+        for (category, totals) in &self.totals {
+            rolled
+                .totals
+                .entry(category.truncated(depth))
+                .or_default()
+                .add(totals);
+        }
+        rolled
+    }
+
+    /// Share of all expenses that falls within `category`, between 0 and 1.
+    pub fn expense_share(&self, category: &Category) -> f64 {
+        let total = self.total().expenses;
+        if total == 0.0 {
+            0.0
+        } else {
+            self.within(category).expenses / total
+        }
+    }
+
+    /// Totals of the direct children of `parent` (or of the roots when `parent` is `None`),
+    /// with deeper categories rolled up into the child they belong to. Items filed directly
+    /// under `parent` are reported under `parent` itself.
+    pub fn composition(&self, parent: Option<&Category>) -> Vec<(Category, CategoryTotals)> {
+        let depth = parent.map_or(1, |p| p.depth() + 1);
+        let mut children: BTreeMap<Category, CategoryTotals> = BTreeMap::new();
+        for (category, totals) in &self.totals {
+            if parent.is_some_and(|p| !category.is_within(p)) {
+                continue;
+            }
+            children
+                .entry(category.truncated(depth))
+                .or_default()
+                .add(totals);
+        }
+        children.into_iter().collect()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&Category, &CategoryTotals)> {
+        self.totals.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.totals.is_empty()
+    }
+}
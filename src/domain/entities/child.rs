@@ -0,0 +1,145 @@
+use crate::domain::entities::category::Category;
+use crate::domain::entities::person::{Expense, Frequency, Income};
+use serde::{Deserialize, Serialize};
+
+/// A cost (or benefit) that applies while the child is between two ages.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChildStage {
+    pub name: String,
+    /// Child's age at which the stage starts.
+    pub from_age: u32,
+    /// Child's age at which the stage ends (exclusive).
+    pub to_age: u32,
+    pub monthly_amount: f64,
+}
+
+impl ChildStage {
+    pub fn new(name: &str, from_age: u32, to_age: u32, monthly_amount: f64) -> Self {
+        ChildStage {
+            name: name.to_string(),
+            from_age,
+            to_age,
+            monthly_amount,
+        }
+    }
+}
+
+/// The costs and benefits of raising a child, by the child's age.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChildTemplate {
+    pub stages: Vec<ChildStage>,
+    /// Benefits paid for the child, included only when the child has benefits enabled.
+    pub benefits: Vec<ChildStage>,
+    /// Child's age at which all support (costs and benefits) stops.
+    pub support_ends_at: u32,
+}
+
+impl Default for ChildTemplate {
+    fn default() -> Self {
+        ChildTemplate {
+            stages: vec![
+                ChildStage::new("Childcare", 0, 3, 800.0),
+                ChildStage::new("Preschool", 3, 6, 300.0),
+                ChildStage::new("School", 6, 18, 200.0),
+                ChildStage::new("University", 18, 22, 900.0),
+                ChildStage::new("Living costs", 0, 22, 400.0),
+            ],
+            benefits: vec![ChildStage::new("Child benefit", 0, 18, 250.0)],
+            support_ends_at: 22,
+        }
+    }
+}
+
+/// A child whose expenses (and optional benefits) are generated from a template.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Child {
+    pub name: String,
+    /// Main person's age when the child is born; may lie in the past or the future.
+    pub born_at: u32,
+    pub template: ChildTemplate,
+    #[serde(default)]
+    pub benefits: bool,
+}
+
+impl Child {
+    pub fn new(name: String, born_at: u32, template: ChildTemplate) -> Self {
+        Child {
+            name,
+            born_at,
+            template,
+            benefits: false,
+        }
+    }
+
+    /// Places a child born in `birth_year`, given the main person's age in `current_year`.
+    pub fn born_in_year(
+        name: String,
+        birth_year: i32,
+        current_year: i32,
+        current_age: u32,
+        template: ChildTemplate,
+    ) -> Self {
+        let born_at = (current_age as i32 + birth_year - current_year).max(0) as u32;
+        Child::new(name, born_at, template)
+    }
+
+    pub fn with_benefits(mut self, benefits: bool) -> Self {
+        self.benefits = benefits;
+        self
+    }
+
+    pub fn category(&self) -> Category {
+        Category::parse("Children").child(&self.name)
+    }
+
+    // Main person's ages during which a stage applies, cut off at the end of support
+    fn stage_ages(&self, stage: &ChildStage) -> Option<(u32, u32)> {
+        let to_age = stage.to_age.min(self.template.support_ends_at);
+        (stage.from_age < to_age).then(|| (self.born_at + stage.from_age, self.born_at + to_age))
+    }
+
+    /// Expense streams for every stage, timed by the main person's ages.
+    pub fn expenses(&self) -> Vec<Expense> {
+        self.template
+            .stages
+            .iter()
+            .filter_map(|stage| {
+                let (start_age, end_age) = self.stage_ages(stage)?;
+                Some(
+                    Expense::new(
+                        format!("{}: {}", self.name, stage.name),
+                        stage.monthly_amount,
+                        Frequency::Monthly,
+                        start_age,
+                        Some(end_age),
+                    )
+                    .with_category(self.category().child(&stage.name)),
+                )
+            })
+            .collect()
+    }
+
+    /// Benefit incomes, timed by the main person's ages. Empty unless benefits are enabled.
+    pub fn incomes(&self) -> Vec<Income> {
+        if !self.benefits {
+            return Vec::new();
+        }
+        self.template
+            .benefits
+            .iter()
+            .filter_map(|stage| {
+                let (start_age, end_age) = self.stage_ages(stage)?;
+                Some(
+                    Income::new(
+                        format!("{}: {}", self.name, stage.name),
+                        stage.monthly_amount,
+                        Frequency::Monthly,
+                        start_age,
+                        Some(end_age),
+                    )
+                    .with_category(self.category().child(&stage.name)),
+                )
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,24 @@
+use serde::{Deserialize, Serialize};
+
+/// An overdraft or credit line that covers shortfalls instead of letting cash go negative.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CreditLine {
+    /// Most that can be owed at any time.
+    pub limit: f64,
+    /// Yearly interest charged on the amount owed, e.g. 0.08 for 8%.
+    pub interest_rate: f64,
+}
+
+impl CreditLine {
+    pub fn new(limit: f64, interest_rate: f64) -> Self {
+        CreditLine {
+            limit,
+            interest_rate,
+        }
+    }
+
+    /// How much more can be borrowed while `debt` is owed.
+    pub fn available(&self, debt: f64) -> f64 {
+        (self.limit - debt).max(0.0)
+    }
+}
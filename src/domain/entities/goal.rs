@@ -0,0 +1,19 @@
+use serde::{Deserialize, Serialize};
+
+/// A balance the user wants to have by a given age, e.g. a 50k emergency fund by 30.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Goal {
+    pub name: String,
+    pub target_amount: f64,
+    pub by_age: u32,
+}
+
+impl Goal {
+    pub fn new(name: String, target_amount: f64, by_age: u32) -> Self {
+        Goal {
+            name,
+            target_amount,
+            by_age,
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use crate::domain::entities::category::{Category, CategoryTree};
+use crate::domain::entities::child::Child;
+use crate::domain::entities::person::{Expense, Person};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Role {
+    Partner,
+    Child,
+}
+
+impl fmt::Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Role::Partner => write!(f, "Partner"),
+            Role::Child => write!(f, "Child"),
+        }
+    }
+}
+
+/// Someone planned for together with the main person. Their items use their own ages.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Member {
+    pub role: Role,
+    pub person: Person,
+}
+
+impl Member {
+    pub fn new(role: Role, person: Person) -> Self {
+        Member { role, person }
+    }
+
+    /// The member's age in the calendar year in which the main person, who is `head_start_age`
+    /// today, turns `head_age`.
+    pub fn age_when(&self, head_start_age: u32, head_age: u32) -> u32 {
+        (self.person.age + head_age).saturating_sub(head_start_age)
+    }
+}
+
+/// Everyone besides the main person who shares the joint balance, and the expenses that
+/// belong to the household as a whole. Shared expenses use the main person's ages.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Household {
+    pub members: Vec<Member>,
+    pub shared_expenses: Vec<Expense>,
+    /// Children whose costs follow a template rather than hand-entered items.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<Child>,
+}
+
+impl Household {
+    pub fn new() -> Self {
+        Household {
+            members: Vec::new(),
+            shared_expenses: Vec::new(),
+            children: Vec::new(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty() && self.shared_expenses.is_empty() && self.children.is_empty()
+    }
+
+    /// Capital the members bring into the joint balance.
+    pub fn capital(&self) -> f64 {
+        self.members
+            .iter()
+            .map(|member| member.person.capital)
+            .sum()
+    }
+
+    pub fn partners(&self) -> impl Iterator<Item = &Member> {
+        self.members
+            .iter()
+            .filter(|member| member.role == Role::Partner)
+    }
+
+    pub fn children(&self) -> impl Iterator<Item = &Member> {
+        self.members
+            .iter()
+            .filter(|member| member.role == Role::Child)
+    }
+
+    /// Every category used by a member or shared item.
+    pub fn categories(&self) -> Vec<Category> {
+        let member_items = self.members.iter().flat_map(|member| {
+            let person = &member.person;
+            let expenses = person.expenses.iter().map(|e| e.category.clone());
+            let incomes = person.incomes.iter().map(|i| i.category.clone());
+            expenses.chain(incomes)
+        });
+        let shared = self.shared_expenses.iter().map(|e| e.category.clone());
+        let children = self.children.iter().flat_map(|child| {
+            let expenses = child.expenses().into_iter().map(|e| e.category);
+            let incomes = child.incomes().into_iter().map(|i| i.category);
+            expenses.chain(incomes)
+        });
+        member_items.chain(shared).chain(children).collect()
+    }
+
+    /// Moves every member and shared item from under `category` to under `renamed`.
+    pub fn rename_category(&mut self, category: &Category, renamed: &Category) {
+        let member_items = self.members.iter_mut().flat_map(|member| {
+            let person = &mut member.person;
+            let expenses = person.expenses.iter_mut().map(|e| &mut e.category);
+            let incomes = person.incomes.iter_mut().map(|i| &mut i.category);
+            expenses.chain(incomes)
+        });
+        let shared = self.shared_expenses.iter_mut().map(|e| &mut e.category);
+        for item_category in member_items.chain(shared) {
+            if let Some(moved) = CategoryTree::reparent(item_category, category, renamed) {
+                *item_category = moved;
+            }
+        }
+    }
+
+    /// Makes every member and shared item filed under `category` uncategorized.
+    pub fn remove_category(&mut self, category: &Category) {
+        for member in self.members.iter_mut() {
+            member.person.remove_category(category);
+        }
+        for expense in self.shared_expenses.iter_mut() {
+            if expense.category.is_within(category) {
+                expense.category = Category::uncategorized();
+            }
+        }
+    }
+}
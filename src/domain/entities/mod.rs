@@ -1,2 +1,30 @@
+pub mod account;
+pub mod allocation;
+pub mod assumptions;
+pub mod branch;
+pub mod category;
+pub mod child;
+pub mod credit_line;
+pub mod goal;
+pub mod household;
+pub mod mortality;
+pub mod pension;
 pub mod person;
-pub use person::{Expense, Frequency, Person};
+pub mod property;
+pub mod simulation_config;
+pub mod transaction;
+pub use account::{Account, EmployerMatch, TaxTreatment};
+pub use allocation::{Allocation, AssetAllocation, AssetClass, ClassReturn, GlidePath};
+pub use assumptions::{Assumptions, WithdrawalStrategy};
+pub use branch::{Branch, BranchChange};
+pub use category::{Category, CategoryBreakdown, CategoryTotals, CategoryTree};
+pub use child::{Child, ChildStage, ChildTemplate};
+pub use credit_line::CreditLine;
+pub use goal::Goal;
+pub use household::{Household, Member, Role};
+pub use mortality::{LifeTable, Mortality, Sex};
+pub use pension::{Bracket, Indexation, Pension, PensionFormula, PensionRules};
+pub use person::{Expense, Frequency, Income, Person};
+pub use property::{Mortgage, Property};
+pub use simulation_config::{Horizon, SimulationConfig, TimeStep};
+pub use transaction::{Date, Transaction};
@@ -0,0 +1,233 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::sync::OnceLock;
+
+// Life tables shipped with the application
+const BUILTIN_LIFE_TABLES: [&str; 1] = [include_str!("../../../life-tables/us-2019-approx.json")];
+
+/// Share of people still alive below which a mortality horizon stops projecting.
+pub const SURVIVAL_CUTOFF: f64 = 0.01;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Sex {
+    #[default]
+    Female,
+    Male,
+}
+
+impl Sex {
+    pub const ALL: [Sex; 2] = [Sex::Female, Sex::Male];
+
+    pub fn parse(name: &str) -> Result<Self, String> {
+        Sex::ALL
+            .into_iter()
+            .find(|sex| sex.to_string().eq_ignore_ascii_case(name.trim()))
+            .ok_or_else(|| format!("Unknown sex '{}', use female or male", name))
+    }
+}
+
+impl fmt::Display for Sex {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Sex::Female => write!(f, "female"),
+            Sex::Male => write!(f, "male"),
+        }
+    }
+}
+
+/// A period life table: the probability of dying within the year at each age from 0, by sex.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct LifeTable {
+    pub id: String,
+    pub name: String,
+    /// Where the figures come from.
+    pub source: String,
+    male: Vec<f64>,
+    female: Vec<f64>,
+}
+
+impl LifeTable {
+    /// The bundled tables, read once.
+    pub fn builtin() -> &'static [LifeTable] {
+        static TABLES: OnceLock<Vec<LifeTable>> = OnceLock::new();
+        TABLES.get_or_init(|| {
+            BUILTIN_LIFE_TABLES
+                .iter()
+                .map(|json| serde_json::from_str(json).expect("Bundled life table is invalid"))
+                .collect()
+        })
+    }
+
+    pub fn find(id: &str) -> Result<&'static LifeTable, String> {
+        LifeTable::builtin()
+            .iter()
+            .find(|table| table.id == id)
+            .ok_or_else(|| format!("Unknown life table '{}'", id))
+    }
+
+    pub fn death_probabilities(&self, sex: Sex) -> &[f64] {
+        match sex {
+            Sex::Female => &self.female,
+            Sex::Male => &self.male,
+        }
+    }
+}
+
+/// How long the person may live, used to weigh a projection by the chance of being alive.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Mortality {
+    /// One of the bundled life tables.
+    Table { table: String, sex: Sex },
+    /// Chance of being alive at each listed age, with straight lines in between and nobody
+    /// left after the last age.
+    Curve { points: Vec<(u32, f64)> },
+}
+
+impl Default for Mortality {
+    fn default() -> Self {
+        Mortality::Table {
+            table: "us-2019-approx".to_string(),
+            sex: Sex::default(),
+        }
+    }
+}
+
+impl Mortality {
+    /// Reads a survival curve written as `age:chance` pairs, e.g. `65:1, 85:0.5, 100:0.05`.
+    pub fn parse_curve(text: &str) -> Result<Mortality, String> {
+        let points = text
+            .split(',')
+            .map(|pair| pair.trim())
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| {
+                let (age, chance) = pair
+                    .split_once(':')
+                    .ok_or_else(|| format!("Expected age:chance, got '{}'", pair))?;
+                let age = age
+                    .trim()
+                    .parse::<u32>()
+                    .map_err(|_| format!("Invalid age '{}'", age.trim()))?;
+                let chance = chance
+                    .trim()
+                    .parse::<f64>()
+                    .map_err(|_| format!("Invalid chance '{}'", chance.trim()))?;
+                Ok((age, chance))
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        let curve = Mortality::Curve { points };
+        curve.validate()?;
+        Ok(curve)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            Mortality::Table { table, .. } => LifeTable::find(table).map(|_| ()),
+            Mortality::Curve { points } => {
+                if points.is_empty() {
+                    return Err("The survival curve needs at least one age".to_string());
+                }
+                if points
+                    .iter()
+                    .any(|(_, chance)| !(0.0..=1.0).contains(chance))
+                {
+                    return Err("Survival chances must be between 0 and 1".to_string());
+                }
+                if points
+                    .windows(2)
+                    .any(|pair| pair[1].0 <= pair[0].0 || pair[1].1 > pair[0].1)
+                {
+                    return Err(
+                        "Survival curve ages must increase and chances must not".to_string()
+                    );
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Chance of being alive at each age from 0 until nobody is left, not conditioned on any age.
+    fn survival(&self) -> Result<Vec<f64>, String> {
+        self.validate()?;
+        let mut survival = vec![1.0];
+        match self {
+            Mortality::Table { table, sex } => {
+                let table = LifeTable::find(table)?;
+                for probability in table.death_probabilities(*sex) {
+                    let alive = survival.last().copied().unwrap_or(0.0);
+                    survival.push(alive * (1.0 - probability.clamp(0.0, 1.0)));
+                }
+            }
+            Mortality::Curve { points } => {
+                let (first_age, first_chance) = points[0];
+                let (last_age, _) = points[points.len() - 1];
+                survival = (0..=last_age)
+                    .map(|age| {
+                        if age <= first_age {
+                            return first_chance;
+                        }
+                        let next = points.iter().position(|(at, _)| *at >= age).unwrap();
+                        let (from_age, from) = points[next - 1];
+                        let (to_age, to) = points[next];
+                        from + (to - from) * (age - from_age) as f64 / (to_age - from_age) as f64
+                    })
+                    .collect();
+                survival.push(0.0);
+            }
+        }
+        Ok(survival)
+    }
+
+    /// Chance of being alive at each age from `from_age` on, given being alive at `from_age`,
+    /// ending with the first age nobody reaches.
+    pub fn survival_from(&self, from_age: u32) -> Result<Vec<(u32, f64)>, String> {
+        let survival = self.survival()?;
+        let alive_now = survival.get(from_age as usize).copied().unwrap_or(0.0);
+        if alive_now <= 0.0 {
+            return Err(format!(
+                "Nobody reaches age {} under this mortality",
+                from_age
+            ));
+        }
+        let mut curve: Vec<(u32, f64)> = survival
+            .iter()
+            .enumerate()
+            .skip(from_age as usize)
+            .map(|(age, alive)| (age as u32, alive / alive_now))
+            .collect();
+        if let Some(end) = curve.iter().position(|(_, alive)| *alive <= 0.0) {
+            curve.truncate(end + 1);
+        }
+        Ok(curve)
+    }
+
+    /// Age that fewer than [`SURVIVAL_CUTOFF`] of the people reach.
+    pub fn last_age(&self) -> Result<u32, String> {
+        let survival = self.survival()?;
+        Ok(survival
+            .iter()
+            .position(|alive| *alive < SURVIVAL_CUTOFF)
+            .unwrap_or(survival.len() - 1) as u32)
+    }
+
+    /// Expected age at death for someone alive at `from_age`.
+    pub fn life_expectancy(&self, from_age: u32) -> Result<f64, String> {
+        let survival = self.survival_from(from_age)?;
+        // Deaths are spread evenly over the year, so each year lived in part counts half
+        Ok(from_age as f64 + survival.iter().skip(1).map(|(_, alive)| alive).sum::<f64>() + 0.5)
+    }
+}
+
+impl fmt::Display for Mortality {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Mortality::Table { table, sex } => write!(f, "{} life table ({})", table, sex),
+            Mortality::Curve { points } => {
+                let points: Vec<String> = points
+                    .iter()
+                    .map(|(age, chance)| format!("{}:{}", age, chance))
+                    .collect();
+                write!(f, "{}", points.join(", "))
+            }
+        }
+    }
+}
@@ -0,0 +1,329 @@
+use crate::domain::entities::category::Category;
+use crate::domain::entities::person::{Frequency, Income};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::sync::OnceLock;
+
+// Pension rule sets shipped with the application
+const BUILTIN_PENSION_RULES: [&str; 2] = [
+    include_str!("../../../pension-rules/us-social-security.json"),
+    include_str!("../../../pension-rules/germany-statutory.json"),
+];
+
+/// Share of the average earnings replaced between the previous bracket and `up_to`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Bracket {
+    /// Upper end of the bracket in yearly earnings; `None` has no upper end.
+    pub up_to: Option<f64>,
+    pub rate: f64,
+}
+
+/// How contributions turn into a yearly benefit at the normal retirement age.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum PensionFormula {
+    /// Each year earns its earnings divided by `reference_earnings` as points, and every point
+    /// pays `point_value` a year.
+    Points {
+        reference_earnings: f64,
+        point_value: f64,
+        max_points_per_year: Option<f64>,
+    },
+    /// The average of the best `best_years` earnings, years without earnings counting as zero,
+    /// replaced at the bracket rates. With `full_career_years` the benefit shrinks in proportion
+    /// to the missing years.
+    EarningsAverage {
+        best_years: u32,
+        earnings_cap: Option<f64>,
+        brackets: Vec<Bracket>,
+        full_career_years: Option<u32>,
+    },
+}
+
+impl PensionFormula {
+    /// Yearly benefit for the given yearly earnings, before any claiming age adjustment.
+    pub fn accrued(&self, earnings: &[f64]) -> f64 {
+        match self {
+            PensionFormula::Points {
+                reference_earnings,
+                point_value,
+                max_points_per_year,
+            } => {
+                let points: f64 = earnings
+                    .iter()
+                    .map(|year| {
+                        let points = year / reference_earnings;
+                        max_points_per_year.map_or(points, |max| points.min(max))
+                    })
+                    .sum();
+                points * point_value
+            }
+            PensionFormula::EarningsAverage {
+                best_years,
+                earnings_cap,
+                brackets,
+                full_career_years,
+            } => {
+                let mut capped: Vec<f64> = earnings
+                    .iter()
+                    .map(|year| earnings_cap.map_or(*year, |cap| year.min(cap)))
+                    .collect();
+                capped.sort_by(|a, b| b.total_cmp(a));
+                let best: f64 = capped.iter().take(*best_years as usize).sum();
+                let average = best / (*best_years).max(1) as f64;
+
+                let mut benefit = 0.0;
+                let mut floor = 0.0;
+                for bracket in brackets {
+                    let ceiling = bracket.up_to.unwrap_or(f64::INFINITY);
+                    benefit += (average.min(ceiling) - floor).max(0.0) * bracket.rate;
+                    floor = ceiling;
+                }
+                match full_career_years {
+                    Some(full) => {
+                        benefit * (earnings.len() as f64 / (*full).max(1) as f64).min(1.0)
+                    }
+                    None => benefit,
+                }
+            }
+        }
+    }
+}
+
+/// How a pension in payment grows from year to year.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub enum Indexation {
+    /// Follows inflation, keeping its value in today's money.
+    #[default]
+    Prices,
+    /// Follows wages, which grow `real_growth` a year faster than prices.
+    Wages { real_growth: f64 },
+    /// Grows by a fixed nominal rate whatever the inflation; 0 freezes it.
+    Fixed { rate: f64 },
+}
+
+impl Indexation {
+    /// Value after `years` in payment relative to a pension that follows inflation.
+    pub fn real_factor(&self, years: u32, inflation: f64) -> f64 {
+        match self {
+            Indexation::Prices => 1.0,
+            Indexation::Wages { real_growth } => (1.0 + real_growth).powi(years as i32),
+            Indexation::Fixed { rate } => ((1.0 + rate) / (1.0 + inflation)).powi(years as i32),
+        }
+    }
+}
+
+impl fmt::Display for Indexation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Indexation::Prices => write!(f, "follows prices"),
+            Indexation::Wages { real_growth } => {
+                write!(
+                    f,
+                    "follows wages ({:.1}% above prices)",
+                    real_growth * 100.0
+                )
+            }
+            Indexation::Fixed { rate } => write!(f, "fixed {:.1}% a year", rate * 100.0),
+        }
+    }
+}
+
+/// A country's pension scheme, described by a data file. Amounts are yearly, in today's money.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PensionRules {
+    pub id: String,
+    pub name: String,
+    /// Where the figures come from.
+    pub source: String,
+    pub formula: PensionFormula,
+    /// Years with earnings needed for any benefit.
+    #[serde(default)]
+    pub minimum_years: u32,
+    /// Age at which the benefit is paid in full.
+    pub normal_age: u32,
+    pub earliest_age: u32,
+    /// Last age the claim can be put off to; `None` has no limit.
+    pub latest_age: Option<u32>,
+    /// Share of the benefit lost for each year claimed before the normal age.
+    pub early_reduction: f64,
+    /// Share of the benefit gained for each year claimed after the normal age.
+    pub late_increase: f64,
+    #[serde(default)]
+    pub indexation: Indexation,
+}
+
+impl PensionRules {
+    /// The bundled rule sets, read once.
+    pub fn builtin() -> &'static [PensionRules] {
+        static RULES: OnceLock<Vec<PensionRules>> = OnceLock::new();
+        RULES.get_or_init(|| {
+            BUILTIN_PENSION_RULES
+                .iter()
+                .map(|json| {
+                    PensionRules::from_json(json).expect("Bundled pension rules are invalid")
+                })
+                .collect()
+        })
+    }
+
+    pub fn find(id: &str) -> Result<&'static PensionRules, String> {
+        PensionRules::builtin()
+            .iter()
+            .find(|rules| rules.id == id)
+            .ok_or_else(|| format!("Unknown pension rules '{}'", id))
+    }
+
+    /// Reads a rule set from the JSON of a data file.
+    pub fn from_json(json: &str) -> Result<PensionRules, String> {
+        let rules: PensionRules =
+            serde_json::from_str(json).map_err(|e| format!("Invalid pension rules: {}", e))?;
+        rules.validate()?;
+        Ok(rules)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.earliest_age > self.normal_age
+            || self.latest_age.is_some_and(|age| age < self.normal_age)
+        {
+            return Err(format!(
+                "{}: the normal age must lie between the earliest and latest claiming ages",
+                self.id
+            ));
+        }
+        if let PensionFormula::Points {
+            reference_earnings, ..
+        } = &self.formula
+            && *reference_earnings <= 0.0
+        {
+            return Err(format!("{}: reference earnings must be positive", self.id));
+        }
+        Ok(())
+    }
+
+    /// Factor on the benefit when it is claimed at `claiming_age`.
+    pub fn claiming_adjustment(&self, claiming_age: u32) -> f64 {
+        let early = self.normal_age.saturating_sub(claiming_age) as f64;
+        let late = claiming_age.saturating_sub(self.normal_age) as f64;
+        (1.0 - early * self.early_reduction + late * self.late_increase).max(0.0)
+    }
+
+    /// Claiming ages allowed by the rules, up to `fallback` when there is no latest age.
+    pub fn claiming_ages(&self, fallback: u32) -> std::ops::RangeInclusive<u32> {
+        self.earliest_age..=self.latest_age.unwrap_or(fallback.max(self.normal_age))
+    }
+}
+
+/// A public or occupational pension whose amount follows from the contributions paid rather than
+/// being typed in.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Pension {
+    pub name: String,
+    /// Copied in, so the scenario keeps the rules it was planned with.
+    pub rules: PensionRules,
+    /// Earnings of past years in today's money, by age. Later years are taken from the earned
+    /// incomes until retirement.
+    #[serde(default)]
+    pub history: Vec<(u32, f64)>,
+    pub claiming_age: u32,
+}
+
+impl Pension {
+    pub fn new(name: String, rules: PensionRules) -> Self {
+        let claiming_age = rules.normal_age;
+        Pension {
+            name,
+            rules,
+            history: Vec::new(),
+            claiming_age,
+        }
+    }
+
+    pub fn with_history(mut self, history: Vec<(u32, f64)>) -> Self {
+        self.history = history;
+        self
+    }
+
+    pub fn with_claiming_age(mut self, claiming_age: u32) -> Self {
+        self.claiming_age = claiming_age;
+        self
+    }
+
+    /// Reads an earnings history written as `age:earnings` pairs, e.g. `25:30000, 26:32000`.
+    /// A range such as `25-34:40000` repeats the amount for every age in it.
+    pub fn parse_history(text: &str) -> Result<Vec<(u32, f64)>, String> {
+        let mut history = Vec::new();
+        for pair in text
+            .split(',')
+            .map(str::trim)
+            .filter(|pair| !pair.is_empty())
+        {
+            let (ages, earnings) = pair
+                .split_once(':')
+                .ok_or_else(|| format!("Expected age:earnings, got '{}'", pair))?;
+            let earnings = earnings
+                .trim()
+                .parse::<f64>()
+                .map_err(|_| format!("Invalid earnings '{}'", earnings.trim()))?;
+            let (from, to) = ages.split_once('-').unwrap_or((ages, ages));
+            let parse_age = |age: &str| {
+                age.trim()
+                    .parse::<u32>()
+                    .map_err(|_| format!("Invalid age '{}'", age.trim()))
+            };
+            let (from, to) = (parse_age(from)?, parse_age(to)?);
+            if to < from {
+                return Err(format!("Invalid age range '{}'", ages.trim()));
+            }
+            history.extend((from..=to).map(|age| (age, earnings)));
+        }
+        Ok(history)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        self.rules.validate()?;
+        let ages = self.rules.claiming_ages(self.claiming_age);
+        if !ages.contains(&self.claiming_age) {
+            return Err(format!(
+                "{} can be claimed from age {} to {}, not at {}",
+                self.rules.name,
+                ages.start(),
+                ages.end(),
+                self.claiming_age
+            ));
+        }
+        Ok(())
+    }
+
+    /// Yearly benefit from the claiming age in today's money, given the earnings of every
+    /// contribution year. Years from the claiming age on do not count.
+    pub fn benefit(&self, earnings: &[(u32, f64)]) -> f64 {
+        let years: Vec<f64> = earnings
+            .iter()
+            .filter(|(age, amount)| *age < self.claiming_age && *amount > 0.0)
+            .map(|(_, amount)| *amount)
+            .collect();
+        if (years.len() as u32) < self.rules.minimum_years.max(1) {
+            return 0.0;
+        }
+        self.rules.formula.accrued(&years) * self.rules.claiming_adjustment(self.claiming_age)
+    }
+
+    /// The pension as an income paid from the claiming age on.
+    pub fn income(&self, earnings: &[(u32, f64)]) -> Income {
+        Income::new(
+            self.name.clone(),
+            self.benefit(earnings),
+            Frequency::Yearly,
+            self.claiming_age,
+            None,
+        )
+        .with_category(Category::parse("Pension").child(&self.name))
+    }
+
+    /// Value of the income at `age` relative to one that follows inflation.
+    pub fn indexation_factor(&self, age: u32, inflation: f64) -> f64 {
+        self.rules
+            .indexation
+            .real_factor(age.saturating_sub(self.claiming_age), inflation)
+    }
+}
@@ -1,12 +1,21 @@
+use crate::domain::entities::category::{Category, CategoryTree};
+use crate::domain::entities::pension::Pension;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Person {
     pub name: String,
     pub age: u32,
     pub capital: f64,
     pub expenses: Vec<Expense>,
     pub incomes: Vec<Income>,
+    /// Pensions whose incomes are worked out from the contributions paid.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pensions: Vec<Pension>,
+    #[serde(default = "CategoryTree::with_defaults")]
+    pub categories: CategoryTree,
+    #[serde(skip)]
     pub balance_history: HashMap<u32, f64>, // age -> balance
 }
 
@@ -18,6 +27,8 @@ impl Person {
             capital,
             expenses: Vec::new(),
             incomes: Vec::new(),
+            pensions: Vec::new(),
+            categories: CategoryTree::with_defaults(),
             balance_history: HashMap::new(),
         };
 
@@ -27,19 +38,52 @@ impl Person {
     }
 
     pub fn add_expense(&mut self, expense: Expense) {
+        self.categories.add(expense.category.clone());
         self.expenses.push(expense);
     }
 
     pub fn add_income(&mut self, income: Income) {
+        self.categories.add(income.category.clone());
         self.incomes.push(income);
     }
 
+    pub fn add_pension(&mut self, pension: Pension) {
+        self.categories
+            .add(Category::parse("Pension").child(&pension.name));
+        self.pensions.push(pension);
+    }
+
+    /// Renames a category in the tree and moves every item filed under it.
+    pub fn rename_category(&mut self, category: &Category, new_name: &str) {
+        let renamed = self.categories.rename(category, new_name);
+        let expenses = self.expenses.iter_mut().map(|e| &mut e.category);
+        let incomes = self.incomes.iter_mut().map(|i| &mut i.category);
+        for item_category in expenses.chain(incomes) {
+            if let Some(moved) = CategoryTree::reparent(item_category, category, &renamed) {
+                *item_category = moved;
+            }
+        }
+    }
+
+    /// Removes a category subtree; items filed under it become uncategorized.
+    pub fn remove_category(&mut self, category: &Category) {
+        self.categories.remove(category);
+        let expenses = self.expenses.iter_mut().map(|e| &mut e.category);
+        let incomes = self.incomes.iter_mut().map(|i| &mut i.category);
+        for item_category in expenses.chain(incomes) {
+            if item_category.is_within(category) {
+                *item_category = Category::uncategorized();
+            }
+        }
+    }
+
     pub fn get_current_balance(&self) -> f64 {
-        *self.balance_history.get(&self.age).unwrap_or(&0.0)
+        // The history is not persisted, so a freshly loaded person falls back to its capital
+        *self.balance_history.get(&self.age).unwrap_or(&self.capital)
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Default)]
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
 pub enum Frequency {
     #[default]
     Yearly,
@@ -47,13 +91,28 @@ pub enum Frequency {
     Daily,
 }
 
-#[derive(Debug, Clone)]
+impl Frequency {
+    /// Number of periods of this frequency in one year.
+    pub fn periods_per_year(&self) -> f64 {
+        match self {
+            Frequency::Yearly => 1.0,
+            Frequency::Monthly => 12.0,
+            Frequency::Daily => 365.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Expense {
     pub name: String,
     pub amount: f64,
     pub frequency: Frequency, // yearly, monthly, daily
     pub start_age: u32,
     pub end_age: Option<u32>, // None means ongoing
+    #[serde(default)]
+    pub category: Category,
+    #[serde(default)]
+    pub tags: Vec<String>,
 }
 
 impl Expense {
@@ -70,17 +129,49 @@ impl Expense {
             frequency,
             start_age,
             end_age,
+            category: Category::uncategorized(),
+            tags: Vec::new(),
         }
     }
+
+    pub fn with_category(mut self, category: Category) -> Self {
+        self.category = category;
+        self
+    }
+
+    pub fn with_tags(mut self, tags: Vec<String>) -> Self {
+        self.tags = tags;
+        self
+    }
+
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t.eq_ignore_ascii_case(tag))
+    }
+
+    /// Whether this expense applies in the year starting at `age` (the end age is exclusive).
+    pub fn is_active_at(&self, age: u32) -> bool {
+        age >= self.start_age && self.end_age.is_none_or(|end_age| age < end_age)
+    }
+
+    pub fn yearly_amount(&self) -> f64 {
+        self.amount * self.frequency.periods_per_year()
+    }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Income {
     pub name: String,
     pub amount: f64,
     pub frequency: Frequency, // yearly, monthly, daily
     pub start_age: u32,
     pub end_age: Option<u32>, // None means ongoing
+    #[serde(default)]
+    pub category: Category,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Employment income, which stops at the retirement age.
+    #[serde(default)]
+    pub earned: bool,
 }
 
 impl Income {
@@ -97,6 +188,37 @@ impl Income {
             frequency,
             start_age,
             end_age,
+            category: Category::uncategorized(),
+            tags: Vec::new(),
+            earned: false,
         }
     }
+
+    pub fn with_earned(mut self, earned: bool) -> Self {
+        self.earned = earned;
+        self
+    }
+
+    pub fn with_category(mut self, category: Category) -> Self {
+        self.category = category;
+        self
+    }
+
+    pub fn with_tags(mut self, tags: Vec<String>) -> Self {
+        self.tags = tags;
+        self
+    }
+
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t.eq_ignore_ascii_case(tag))
+    }
+
+    /// Whether this income applies in the year starting at `age` (the end age is exclusive).
+    pub fn is_active_at(&self, age: u32) -> bool {
+        age >= self.start_age && self.end_age.is_none_or(|end_age| age < end_age)
+    }
+
+    pub fn yearly_amount(&self) -> f64 {
+        self.amount * self.frequency.periods_per_year()
+    }
 }
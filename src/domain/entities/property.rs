@@ -0,0 +1,253 @@
+use crate::domain::entities::category::Category;
+use serde::{Deserialize, Serialize};
+
+/// A repayment loan paid off in equal yearly instalments.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Mortgage {
+    /// Yearly interest on the amount owed, e.g. 0.04 for 4%.
+    pub interest_rate: f64,
+    pub years: u32,
+}
+
+impl Mortgage {
+    pub fn new(interest_rate: f64, years: u32) -> Self {
+        Mortgage {
+            interest_rate,
+            years,
+        }
+    }
+
+    /// Yearly instalment that repays `principal` with interest over the term.
+    pub fn payment(&self, principal: f64) -> f64 {
+        let years = self.years.max(1) as f64;
+        let rate = self.interest_rate;
+        if rate == 0.0 {
+            return principal / years;
+        }
+        principal * rate / (1.0 - (1.0 + rate).powf(-years))
+    }
+
+    /// Amount still owed on `principal` after `payments` yearly instalments.
+    pub fn balance_after(&self, principal: f64, payments: u32) -> f64 {
+        if payments >= self.years {
+            return 0.0;
+        }
+        let rate = self.interest_rate;
+        let payment = self.payment(principal);
+        if rate == 0.0 {
+            return principal - payment * payments as f64;
+        }
+        let growth = (1.0 + rate).powi(payments as i32);
+        (principal * growth - payment * (growth - 1.0) / rate).max(0.0)
+    }
+}
+
+/// A home or rental property bought at `purchase_age`. The purchase and sale happen at the start
+/// of those years, and the property is held for the years in between.
+///
+/// `price_level` arguments are prices in the purchase year relative to today, which turn the
+/// price in today's money into what is paid; properties bought in the past are taken at the
+/// price paid.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Property {
+    pub name: String,
+    /// Price in today's money, or the price paid for a property bought in the past.
+    pub purchase_price: f64,
+    pub purchase_age: u32,
+    /// Share of the price paid from savings; the mortgage lends the rest.
+    #[serde(default)]
+    pub down_payment: f64,
+    /// Loan for the part of the price the down payment leaves; without one the whole price is
+    /// paid from savings.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mortgage: Option<Mortgage>,
+    /// Yearly growth of the value, before inflation is taken out.
+    #[serde(default)]
+    pub appreciation: f64,
+    /// Yearly upkeep as a share of the value.
+    #[serde(default)]
+    pub maintenance: f64,
+    /// Yearly property tax as a share of the value.
+    #[serde(default)]
+    pub property_tax: f64,
+    /// Yearly insurance as a share of the value.
+    #[serde(default)]
+    pub insurance: f64,
+    /// Yearly rent received in today's money, for a let property.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rental_income: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sale_age: Option<u32>,
+    /// Agent fees and taxes on the sale, as a share of the sale price.
+    #[serde(default)]
+    pub selling_costs: f64,
+}
+
+impl Property {
+    pub fn new(name: &str, purchase_price: f64, purchase_age: u32) -> Self {
+        Property {
+            name: name.to_string(),
+            purchase_price,
+            purchase_age,
+            down_payment: 1.0,
+            mortgage: None,
+            appreciation: 0.0,
+            maintenance: 0.0,
+            property_tax: 0.0,
+            insurance: 0.0,
+            rental_income: None,
+            sale_age: None,
+            selling_costs: 0.0,
+        }
+    }
+
+    pub fn with_mortgage(mut self, down_payment: f64, mortgage: Mortgage) -> Self {
+        self.down_payment = down_payment;
+        self.mortgage = Some(mortgage);
+        self
+    }
+
+    pub fn with_appreciation(mut self, appreciation: f64) -> Self {
+        self.appreciation = appreciation;
+        self
+    }
+
+    pub fn with_costs(mut self, maintenance: f64, property_tax: f64, insurance: f64) -> Self {
+        self.maintenance = maintenance;
+        self.property_tax = property_tax;
+        self.insurance = insurance;
+        self
+    }
+
+    pub fn with_rental_income(mut self, rental_income: f64) -> Self {
+        self.rental_income = Some(rental_income);
+        self
+    }
+
+    pub fn with_sale(mut self, sale_age: u32, selling_costs: f64) -> Self {
+        self.sale_age = Some(sale_age);
+        self.selling_costs = selling_costs;
+        self
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.purchase_price <= 0.0 {
+            return Err(format!("{}: the price must be positive", self.name));
+        }
+        if self.mortgage.is_some() && !(0.0..=1.0).contains(&self.down_payment) {
+            return Err(format!(
+                "{}: the down payment must be from 0 to 100% of the price",
+                self.name
+            ));
+        }
+        if self.sale_age.is_some_and(|age| age <= self.purchase_age) {
+            return Err(format!("{}: it must be sold after it is bought", self.name));
+        }
+        let shares = [
+            self.maintenance,
+            self.property_tax,
+            self.insurance,
+            self.selling_costs,
+        ];
+        if shares.iter().any(|share| !(0.0..1.0).contains(share)) {
+            return Err(format!(
+                "{}: costs must be from 0 to 100% of the value",
+                self.name
+            ));
+        }
+        Ok(())
+    }
+
+    /// Whether the property is held at `age`, the start of that year.
+    pub fn is_owned_at(&self, age: u32) -> bool {
+        age > self.purchase_age && self.sale_age.is_none_or(|sale_age| age <= sale_age)
+    }
+
+    /// Whether the costs, rent and mortgage of the year starting at `age` are paid.
+    pub fn is_held_during(&self, age: u32) -> bool {
+        age >= self.purchase_age && self.sale_age.is_none_or(|sale_age| age < sale_age)
+    }
+
+    fn price(&self, price_level: f64) -> f64 {
+        self.purchase_price * price_level
+    }
+
+    fn loan(&self, price_level: f64) -> f64 {
+        match self.mortgage {
+            Some(_) => self.price(price_level) * (1.0 - self.down_payment),
+            None => 0.0,
+        }
+    }
+
+    /// What is paid from savings at the purchase.
+    pub fn cash_price(&self, price_level: f64) -> f64 {
+        self.price(price_level) - self.loan(price_level)
+    }
+
+    /// Market value at `age`, having grown by the appreciation since the purchase.
+    pub fn value_at(&self, age: u32, price_level: f64) -> f64 {
+        let years = age.saturating_sub(self.purchase_age);
+        self.price(price_level) * (1.0 + self.appreciation).powi(years as i32)
+    }
+
+    /// Yearly mortgage instalment, fixed in the money of the purchase year.
+    pub fn mortgage_payment(&self, price_level: f64) -> f64 {
+        self.mortgage
+            .as_ref()
+            .map_or(0.0, |mortgage| mortgage.payment(self.loan(price_level)))
+    }
+
+    /// Whether a mortgage instalment falls in the year starting at `age`.
+    pub fn is_repaying_during(&self, age: u32) -> bool {
+        self.is_held_during(age)
+            && self
+                .mortgage
+                .as_ref()
+                .is_some_and(|mortgage| age < self.purchase_age + mortgage.years)
+    }
+
+    /// Amount owed on the mortgage at `age`.
+    pub fn mortgage_balance_at(&self, age: u32, price_level: f64) -> f64 {
+        match &self.mortgage {
+            Some(mortgage) if age > self.purchase_age => {
+                mortgage.balance_after(self.loan(price_level), age - self.purchase_age)
+            }
+            Some(_) => self.loan(price_level),
+            None => 0.0,
+        }
+    }
+
+    /// Value less what is owed on it at `age`, or 0 when it is not held.
+    pub fn equity_at(&self, age: u32, price_level: f64) -> f64 {
+        if !self.is_owned_at(age) {
+            return 0.0;
+        }
+        self.value_at(age, price_level) - self.mortgage_balance_at(age, price_level)
+    }
+
+    /// Maintenance, tax and insurance for the year starting at `age`.
+    pub fn running_costs_at(&self, age: u32, price_level: f64) -> f64 {
+        (self.maintenance + self.property_tax + self.insurance) * self.value_at(age, price_level)
+    }
+
+    /// What the sale leaves after selling costs and paying off the mortgage.
+    pub fn sale_proceeds(&self, price_level: f64) -> f64 {
+        let Some(sale_age) = self.sale_age else {
+            return 0.0;
+        };
+        self.value_at(sale_age, price_level) * (1.0 - self.selling_costs)
+            - self.mortgage_balance_at(sale_age, price_level)
+    }
+
+    pub fn mortgage_category(&self) -> Category {
+        Category::parse("Housing > Mortgage").child(&self.name)
+    }
+
+    pub fn costs_category(&self) -> Category {
+        Category::parse("Housing > Property costs").child(&self.name)
+    }
+
+    pub fn rent_category(&self) -> Category {
+        Category::parse("Rental income").child(&self.name)
+    }
+}
@@ -0,0 +1,172 @@
+use crate::domain::entities::mortality::Mortality;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// How far a projection runs.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Horizon {
+    /// Project up to a fixed age.
+    EndAge(u32),
+    /// Project past the expected age of death by `margin` years, since half of the people
+    /// outlive their life expectancy.
+    LifeExpectancy { age: u32, margin: u32 },
+    /// Project until almost nobody is left under a mortality table or survival curve, and
+    /// weigh the outcome by the chance of being alive at each age.
+    Mortality(Mortality),
+}
+
+impl Default for Horizon {
+    fn default() -> Self {
+        Horizon::EndAge(100)
+    }
+}
+
+impl Horizon {
+    /// Margin used when switching to a life expectancy.
+    pub const DEFAULT_MARGIN: u32 = 5;
+
+    /// A mortality that cannot be read falls back to the default end age; `validate` reports it.
+    pub fn end_age(&self) -> u32 {
+        match self {
+            Horizon::EndAge(age) => *age,
+            Horizon::LifeExpectancy { age, margin } => age + margin,
+            Horizon::Mortality(mortality) => mortality
+                .last_age()
+                .unwrap_or_else(|_| Horizon::default().end_age()),
+        }
+    }
+}
+
+/// Length of the steps the balance is simulated in. Returns and credit line interest compound
+/// at every step, and a year's incomes and expenses are spread evenly over its steps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum TimeStep {
+    #[default]
+    Yearly,
+    Monthly,
+}
+
+impl TimeStep {
+    pub const ALL: [TimeStep; 2] = [TimeStep::Yearly, TimeStep::Monthly];
+
+    pub fn parse(name: &str) -> Result<Self, String> {
+        TimeStep::ALL
+            .into_iter()
+            .find(|step| step.to_string().eq_ignore_ascii_case(name.trim()))
+            .ok_or_else(|| format!("Unknown time step '{}', use yearly or monthly", name))
+    }
+
+    pub fn steps_per_year(&self) -> u32 {
+        match self {
+            TimeStep::Yearly => 1,
+            TimeStep::Monthly => 12,
+        }
+    }
+
+    /// Rate per step that compounds to `annual_rate` over a year.
+    pub fn step_rate(&self, annual_rate: f64) -> f64 {
+        match self {
+            TimeStep::Yearly => annual_rate,
+            TimeStep::Monthly => (1.0 + annual_rate).powf(1.0 / 12.0) - 1.0,
+        }
+    }
+}
+
+impl fmt::Display for TimeStep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TimeStep::Yearly => write!(f, "yearly"),
+            TimeStep::Monthly => write!(f, "monthly"),
+        }
+    }
+}
+
+/// Which ages a scenario is projected over and how finely, saved with the scenario so every
+/// view, report and command uses the same range.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SimulationConfig {
+    /// First age shown in tables and charts; `None` starts at the current age.
+    #[serde(default)]
+    pub start_age: Option<u32>,
+    #[serde(default)]
+    pub horizon: Horizon,
+    #[serde(default)]
+    pub time_step: TimeStep,
+    /// Years between the rows of projection tables.
+    #[serde(default = "default_granularity")]
+    pub granularity: u32,
+}
+
+fn default_granularity() -> u32 {
+    1
+}
+
+impl Default for SimulationConfig {
+    fn default() -> Self {
+        SimulationConfig {
+            start_age: None,
+            horizon: Horizon::default(),
+            time_step: TimeStep::default(),
+            granularity: default_granularity(),
+        }
+    }
+}
+
+impl SimulationConfig {
+    pub fn end_age(&self) -> u32 {
+        self.horizon.end_age()
+    }
+
+    pub fn mortality(&self) -> Option<&Mortality> {
+        match &self.horizon {
+            Horizon::Mortality(mortality) => Some(mortality),
+            _ => None,
+        }
+    }
+
+    /// First age shown for a person who is `current_age` today; earlier ages are not projected.
+    pub fn first_age(&self, current_age: u32) -> u32 {
+        self.start_age
+            .map_or(current_age, |age| age.max(current_age))
+            .min(self.end_age().max(current_age))
+    }
+
+    /// Ages listed in projection tables: every `granularity` years from the first age, always
+    /// ending with the end age.
+    pub fn output_ages(&self, current_age: u32) -> Vec<u32> {
+        let end_age = self.end_age().max(current_age);
+        let mut ages: Vec<u32> = (self.first_age(current_age)..=end_age)
+            .step_by(self.granularity.max(1) as usize)
+            .collect();
+        if ages.last() != Some(&end_age) {
+            ages.push(end_age);
+        }
+        ages
+    }
+
+    pub fn validate(&self, current_age: u32) -> Result<(), String> {
+        if let Some(mortality) = self.mortality() {
+            mortality.survival_from(current_age)?;
+        }
+        if self.end_age() <= current_age {
+            return Err(format!(
+                "The end age ({}) must be after the current age ({})",
+                self.end_age(),
+                current_age
+            ));
+        }
+        if let Some(start_age) = self.start_age
+            && start_age >= self.end_age()
+        {
+            return Err(format!(
+                "The start age ({}) must be before the end age ({})",
+                start_age,
+                self.end_age()
+            ));
+        }
+        if self.granularity == 0 {
+            return Err("Rows must be at least one year apart".to_string());
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,78 @@
+use crate::domain::entities::category::Category;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// A calendar date as found in bank statements.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct Date {
+    pub year: i32,
+    pub month: u32,
+    pub day: u32,
+}
+
+impl Date {
+    /// Builds a date, rejecting days that do not exist in the given month.
+    pub fn new(year: i32, month: u32, day: u32) -> Option<Self> {
+        let valid = (1..=12).contains(&month) && day >= 1 && day <= days_in_month(year, month);
+        valid.then_some(Date { year, month, day })
+    }
+
+    /// Days since 1970-01-01, so that differences between dates are day counts.
+    pub fn days(&self) -> i64 {
+        // Shifting the year to start in March puts the leap day at its end
+        let year = self.year as i64 - i64::from(self.month <= 2);
+        let era = year.div_euclid(400);
+        let year_of_era = year - era * 400;
+        let month = self.month as i64;
+        let day_of_year =
+            (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + self.day as i64 - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        era * 146_097 + day_of_era - 719_468
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// One booked transaction. Money leaving the account has a negative amount.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Transaction {
+    pub date: Date,
+    pub amount: f64,
+    pub description: String,
+    /// Category booked with the transaction, e.g. from a QIF category or a ledger account.
+    #[serde(default)]
+    pub category: Category,
+}
+
+impl Transaction {
+    pub fn new(date: Date, amount: f64, description: String) -> Self {
+        Transaction {
+            date,
+            amount,
+            description,
+            category: Category::uncategorized(),
+        }
+    }
+
+    pub fn with_category(mut self, category: Category) -> Self {
+        self.category = category;
+        self
+    }
+
+    pub fn is_income(&self) -> bool {
+        self.amount > 0.0
+    }
+}
@@ -0,0 +1,84 @@
+use crate::domain::services::life_simulator::LifeSimulator;
+
+/// A stretch of consecutive ages during which the balance stays below the cash buffer.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ShortfallPeriod {
+    pub start_age: u32,
+    /// First age back at or above the buffer, or `None` if it never recovers before the end age.
+    pub recovered_at: Option<u32>,
+    pub lowest_age: u32,
+    pub lowest_balance: f64,
+}
+
+impl ShortfallPeriod {
+    /// Number of projected years spent below the buffer, counting up to `end_age`
+    /// for a period that does not recover.
+    pub fn years(&self, end_age: u32) -> u32 {
+        self.recovered_at.unwrap_or(end_age + 1) - self.start_age
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BalanceAlert {
+    pub cash_buffer: f64,
+    pub end_age: u32,
+    pub periods: Vec<ShortfallPeriod>,
+}
+
+impl BalanceAlert {
+    /// First age at which the balance drops below the buffer.
+    pub fn first_age(&self) -> Option<u32> {
+        self.periods.first().map(|period| period.start_age)
+    }
+
+    /// Total years spent below the buffer.
+    pub fn years_below(&self) -> u32 {
+        self.periods
+            .iter()
+            .map(|period| period.years(self.end_age))
+            .sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.periods.is_empty()
+    }
+}
+
+/// Finds every period up to `end_age` in which the projected balance falls below the
+/// simulator's cash buffer (zero unless configured).
+pub fn balance_alert(simulator: &mut LifeSimulator, end_age: u32) -> BalanceAlert {
+    let cash_buffer = simulator.get_cash_buffer();
+    let mut periods: Vec<ShortfallPeriod> = Vec::new();
+    let mut current: Option<ShortfallPeriod> = None;
+
+    for (age, balance) in simulator.balance_series(end_age) {
+        let below = balance < cash_buffer;
+        match (&mut current, below) {
+            (Some(period), true) if balance < period.lowest_balance => {
+                period.lowest_age = age;
+                period.lowest_balance = balance;
+            }
+            (Some(period), false) => {
+                period.recovered_at = Some(age);
+                periods.push(*period);
+                current = None;
+            }
+            (None, true) => {
+                current = Some(ShortfallPeriod {
+                    start_age: age,
+                    recovered_at: None,
+                    lowest_age: age,
+                    lowest_balance: balance,
+                });
+            }
+            _ => {}
+        }
+    }
+    periods.extend(current);
+
+    BalanceAlert {
+        cash_buffer,
+        end_age,
+        periods,
+    }
+}
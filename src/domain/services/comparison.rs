@@ -0,0 +1,77 @@
+use crate::domain::entities::{
+    category::Category,
+    person::{Expense, Frequency},
+};
+use crate::domain::services::life_simulator::LifeSimulator;
+
+/// The balances of two scenarios at one age.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BalanceDelta {
+    pub age: u32,
+    pub baseline: f64,
+    pub other: f64,
+}
+
+impl BalanceDelta {
+    /// How much better (positive) or worse (negative) `other` ends up than `baseline`.
+    pub fn delta(&self) -> f64 {
+        self.other - self.baseline
+    }
+}
+
+/// Projects both scenarios up to `end_age` and pairs their balances for every age
+/// that both of them cover.
+pub fn compare_balances(
+    baseline: &mut LifeSimulator,
+    other: &mut LifeSimulator,
+    end_age: u32,
+) -> Vec<BalanceDelta> {
+    let start_age = baseline.get_current_age().max(other.get_current_age());
+    (start_age..=end_age)
+        .map(|age| BalanceDelta {
+            age,
+            baseline: baseline.calculate_balance_at_age(age),
+            other: other.calculate_balance_at_age(age),
+        })
+        .collect()
+}
+
+/// Compares the net worth of renting at `rent` a year in today's money (the baseline) with
+/// buying the properties the scenario has yet to buy (the other side), up to `end_age`. The renter pays rent from the
+/// first purchase until the last sale and keeps the down payments invested.
+pub fn buy_vs_rent(
+    simulator: &LifeSimulator,
+    rent: f64,
+    end_age: u32,
+) -> Result<Vec<BalanceDelta>, String> {
+    let current_age = simulator.get_current_age();
+    let (bought, owned): (Vec<_>, Vec<_>) = simulator
+        .get_properties()
+        .iter()
+        .cloned()
+        .partition(|property| property.purchase_age >= current_age);
+    let Some(from) = bought.iter().map(|property| property.purchase_age).min() else {
+        return Err("The scenario has no property to buy".to_string());
+    };
+    let until = bought
+        .iter()
+        .map(|property| property.sale_age)
+        .collect::<Option<Vec<u32>>>()
+        .and_then(|ages| ages.into_iter().max());
+
+    let mut buying = simulator.clone();
+    let mut renting = simulator.clone();
+    renting.set_properties(owned);
+    renting.add_expense(
+        Expense::new("Rent".to_string(), rent, Frequency::Yearly, from, until)
+            .with_category(Category::parse("Housing > Rent")),
+    );
+    // The bought home counts at its equity, so both sides are compared by net worth
+    Ok((current_age..=end_age)
+        .map(|age| BalanceDelta {
+            age,
+            baseline: renting.net_worth_at(age),
+            other: buying.net_worth_at(age),
+        })
+        .collect())
+}
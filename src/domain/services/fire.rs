@@ -0,0 +1,131 @@
+use crate::domain::services::life_simulator::LifeSimulator;
+use std::fmt;
+
+/// How much spending the financially independent life should support, relative to the
+/// expenses that are modeled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FireVariant {
+    /// Bare-bones spending, 75% of the modeled expenses.
+    Lean,
+    #[default]
+    Regular,
+    /// Comfortable spending, 150% of the modeled expenses.
+    Fat,
+}
+
+impl FireVariant {
+    pub const ALL: [FireVariant; 3] = [FireVariant::Lean, FireVariant::Regular, FireVariant::Fat];
+
+    pub fn spending_multiplier(&self) -> f64 {
+        match self {
+            FireVariant::Lean => 0.75,
+            FireVariant::Regular => 1.0,
+            FireVariant::Fat => 1.5,
+        }
+    }
+
+    /// Parses `lean`, `regular` or `fat` (case insensitive).
+    pub fn parse(name: &str) -> Result<FireVariant, String> {
+        match name.to_lowercase().as_str() {
+            "lean" => Ok(FireVariant::Lean),
+            "regular" => Ok(FireVariant::Regular),
+            "fat" => Ok(FireVariant::Fat),
+            _ => Err(format!(
+                "Unknown FIRE variant \"{}\", expected lean, regular or fat",
+                name
+            )),
+        }
+    }
+}
+
+impl fmt::Display for FireVariant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FireVariant::Lean => write!(f, "Lean"),
+            FireVariant::Regular => write!(f, "Regular"),
+            FireVariant::Fat => write!(f, "Fat"),
+        }
+    }
+}
+
+/// Savings and FIRE target for one year.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FireYear {
+    pub age: u32,
+    /// Balance at the start of the year.
+    pub balance: f64,
+    pub incomes: f64,
+    pub expenses: f64,
+    /// Portfolio needed to fund this year's spending forever at the withdrawal rate.
+    pub fire_number: f64,
+}
+
+impl FireYear {
+    /// Share of the year's income that is saved, or `None` without income.
+    pub fn savings_rate(&self) -> Option<f64> {
+        (self.incomes > 0.0).then(|| (self.incomes - self.expenses) / self.incomes)
+    }
+
+    pub fn is_independent(&self) -> bool {
+        self.balance >= self.fire_number
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FireReport {
+    pub variant: FireVariant,
+    pub withdrawal_rate: f64,
+    pub years: Vec<FireYear>,
+    /// First age at which the balance reaches the FIRE number.
+    pub fire_age: Option<u32>,
+}
+
+impl FireReport {
+    /// FIRE number for the first projected year, i.e. in today's money.
+    pub fn current_fire_number(&self) -> Option<f64> {
+        self.years.first().map(|year| year.fire_number)
+    }
+
+    /// Average savings rate over the years that have income.
+    pub fn average_savings_rate(&self) -> Option<f64> {
+        let rates: Vec<f64> = self
+            .years
+            .iter()
+            .filter_map(|year| year.savings_rate())
+            .collect();
+        (!rates.is_empty()).then(|| rates.iter().sum::<f64>() / rates.len() as f64)
+    }
+}
+
+/// Compares the projected balance with the FIRE number of every year up to `end_age`.
+/// `withdrawal_rate` is the safe withdrawal rate, e.g. 0.04.
+pub fn fire_report(
+    simulator: &mut LifeSimulator,
+    variant: FireVariant,
+    withdrawal_rate: f64,
+    end_age: u32,
+) -> FireReport {
+    let years: Vec<FireYear> = (simulator.get_current_age()..=end_age)
+        .map(|age| {
+            let totals = simulator.yearly_totals(age, None);
+            FireYear {
+                age,
+                balance: simulator.calculate_balance_at_age(age),
+                incomes: totals.incomes,
+                expenses: totals.expenses,
+                fire_number: totals.expenses * variant.spending_multiplier() / withdrawal_rate,
+            }
+        })
+        .collect();
+    let fire_age = years
+        .iter()
+        .find(|year| year.is_independent())
+        .map(|year| year.age);
+
+    FireReport {
+        variant,
+        withdrawal_rate,
+        years,
+        fire_age,
+    }
+}
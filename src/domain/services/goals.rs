@@ -0,0 +1,63 @@
+use crate::domain::entities::goal::Goal;
+use crate::domain::services::life_simulator::LifeSimulator;
+
+/// How a goal compares with the projected balance.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GoalProgress {
+    pub goal: Goal,
+    /// Projected balance at the goal's age.
+    pub projected: f64,
+    /// Extra saving per month from today that would close the shortfall, or `None` when the
+    /// goal's age has already been reached.
+    pub required_monthly_saving: Option<f64>,
+}
+
+impl GoalProgress {
+    pub fn is_met(&self) -> bool {
+        self.projected >= self.goal.target_amount
+    }
+
+    pub fn shortfall(&self) -> f64 {
+        (self.goal.target_amount - self.projected).max(0.0)
+    }
+
+    /// Projected balance as a share of the target, capped at 1.
+    pub fn fraction(&self) -> f64 {
+        if self.goal.target_amount <= 0.0 {
+            1.0
+        } else {
+            (self.projected / self.goal.target_amount).clamp(0.0, 1.0)
+        }
+    }
+}
+
+/// Evaluates every goal against the projected balance of `simulator`.
+pub fn evaluate_goals(simulator: &mut LifeSimulator, goals: &[Goal]) -> Vec<GoalProgress> {
+    let current_age = simulator.get_current_age();
+    let annual_return = simulator.get_assumptions().return_at(current_age);
+
+    goals
+        .iter()
+        .map(|goal| {
+            let projected = simulator.calculate_balance_at_age(goal.by_age);
+            let years = goal.by_age.saturating_sub(current_age);
+            let shortfall = (goal.target_amount - projected).max(0.0);
+
+            // A yearly extra saving grows like an annuity at the expected return
+            let required_monthly_saving = (years > 0).then(|| {
+                let growth = if annual_return == 0.0 {
+                    years as f64
+                } else {
+                    ((1.0 + annual_return).powi(years as i32) - 1.0) / annual_return
+                };
+                shortfall / growth / 12.0
+            });
+
+            GoalProgress {
+                goal: goal.clone(),
+                projected,
+                required_monthly_saving,
+            }
+        })
+        .collect()
+}
@@ -1,23 +1,395 @@
-use crate::domain::{
-    entities::person::{Expense, Frequency, Person},
-    person::Income,
+use crate::domain::entities::{
+    account::Account,
+    assumptions::Assumptions,
+    category::{Category, CategoryBreakdown, CategoryTotals},
+    child::Child,
+    credit_line::CreditLine,
+    household::{Household, Member},
+    pension::Pension,
+    person::{Expense, Income, Person},
+    property::Property,
+    simulation_config::SimulationConfig,
 };
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
 
+/// What happened in one simulated year besides the change in balance.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct YearResult {
+    /// Amount taken from the portfolio, once retired.
+    pub withdrawal: Option<f64>,
+    /// Interest charged on the credit line during the year.
+    pub interest: f64,
+    /// Amount owed on the credit line at the end of the year.
+    pub debt: f64,
+    /// Tax and early-withdrawal penalties paid on money taken out of the accounts.
+    pub tax: f64,
+    /// Value of the properties held at the end of the year less what is owed on them. It is not
+    /// part of the balance, which only counts what can be spent.
+    pub equity: f64,
+    /// Expenses left unpaid because the withdrawal strategy spent less than they need.
+    pub unpaid: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LifeSimulator {
     person: Person,
+    #[serde(default)]
+    assumptions: Assumptions,
+    /// Liquid balance the person wants to keep at all times; dropping below it raises an alert.
+    #[serde(default)]
+    cash_buffer: f64,
+    /// Borrowing that covers shortfalls; without one the balance simply goes negative.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    credit_line: Option<CreditLine>,
+    /// Tax-advantaged accounts, in the order savings are paid into them.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    accounts: Vec<Account>,
+    /// Homes and rental properties, counted in the balance at their value less the mortgage.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    properties: Vec<Property>,
+    /// Partner, children and shared expenses that draw on the same joint balance.
+    #[serde(default, skip_serializing_if = "Household::is_empty")]
+    household: Household,
+    /// Ages to project over and the length of the simulation steps.
+    #[serde(default)]
+    simulation: SimulationConfig,
+    // Result of each year starting at the key age, filled alongside the balance history
+    #[serde(skip)]
+    year_results: BTreeMap<u32, YearResult>,
+    // Balance of each account at the key age, for the projected ages
+    #[serde(skip)]
+    account_balances: BTreeMap<u32, Vec<f64>>,
+    // Stamp of the last edit, unique across simulators, so branches can tell when to rebuild
+    #[serde(skip)]
+    revision: u64,
 }
 
 impl LifeSimulator {
     pub fn new(person: Person) -> Self {
-        LifeSimulator { person }
+        LifeSimulator {
+            person,
+            assumptions: Assumptions::default(),
+            cash_buffer: 0.0,
+            credit_line: None,
+            accounts: Vec::new(),
+            properties: Vec::new(),
+            household: Household::new(),
+            simulation: SimulationConfig::default(),
+            year_results: BTreeMap::new(),
+            account_balances: BTreeMap::new(),
+            revision: 0,
+        }
+    }
+
+    pub fn get_assumptions(&self) -> &Assumptions {
+        &self.assumptions
+    }
+
+    pub fn set_assumptions(&mut self, assumptions: Assumptions) {
+        if self.assumptions != assumptions {
+            self.assumptions = assumptions;
+            self.invalidate();
+        }
+    }
+
+    pub fn get_simulation(&self) -> &SimulationConfig {
+        &self.simulation
+    }
+
+    /// Only a different time step changes the balances; the ages shown are read on demand.
+    pub fn set_simulation(&mut self, simulation: SimulationConfig) {
+        let time_step_changed = self.simulation.time_step != simulation.time_step;
+        self.simulation = simulation;
+        if time_step_changed {
+            self.invalidate();
+        }
+    }
+
+    pub fn get_cash_buffer(&self) -> f64 {
+        self.cash_buffer
+    }
+
+    /// Changes only what counts as a shortfall, so cached balances stay valid.
+    pub fn set_cash_buffer(&mut self, cash_buffer: f64) {
+        self.cash_buffer = cash_buffer;
+    }
+
+    pub fn get_credit_line(&self) -> Option<&CreditLine> {
+        self.credit_line.as_ref()
+    }
+
+    pub fn set_credit_line(&mut self, credit_line: Option<CreditLine>) {
+        if self.credit_line != credit_line {
+            self.credit_line = credit_line;
+            self.invalidate();
+        }
+    }
+
+    pub fn get_accounts(&self) -> &[Account] {
+        &self.accounts
+    }
+
+    /// Replaces the accounts; their order is the order savings are paid into them.
+    pub fn set_accounts(&mut self, accounts: Vec<Account>) {
+        if self.accounts != accounts {
+            self.accounts = accounts;
+            self.invalidate();
+        }
+    }
+
+    pub fn get_properties(&self) -> &[Property] {
+        &self.properties
+    }
+
+    pub fn set_properties(&mut self, properties: Vec<Property>) {
+        if self.properties != properties {
+            self.properties = properties;
+            self.invalidate();
+        }
+    }
+
+    // Prices in the purchase year of `property` relative to today; past purchases are taken at
+    // the price paid
+    fn purchase_price_level(&self, property: &Property) -> f64 {
+        self.assumptions
+            .inflation_factor(property.purchase_age.saturating_sub(self.person.age))
+    }
+
+    /// Value of the properties held at `age` less what is owed on their mortgages.
+    pub fn property_equity_at(&self, age: u32) -> f64 {
+        self.properties
+            .iter()
+            .map(|property| property.equity_at(age, self.purchase_price_level(property)))
+            .sum()
+    }
+
+    // Down payments on properties bought and proceeds of those sold in the year starting at `age`
+    fn property_transactions(&self, age: u32) -> f64 {
+        self.properties
+            .iter()
+            .map(|property| {
+                let price_level = self.purchase_price_level(property);
+                let mut amount = 0.0;
+                if property.purchase_age == age {
+                    amount -= property.cash_price(price_level);
+                }
+                if property.sale_age == Some(age) {
+                    amount += property.sale_proceeds(price_level);
+                }
+                amount
+            })
+            .sum()
+    }
+
+    // Adds the mortgage instalments, running costs and rents of the properties held in the year
+    // starting at `age`
+    fn add_properties(&self, breakdown: &mut CategoryBreakdown, age: u32, price_level: f64) {
+        for property in self.properties.iter().filter(|p| p.is_held_during(age)) {
+            let purchase_level = self.purchase_price_level(property);
+            if property.is_repaying_during(age) {
+                breakdown.add_expense(
+                    &property.mortgage_category(),
+                    property.mortgage_payment(purchase_level),
+                );
+            }
+            let costs = property.running_costs_at(age, purchase_level);
+            if costs > 0.0 {
+                breakdown.add_expense(&property.costs_category(), costs);
+            }
+            if let Some(rent) = property.rental_income {
+                breakdown.add_income(&property.rent_category(), rent * price_level);
+            }
+        }
+    }
+
+    /// Swaps in a different person while keeping the assumptions and settings.
+    pub fn set_person(&mut self, person: Person) {
+        self.person = person;
+        self.invalidate();
+    }
+
+    pub fn get_household(&self) -> &Household {
+        &self.household
+    }
+
+    pub fn set_household(&mut self, household: Household) {
+        self.household = household;
+        self.sync_categories();
+        self.invalidate();
+    }
+
+    pub fn add_member(&mut self, member: Member) {
+        self.household.members.push(member);
+        self.sync_categories();
+        self.invalidate();
+    }
+
+    pub fn remove_member(&mut self, index: usize) -> Option<Member> {
+        if index >= self.household.members.len() {
+            return None;
+        }
+        let member = self.household.members.remove(index);
+        self.invalidate();
+        Some(member)
+    }
+
+    /// Adds an expense to a member, with start and end given in the member's own ages.
+    pub fn add_member_expense(&mut self, index: usize, expense: Expense) {
+        if let Some(member) = self.household.members.get_mut(index) {
+            member.person.add_expense(expense);
+            self.sync_categories();
+            self.invalidate();
+        }
+    }
+
+    /// Adds an income to a member, with start and end given in the member's own ages.
+    pub fn add_member_income(&mut self, index: usize, income: Income) {
+        if let Some(member) = self.household.members.get_mut(index) {
+            member.person.add_income(income);
+            self.sync_categories();
+            self.invalidate();
+        }
+    }
+
+    pub fn add_child(&mut self, child: Child) {
+        self.household.children.push(child);
+        self.sync_categories();
+        self.invalidate();
+    }
+
+    pub fn remove_child(&mut self, index: usize) -> Option<Child> {
+        if index >= self.household.children.len() {
+            return None;
+        }
+        let child = self.household.children.remove(index);
+        self.invalidate();
+        Some(child)
+    }
+
+    /// Adds an expense of the whole household, timed by the main person's ages.
+    pub fn add_shared_expense(&mut self, expense: Expense) {
+        self.household.shared_expenses.push(expense);
+        self.sync_categories();
+        self.invalidate();
+    }
+
+    /// Renames a category for the main person and the household alike.
+    pub fn rename_category(&mut self, category: &Category, new_name: &str) {
+        self.person.rename_category(category, new_name);
+        let renamed = match category.parent() {
+            Some(parent) => parent.child(new_name),
+            None => Category::parse(new_name),
+        };
+        self.household.rename_category(category, &renamed);
+        self.touch();
+    }
+
+    /// Removes a category subtree for the main person and the household alike.
+    pub fn remove_category(&mut self, category: &Category) {
+        self.person.remove_category(category);
+        self.household.remove_category(category);
+        self.touch();
+    }
+
+    /// Adds the category of every item, the household's included, to the main person's tree,
+    /// which is what items are picked and filtered with.
+    pub fn sync_categories(&mut self) {
+        let person = &self.person;
+        let own = person
+            .expenses
+            .iter()
+            .map(|e| e.category.clone())
+            .chain(person.incomes.iter().map(|i| i.category.clone()));
+        let categories: Vec<_> = own.chain(self.household.categories()).collect();
+        for category in categories {
+            if !category.is_uncategorized() {
+                self.person.categories.add(category);
+            }
+        }
     }
 
     pub fn add_expense(&mut self, expense: Expense) {
         self.person.add_expense(expense);
+        self.invalidate();
     }
 
     pub fn add_income(&mut self, income: Income) {
         self.person.add_income(income);
+        self.invalidate();
+    }
+
+    pub fn add_pension(&mut self, pension: Pension) {
+        self.person.add_pension(pension);
+        self.invalidate();
+    }
+
+    pub fn remove_pension(&mut self, index: usize) -> Option<Pension> {
+        if index >= self.person.pensions.len() {
+            return None;
+        }
+        let pension = self.person.pensions.remove(index);
+        self.invalidate();
+        Some(pension)
+    }
+
+    /// Earnings a pension of `person` is worked out from: the recorded history, then the
+    /// person's earned incomes in today's money from their current age until they retire.
+    pub fn pension_earnings(&self, person: &Person, pension: &Pension) -> Vec<(u32, f64)> {
+        let mut earnings = pension.history.clone();
+        for age in person.age..pension.claiming_age {
+            if self.assumptions.is_retired_at(age) || earnings.iter().any(|(at, _)| *at == age) {
+                continue;
+            }
+            let earned: f64 = person
+                .incomes
+                .iter()
+                .filter(|i| i.earned && i.is_active_at(age))
+                .map(|i| i.yearly_amount())
+                .sum();
+            earnings.push((age, earned));
+        }
+        earnings
+    }
+
+    /// The income a pension of `person` pays, in today's money.
+    pub fn pension_income(&self, person: &Person, pension: &Pension) -> Income {
+        pension.income(&self.pension_earnings(person, pension))
+    }
+
+    // Adds the pensions of `person`, who is `age` in the year looked at
+    fn add_pensions(
+        &self,
+        breakdown: &mut CategoryBreakdown,
+        person: &Person,
+        age: u32,
+        price_level: f64,
+    ) {
+        for pension in &person.pensions {
+            let income = self.pension_income(person, pension);
+            if income.is_active_at(age) {
+                let indexation = pension.indexation_factor(age, self.assumptions.inflation);
+                breakdown.add_income(
+                    &income.category,
+                    income.yearly_amount() * price_level * indexation,
+                );
+            }
+        }
+    }
+
+    /// Drops every cached balance except the starting one. Must be called after the
+    /// person's items change, otherwise previously projected ages would be served stale.
+    pub fn invalidate(&mut self) {
+        self.touch();
+        self.person.balance_history.clear();
+        self.year_results.clear();
+        self.account_balances.clear();
+        let in_accounts: f64 = self.accounts.iter().map(|account| account.balance).sum();
+        let starting_balance = self.person.capital + self.household.capital() + in_accounts;
+        self.person
+            .balance_history
+            .insert(self.person.age, starting_balance);
     }
 
     pub fn calculate_balance_at_age(&mut self, target_age: u32) -> f64 {
@@ -43,13 +415,28 @@ impl LifeSimulator {
         // Find the closest age that we have a balance for
         let mut current_age = self.person.age;
         let mut current_balance = self.person.get_current_balance();
+        let mut previous = YearResult::default();
+        let mut accounts: Vec<f64> = self
+            .accounts
+            .iter()
+            .map(|account| account.balance)
+            .collect();
 
         // Determine direction of calculation
         if target_age > current_age {
             // Calculate forward in time
             while current_age < target_age {
-                current_balance = self.calculate_balance_for_year(current_age, current_balance);
+                (current_balance, previous, accounts) = self.calculate_balance_for_year(
+                    current_age,
+                    current_balance,
+                    &previous,
+                    &accounts,
+                );
+                self.year_results.insert(current_age, previous);
                 current_age += 1;
+                if !accounts.is_empty() {
+                    self.account_balances.insert(current_age, accounts.clone());
+                }
 
                 // Store intermediate balances for efficiency
                 self.person
@@ -69,49 +456,326 @@ impl LifeSimulator {
         current_balance
     }
 
-    fn calculate_balance_for_year(&self, current_age: u32, current_balance: f64) -> f64 {
-        let mut yearly_expenses = 0.0;
-        let mut yearly_incomes = 0.0;
+    /// Steps one year from `current_age` in the configured time steps, given the result of the
+    /// year before and the balance of each account. Returns the balance a year later (cash and
+    /// accounts minus credit line debt), what happened during the year and the new account
+    /// balances.
+    fn calculate_balance_for_year(
+        &self,
+        current_age: u32,
+        current_balance: f64,
+        previous: &YearResult,
+        accounts: &[f64],
+    ) -> (f64, YearResult, Vec<f64>) {
+        let totals = self.category_totals_for_year(current_age).total();
+        let time_step = self.simulation.time_step;
+        let steps = time_step.steps_per_year();
+        // Share of the year's incomes and expenses booked in each step
+        let share = 1.0 / steps as f64;
+        let step_return = time_step.step_rate(self.assumptions.return_at(current_age));
+        let account_returns: Vec<f64> = self
+            .accounts
+            .iter()
+            .map(|account| {
+                let annual = self
+                    .assumptions
+                    .account_return_at(account.glide_path.as_ref(), current_age);
+                time_step.step_rate(annual)
+            })
+            .collect();
+        let step_interest = self
+            .credit_line
+            .as_ref()
+            .map_or(0.0, |line| time_step.step_rate(line.interest_rate));
 
-        for expense in &self.person.expenses {
-            if current_age >= expense.start_age {
-                if let Some(end_age) = expense.end_age {
-                    if current_age >= end_age {
-                        continue; // Expense has ended
-                    }
-                }
+        let mut accounts = accounts.to_vec();
+        let mut debt = previous.debt;
+        // Buying and selling property moves money between cash and equity at the start of the year
+        let mut cash = current_balance + debt - accounts.iter().sum::<f64>()
+            + self.property_transactions(current_age);
+        let mut interest = 0.0;
+        let mut tax = 0.0;
 
-                // Calculate yearly expense based on frequency
-                let yearly_expense_amount = match expense.frequency {
-                    Frequency::Yearly => expense.amount,
-                    Frequency::Monthly => expense.amount * 12.0,
-                    Frequency::Daily => expense.amount * 365.0,
-                };
+        // In retirement the strategy's withdrawal is what gets spent; incomes that still arrive
+        // (pensions, rents) reduce the need and any surplus is reinvested. Expenses above the
+        // withdrawal go unpaid until the portfolio is emptied, after which they run it down
+        let retired = self.assumptions.is_retired_at(current_age);
+        let need = totals.expenses - totals.incomes;
+        let portfolio = cash + accounts.iter().sum::<f64>();
+        let withdrawal = retired.then(|| {
+            self.assumptions.withdrawal.withdrawal(
+                current_age,
+                portfolio,
+                need,
+                previous.withdrawal,
+                &self.assumptions,
+            )
+        });
+        let spent = withdrawal.map(|amount| {
+            if amount < portfolio {
+                amount
+            } else {
+                amount.max(need)
+            }
+        });
+        let unpaid = spent.map_or(0.0, |spent| (need - spent).max(0.0));
+
+        // Savings are paid into the accounts until retiring, taken from the year's surplus
+        let contributions = match withdrawal {
+            None => self.account_contributions(current_age, -need),
+            Some(_) => vec![(0.0, 0.0); accounts.len()],
+        };
+        let saved: f64 = contributions.iter().map(|(cost, _)| cost).sum();
+
+        for _ in 0..steps {
+            let step_charge = debt * step_interest;
+
+            for ((balance, (_, paid_in)), step_return) in accounts
+                .iter_mut()
+                .zip(&contributions)
+                .zip(&account_returns)
+            {
+                *balance += *balance * step_return + paid_in * share;
+            }
 
-                yearly_expenses += yearly_expense_amount;
+            // Savings grow with the expected return, debt does not
+            let investment_return = cash.max(0.0) * step_return;
+
+            cash = match spent {
+                // Add income for the year and subtract expenses
+                None => cash + investment_return - totals.expenses * share + totals.incomes * share,
+                Some(spent) => cash + investment_return - spent * share + (-need).max(0.0) * share,
+            };
+            cash -= step_charge + saved * share;
+            interest += step_charge;
+
+            // Shortfalls are drawn from the accounts, net of tax, before borrowing
+            if cash < 0.0 {
+                let (drawn, paid) = self.draw_from_accounts(current_age, -cash, &mut accounts);
+                cash += drawn;
+                tax += paid;
+            }
+
+            // Shortfalls are borrowed up to the limit and surpluses repay the debt first
+            if let Some(line) = &self.credit_line {
+                if cash < 0.0 {
+                    let borrowed = (-cash).min(line.available(debt));
+                    debt += borrowed;
+                    cash += borrowed;
+                } else {
+                    let repaid = cash.min(debt);
+                    debt -= repaid;
+                    cash -= repaid;
+                }
             }
         }
 
-        for income in &self.person.incomes {
-            if current_age >= income.start_age {
-                if let Some(end_age) = income.end_age {
-                    if current_age >= end_age {
-                        continue; // Expense has ended
-                    }
+        let equity = self.property_equity_at(current_age + 1);
+        let result = YearResult {
+            withdrawal,
+            interest,
+            debt,
+            tax,
+            equity,
+            unpaid,
+        };
+        (cash - debt + accounts.iter().sum::<f64>(), result, accounts)
+    }
+
+    /// What the savings of the year starting at `age` pay into each account in priority order:
+    /// the net pay used and the amount paid in, employer match included.
+    fn account_contributions(&self, age: u32, savings: f64) -> Vec<(f64, f64)> {
+        let price_level = self
+            .assumptions
+            .inflation_factor(age.saturating_sub(self.person.age));
+        let earned_income = self
+            .person
+            .incomes
+            .iter()
+            .filter(|i| i.earned && i.is_active_at(age))
+            .map(|i| i.yearly_amount() * price_level)
+            .sum();
+        let mut left = savings.max(0.0);
+        self.accounts
+            .iter()
+            .map(|account| {
+                let (cost, paid_in) = account.contribute(left, price_level);
+                left -= cost;
+                let matched = account
+                    .employer_match
+                    .as_ref()
+                    .map_or(0.0, |employer| employer.matched(paid_in, earned_income));
+                (cost, paid_in + matched)
+            })
+            .collect()
+    }
+
+    /// Takes up to `need` after tax out of the accounts, those without an early-withdrawal
+    /// penalty at `age` first. Returns the amount received and the tax and penalties paid.
+    fn draw_from_accounts(&self, age: u32, need: f64, balances: &mut [f64]) -> (f64, f64) {
+        let mut received = 0.0;
+        let mut paid = 0.0;
+        for penalized in [false, true] {
+            for (account, balance) in self.accounts.iter().zip(balances.iter_mut()) {
+                let net_share = account.net_share(age);
+                if account.is_penalized_at(age) != penalized || net_share <= 0.0 {
+                    continue;
                 }
+                let gross = ((need - received) / net_share).min(balance.max(0.0));
+                *balance -= gross;
+                received += gross * net_share;
+                paid += gross * (1.0 - net_share);
             }
+        }
+        (received, paid)
+    }
 
-            let yearly_income_amount = match income.frequency {
-                Frequency::Yearly => income.amount,
-                Frequency::Monthly => income.amount * 12.0,
-                Frequency::Daily => income.amount * 365.0,
-            };
+    /// Balance of each account at `age`, projecting up to it if needed.
+    pub fn account_balances_at(&mut self, age: u32) -> Vec<f64> {
+        if age <= self.person.age {
+            return self
+                .accounts
+                .iter()
+                .map(|account| account.balance)
+                .collect();
+        }
+        self.calculate_balance_at_age(age);
+        self.account_balances.get(&age).cloned().unwrap_or_default()
+    }
+
+    /// First age in `path`, given as `(age, balance)` pairs, at which the money has run out: the
+    /// balance is below zero, or has just fallen to exactly zero. A path that starts at zero has
+    /// not run out until it goes negative.
+    pub fn depletion_age(path: &[(u32, f64)]) -> Option<u32> {
+        path.iter()
+            .enumerate()
+            .find(|(i, (_, balance))| {
+                let fell_to_zero = *i > 0 && path[i - 1].1 > 0.0 && *balance <= 0.0;
+                *balance < 0.0 || fell_to_zero
+            })
+            .map(|(_, (age, _))| *age)
+    }
+
+    /// What happened in the year starting at `age`. Only available for projected ages.
+    pub fn year_result(&self, age: u32) -> Option<&YearResult> {
+        self.year_results.get(&age)
+    }
+
+    /// Amount withdrawn from the portfolio in the retirement year starting at `age`.
+    /// Only available for ages that have been projected.
+    pub fn withdrawal_at(&self, age: u32) -> Option<f64> {
+        self.year_results
+            .get(&age)
+            .and_then(|result| result.withdrawal)
+    }
+
+    /// What the expenses at `age` need after the incomes that are still paid.
+    pub fn spending_need_at(&self, age: u32) -> f64 {
+        let totals = self.category_totals_for_year(age).total();
+        totals.expenses - totals.incomes
+    }
+
+    /// Yearly expense and income amounts at `age`, keyed by the category of each item.
+    /// Amounts are inflated from today's money and earned incomes stop at retirement.
+    pub fn category_totals_for_year(&self, age: u32) -> CategoryBreakdown {
+        let mut breakdown = CategoryBreakdown::new();
+        let price_level = self
+            .assumptions
+            .inflation_factor(age.saturating_sub(self.person.age));
+        let retired = self.assumptions.is_retired_at(age);
+
+        for expense in self.person.expenses.iter().filter(|e| e.is_active_at(age)) {
+            breakdown.add_expense(&expense.category, expense.yearly_amount() * price_level);
+        }
+
+        for income in self
+            .person
+            .incomes
+            .iter()
+            .filter(|i| i.is_active_at(age) && !(retired && i.earned))
+        {
+            breakdown.add_income(&income.category, income.yearly_amount() * price_level);
+        }
+        self.add_pensions(&mut breakdown, &self.person, age, price_level);
+        self.add_properties(&mut breakdown, age, price_level);
+
+        // Members are older or younger, so their items are looked up at their own age
+        for member in &self.household.members {
+            let member_age = member.age_when(self.person.age, age);
+            let member_retired = self.assumptions.is_retired_at(member_age);
+            let person = &member.person;
+            for expense in person
+                .expenses
+                .iter()
+                .filter(|e| e.is_active_at(member_age))
+            {
+                breakdown.add_expense(&expense.category, expense.yearly_amount() * price_level);
+            }
+            for income in person
+                .incomes
+                .iter()
+                .filter(|i| i.is_active_at(member_age) && !(member_retired && i.earned))
+            {
+                breakdown.add_income(&income.category, income.yearly_amount() * price_level);
+            }
+            self.add_pensions(&mut breakdown, person, member_age, price_level);
+        }
+
+        for expense in self
+            .household
+            .shared_expenses
+            .iter()
+            .filter(|e| e.is_active_at(age))
+        {
+            breakdown.add_expense(&expense.category, expense.yearly_amount() * price_level);
+        }
+
+        for child in &self.household.children {
+            for expense in child.expenses().iter().filter(|e| e.is_active_at(age)) {
+                breakdown.add_expense(&expense.category, expense.yearly_amount() * price_level);
+            }
+            for income in child.incomes().iter().filter(|i| i.is_active_at(age)) {
+                breakdown.add_income(&income.category, income.yearly_amount() * price_level);
+            }
+        }
 
-            yearly_incomes += yearly_income_amount;
+        breakdown
+    }
+
+    /// Category totals summed over the years `from_age..to_age`.
+    pub fn category_totals_between(&self, from_age: u32, to_age: u32) -> CategoryBreakdown {
+        let mut breakdown = CategoryBreakdown::new();
+        for age in from_age..to_age {
+            breakdown.merge(&self.category_totals_for_year(age));
         }
+        breakdown
+    }
 
-        // Add income for the year and subtract expenses
-        current_balance - yearly_expenses + yearly_incomes
+    /// Yearly totals at `age`, optionally restricted to one category and its descendants.
+    pub fn yearly_totals(&self, age: u32, filter: Option<&Category>) -> CategoryTotals {
+        let breakdown = self.category_totals_for_year(age);
+        match filter {
+            Some(category) => breakdown.within(category),
+            None => breakdown.total(),
+        }
+    }
+
+    /// Projects the balance for every age from the current one up to `end_age`.
+    pub fn balance_series(&mut self, end_age: u32) -> Vec<(u32, f64)> {
+        (self.person.age..=end_age)
+            .map(|age| (age, self.calculate_balance_at_age(age)))
+            .collect()
+    }
+
+    /// Balance at `age` plus the equity of the properties held then.
+    pub fn net_worth_at(&mut self, age: u32) -> f64 {
+        self.calculate_balance_at_age(age) + self.property_equity_at(age)
+    }
+
+    pub fn net_worth_series(&mut self, end_age: u32) -> Vec<(u32, f64)> {
+        (self.person.age..=end_age)
+            .map(|age| (age, self.net_worth_at(age)))
+            .collect()
     }
 
     pub fn get_person(&self) -> &Person {
@@ -119,9 +783,20 @@ impl LifeSimulator {
     }
 
     pub fn get_person_mut(&mut self) -> &mut Person {
+        self.touch();
         &mut self.person
     }
 
+    /// Changes whenever the person, household or settings may have been edited.
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    fn touch(&mut self) {
+        static NEXT_REVISION: AtomicU64 = AtomicU64::new(1);
+        self.revision = NEXT_REVISION.fetch_add(1, Ordering::Relaxed);
+    }
+
     pub fn get_balance_history(&self) -> &std::collections::HashMap<u32, f64> {
         &self.person.balance_history
     }
@@ -0,0 +1,86 @@
+use crate::domain::entities::mortality::Mortality;
+use crate::domain::services::life_simulator::LifeSimulator;
+
+/// A projection weighed by the chance of being alive at each age.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LongevityReport {
+    /// Chance of being alive at each age from today, ending with the first age nobody reaches.
+    pub survival: Vec<(u32, f64)>,
+    /// Expected age at death.
+    pub life_expectancy: f64,
+    /// Chance of still being alive when the money runs out.
+    pub ruin_probability: f64,
+    /// Balance expected to be left at death; a negative balance leaves nothing.
+    pub expected_bequest: f64,
+    /// Age at which the money runs out in the plain projection.
+    pub depletion_age: Option<u32>,
+}
+
+impl LongevityReport {
+    /// Chance of being alive at `age`; zero past the end of the curve.
+    pub fn survival_at(&self, age: u32) -> f64 {
+        self.survival
+            .iter()
+            .find(|(at, _)| *at == age)
+            .map_or(0.0, |(_, alive)| *alive)
+    }
+}
+
+/// Chance of outliving the money and expected bequest over several balance paths, each given as
+/// `(age, balance)` pairs from today and counted equally. The money runs out as decided by
+/// [`LifeSimulator::depletion_age`]. The plain projection is a single path; randomised runs can be passed
+/// instead to weigh market and longevity risk together.
+pub fn weigh_paths(paths: &[Vec<(u32, f64)>], survival: &[(u32, f64)]) -> (f64, f64) {
+    if paths.is_empty() {
+        return (0.0, 0.0);
+    }
+    let alive_at = |age: u32| {
+        survival
+            .iter()
+            .find(|(at, _)| *at == age)
+            .map_or(0.0, |(_, alive)| *alive)
+    };
+
+    let mut ruin = 0.0;
+    let mut bequest = 0.0;
+    for path in paths {
+        if let Some(age) = LifeSimulator::depletion_age(path) {
+            ruin += alive_at(age);
+        }
+        // Dying during the year from `age` leaves the balance at its end; ages past the end of
+        // the path keep its last balance
+        let balance_at = |age: u32| {
+            path.iter()
+                .find(|(at, _)| *at == age)
+                .or(path.last())
+                .map_or(0.0, |(_, balance)| *balance)
+        };
+        for pair in survival.windows(2) {
+            let (age, alive) = pair[0];
+            let deaths = alive - pair[1].1;
+            bequest += deaths * balance_at(age + 1).max(0.0);
+        }
+    }
+    let runs = paths.len() as f64;
+    (ruin / runs, bequest / runs)
+}
+
+/// Weighs the projection of `simulator` by `mortality`, projecting until nobody is left.
+pub fn longevity_report(
+    simulator: &mut LifeSimulator,
+    mortality: &Mortality,
+) -> Result<LongevityReport, String> {
+    let current_age = simulator.get_current_age();
+    let survival = mortality.survival_from(current_age)?;
+    let last_age = survival.last().map_or(current_age, |(age, _)| *age);
+    let path = simulator.balance_series(last_age);
+    let (ruin_probability, expected_bequest) = weigh_paths(std::slice::from_ref(&path), &survival);
+
+    Ok(LongevityReport {
+        life_expectancy: mortality.life_expectancy(current_age)?,
+        ruin_probability,
+        expected_bequest,
+        depletion_age: LifeSimulator::depletion_age(&path),
+        survival,
+    })
+}
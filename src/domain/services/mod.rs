@@ -1,2 +1,24 @@
+pub mod alerts;
+pub mod comparison;
+pub mod fire;
+pub mod goals;
 pub mod life_simulator;
-pub use life_simulator::LifeSimulator;
+pub mod longevity;
+pub mod projection;
+pub mod recurring;
+pub mod retirement;
+pub mod scenario;
+pub mod what_if;
+pub use alerts::{BalanceAlert, ShortfallPeriod, balance_alert};
+pub use comparison::{BalanceDelta, buy_vs_rent, compare_balances};
+pub use fire::{FireReport, FireVariant, FireYear, fire_report};
+pub use goals::{GoalProgress, evaluate_goals};
+pub use life_simulator::{LifeSimulator, YearResult};
+pub use longevity::{LongevityReport, longevity_report, weigh_paths};
+pub use projection::{ProjectionRow, configured_projection, projection};
+pub use recurring::{
+    RecurringItem, detect_recurring, normalize_description, person_from_transactions,
+};
+pub use retirement::{RetirementReport, RetirementYear, retirement_report};
+pub use scenario::{Scenario, ScenarioSet};
+pub use what_if::{ItemRef, WhatIf, WhatIfOutcome};
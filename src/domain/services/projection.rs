@@ -0,0 +1,68 @@
+use crate::domain::entities::simulation_config::SimulationConfig;
+use crate::domain::services::life_simulator::LifeSimulator;
+
+/// One year of the projection as listed in reports: the balance at that age and the
+/// incomes and expenses booked during the year.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProjectionRow {
+    pub age: u32,
+    pub balance: f64,
+    pub incomes: f64,
+    pub expenses: f64,
+    /// Change of the balance since the previous age; zero for the first row.
+    pub net_change: f64,
+}
+
+/// Projects the simulator from its current age up to `end_age`.
+pub fn projection(simulator: &mut LifeSimulator, end_age: u32) -> Vec<ProjectionRow> {
+    let series = simulator.balance_series(end_age);
+    let mut previous = None;
+    series
+        .into_iter()
+        .map(|(age, balance)| {
+            let totals = simulator.yearly_totals(age, None);
+            let row = ProjectionRow {
+                age,
+                balance,
+                incomes: totals.incomes,
+                expenses: totals.expenses,
+                net_change: previous.map_or(0.0, |previous| balance - previous),
+            };
+            previous = Some(balance);
+            row
+        })
+        .collect()
+}
+
+/// Projects the simulator over `config`: one row at each listed age from the start age to the
+/// end age. Incomes and expenses are summed over the years up to the next row.
+pub fn configured_projection(
+    simulator: &mut LifeSimulator,
+    config: &SimulationConfig,
+) -> Vec<ProjectionRow> {
+    let ages = config.output_ages(simulator.get_current_age());
+    let rows = projection(simulator, config.end_age());
+    let mut previous: Option<f64> = None;
+    ages.iter()
+        .enumerate()
+        .filter_map(|(index, &age)| {
+            let next_age = ages.get(index + 1).copied().unwrap_or(age + 1);
+            let period = rows
+                .iter()
+                .filter(|row| row.age >= age && row.age < next_age);
+            let (incomes, expenses) = period.fold((0.0, 0.0), |(incomes, expenses), row| {
+                (incomes + row.incomes, expenses + row.expenses)
+            });
+            let balance = rows.iter().find(|row| row.age == age)?.balance;
+            let row = ProjectionRow {
+                age,
+                balance,
+                incomes,
+                expenses,
+                net_change: previous.map_or(0.0, |previous| balance - previous),
+            };
+            previous = Some(balance);
+            Some(row)
+        })
+        .collect()
+}
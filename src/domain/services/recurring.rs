@@ -0,0 +1,194 @@
+use crate::domain::entities::category::Category;
+use crate::domain::entities::person::{Expense, Frequency, Income, Person};
+use crate::domain::entities::transaction::Transaction;
+use std::collections::BTreeMap;
+
+// Keywords in a transaction description that hint at one of the default categories
+const CATEGORY_HINTS: [(&str, &str); 14] = [
+    ("rent", "Housing > Rent"),
+    ("mortgage", "Housing > Mortgage"),
+    ("electric", "Housing > Utilities"),
+    ("water", "Housing > Utilities"),
+    ("energy", "Housing > Utilities"),
+    ("salary", "Work > Salary"),
+    ("payroll", "Work > Salary"),
+    ("wage", "Work > Salary"),
+    ("bonus", "Work > Bonus"),
+    ("fuel", "Transport > Fuel"),
+    ("transit", "Transport > Public Transport"),
+    ("supermarket", "Food > Groceries"),
+    ("gym", "Health"),
+    ("insurance", "Health"),
+];
+
+// Keywords of subscriptions, filed under Leisure > Subscriptions
+const SUBSCRIPTION_HINTS: [&str; 6] = [
+    "netflix",
+    "spotify",
+    "disney",
+    "prime",
+    "subscription",
+    "youtube",
+];
+
+/// A group of transactions that repeat often enough to become an expense or income.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecurringItem {
+    pub name: String,
+    pub is_income: bool,
+    pub frequency: Frequency,
+    /// Average amount per period, always positive.
+    pub amount: f64,
+    pub occurrences: usize,
+    pub category: Category,
+}
+
+impl RecurringItem {
+    pub fn yearly_amount(&self) -> f64 {
+        self.amount * self.frequency.periods_per_year()
+    }
+
+    pub fn to_expense(&self, start_age: u32) -> Expense {
+        Expense::new(
+            self.name.clone(),
+            self.amount,
+            self.frequency.clone(),
+            start_age,
+            None,
+        )
+        .with_category(self.category.clone())
+    }
+
+    pub fn to_income(&self, start_age: u32) -> Income {
+        Income::new(
+            self.name.clone(),
+            self.amount,
+            self.frequency.clone(),
+            start_age,
+            None,
+        )
+        .with_category(self.category.clone())
+    }
+}
+
+/// Groups transactions by their description, direction and booked category and keeps the
+/// groups that repeat at a regular interval. Weekly and biweekly payments have no frequency of
+/// their own and become monthly ones, and quarterly payments become yearly ones. The result is
+/// sorted by yearly amount, largest first.
+pub fn detect_recurring(transactions: &[Transaction]) -> Vec<RecurringItem> {
+    let mut groups: BTreeMap<(String, bool, String), Vec<&Transaction>> = BTreeMap::new();
+    for transaction in transactions {
+        if transaction.amount == 0.0 {
+            continue;
+        }
+        let key = normalize_description(&transaction.description);
+        if key.is_empty() {
+            continue;
+        }
+        groups
+            .entry((
+                key,
+                transaction.is_income(),
+                transaction.category.to_string(),
+            ))
+            .or_default()
+            .push(transaction);
+    }
+
+    let mut items: Vec<RecurringItem> = groups
+        .into_iter()
+        .filter_map(|((key, is_income, _), mut group)| {
+            group.sort_by_key(|transaction| transaction.date);
+            let mut gaps: Vec<i64> = group
+                .windows(2)
+                .map(|pair| pair[1].date.days() - pair[0].date.days())
+                .collect();
+            if gaps.is_empty() {
+                return None;
+            }
+            gaps.sort_unstable();
+            let median_gap = gaps[gaps.len() / 2];
+
+            let average = group.iter().map(|t| t.amount.abs()).sum::<f64>() / group.len() as f64;
+            let (frequency, amount) = match median_gap {
+                0..=2 if group.len() >= 14 => (Frequency::Daily, average),
+                5..=9 if group.len() >= 4 => (Frequency::Monthly, average * 52.0 / 12.0),
+                12..=16 if group.len() >= 3 => (Frequency::Monthly, average * 26.0 / 12.0),
+                25..=35 if group.len() >= 3 => (Frequency::Monthly, average),
+                85..=97 => (Frequency::Yearly, average * 4.0),
+                350..=380 => (Frequency::Yearly, average),
+                _ => return None,
+            };
+
+            Some(RecurringItem {
+                name: title_case(&key),
+                is_income,
+                frequency,
+                amount,
+                occurrences: group.len(),
+                category: if group[0].category.is_uncategorized() {
+                    guess_category(&key, is_income)
+                } else {
+                    group[0].category.clone()
+                },
+            })
+        })
+        .collect();
+
+    items.sort_by(|a, b| b.yearly_amount().total_cmp(&a.yearly_amount()));
+    items
+}
+
+/// Builds a person whose expenses and incomes are the recurring items found in `transactions`,
+/// all starting at the person's current age.
+pub fn person_from_transactions(
+    name: String,
+    age: u32,
+    capital: f64,
+    transactions: &[Transaction],
+) -> Person {
+    let mut person = Person::new(name, age, capital);
+    for item in detect_recurring(transactions) {
+        if item.is_income {
+            person.add_income(item.to_income(age));
+        } else {
+            person.add_expense(item.to_expense(age));
+        }
+    }
+    person
+}
+
+/// Reduces a bank description to the words that stay the same between payments,
+/// dropping reference numbers, dates and punctuation.
+pub fn normalize_description(description: &str) -> String {
+    description
+        .to_lowercase()
+        .split(|c: char| !c.is_alphabetic())
+        .filter(|word| word.len() > 1)
+        .take(4)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn guess_category(key: &str, is_income: bool) -> Category {
+    if !is_income && SUBSCRIPTION_HINTS.iter().any(|hint| key.contains(hint)) {
+        return Category::parse("Leisure > Subscriptions");
+    }
+    CATEGORY_HINTS
+        .iter()
+        .filter(|(_, path)| path.starts_with("Work") == is_income)
+        .find(|(hint, _)| key.contains(hint))
+        .map_or_else(Category::uncategorized, |(_, path)| Category::parse(path))
+}
+
+fn title_case(key: &str) -> String {
+    key.split(' ')
+        .map(|word| {
+            let mut chars = word.chars();
+            chars.next().map_or_else(String::new, |first| {
+                first.to_uppercase().chain(chars).collect()
+            })
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
@@ -0,0 +1,83 @@
+use crate::domain::services::life_simulator::LifeSimulator;
+
+/// One year of retirement as projected by the simulator.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetirementYear {
+    pub age: u32,
+    /// Portfolio at the start of the year.
+    pub balance: f64,
+    /// What the expenses need after the incomes that are still paid.
+    pub need: f64,
+    /// What the strategy takes out of the portfolio and spends.
+    pub withdrawal: f64,
+    /// Part of the need left unpaid because the withdrawal was cut below it. Once the portfolio
+    /// is empty the need is paid regardless, running the balance negative.
+    pub shortfall: f64,
+}
+
+impl RetirementYear {
+    /// Part of the withdrawal spent beyond the need.
+    pub fn excess(&self) -> f64 {
+        (self.withdrawal - self.need.max(0.0)).max(0.0)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetirementReport {
+    pub retirement_age: u32,
+    pub years: Vec<RetirementYear>,
+    /// First age at which the portfolio is empty, or `None` if it lasts until the end age.
+    pub survival_age: Option<u32>,
+}
+
+impl RetirementReport {
+    pub fn total_withdrawn(&self) -> f64 {
+        self.years.iter().map(|year| year.withdrawal).sum()
+    }
+
+    /// Years in which the withdrawal left part of the expenses unpaid.
+    pub fn shortfall_years(&self) -> usize {
+        self.years
+            .iter()
+            .filter(|year| year.shortfall > 0.0)
+            .count()
+    }
+
+    /// Years in which more was withdrawn and spent than the expenses need.
+    pub fn excess_years(&self) -> usize {
+        self.years.iter().filter(|year| year.excess() > 0.0).count()
+    }
+}
+
+/// Projects the retirement phase up to `end_age`. Returns `None` if no retirement age is set.
+pub fn retirement_report(simulator: &mut LifeSimulator, end_age: u32) -> Option<RetirementReport> {
+    let retirement_age = simulator
+        .get_assumptions()
+        .retirement_age?
+        .max(simulator.get_current_age());
+
+    // Projecting to the end age fills in the withdrawal of every year before it
+    simulator.calculate_balance_at_age(end_age);
+
+    let years = (retirement_age..end_age)
+        .map(|age| RetirementYear {
+            age,
+            balance: simulator.calculate_balance_at_age(age),
+            need: simulator.spending_need_at(age),
+            withdrawal: simulator.withdrawal_at(age).unwrap_or_default(),
+            shortfall: simulator
+                .year_result(age)
+                .map_or(0.0, |result| result.unpaid),
+        })
+        .collect();
+    let path: Vec<(u32, f64)> = (retirement_age..=end_age)
+        .map(|age| (age, simulator.calculate_balance_at_age(age)))
+        .collect();
+    let survival_age = LifeSimulator::depletion_age(&path);
+
+    Some(RetirementReport {
+        retirement_age,
+        years,
+        survival_age,
+    })
+}
@@ -0,0 +1,414 @@
+use crate::domain::entities::{
+    branch::{Branch, BranchChange},
+    goal::Goal,
+    household::Household,
+    pension::Pension,
+    person::{Expense, Income, Person},
+};
+use crate::domain::services::life_simulator::LifeSimulator;
+use crate::domain::services::what_if::{ItemRef, WhatIf};
+use serde::{Deserialize, Serialize};
+
+/// A named plan: one person with their incomes and expenses. A scenario forked from another
+/// one keeps a `branch` describing how it differs, and its simulator is rebuilt from the parent.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Scenario {
+    pub name: String,
+    #[serde(flatten)]
+    pub simulator: LifeSimulator,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch: Option<Branch>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub goals: Vec<Goal>,
+    // What a branch was last rebuilt into, to tell direct edits from changes to the parent
+    #[serde(skip)]
+    built: Option<Built>,
+}
+
+#[derive(Debug, Clone)]
+struct Built {
+    parent_revision: u64,
+    revision: u64,
+    person: Person,
+    household: Household,
+}
+
+impl Scenario {
+    pub fn new(name: String, simulator: LifeSimulator) -> Self {
+        Scenario {
+            name,
+            simulator,
+            branch: None,
+            goals: Vec::new(),
+            built: None,
+        }
+    }
+}
+
+/// The scenarios open in one session, one of which is being edited.
+#[derive(Debug, Clone, Default)]
+pub struct ScenarioSet {
+    scenarios: Vec<Scenario>,
+    active: Option<usize>,
+}
+
+impl ScenarioSet {
+    pub fn new() -> Self {
+        ScenarioSet {
+            scenarios: Vec::new(),
+            active: None,
+        }
+    }
+
+    /// Adds a scenario, renaming it if the name is taken, and makes it the active one.
+    pub fn add(&mut self, mut scenario: Scenario) -> usize {
+        scenario.name = self.unique_name(&scenario.name);
+        self.scenarios.push(scenario);
+        let index = self.scenarios.len() - 1;
+        self.active = Some(index);
+        index
+    }
+
+    /// Creates a branch of the scenario at `index` that follows it until `fork_age`,
+    /// and makes the branch active.
+    pub fn fork(&mut self, index: usize, name: &str, fork_age: u32) -> Option<usize> {
+        let parent = self.scenarios.get(index)?;
+        let branch = Branch::new(parent.name.clone(), fork_age);
+        // The branch starts with the parent's assumptions and settings
+        let mut simulator = parent.simulator.clone();
+        simulator.set_person(branch.apply(parent.simulator.get_person()));
+        let mut scenario = Scenario::new(name.to_string(), simulator);
+        scenario.branch = Some(branch);
+        scenario.goals = parent.goals.clone();
+        let index = self.add(scenario);
+        // Built right away, so direct edits to the new branch are recorded from the start
+        self.refresh_branches();
+        Some(index)
+    }
+
+    /// Adds an expense to the active scenario, as a branch change if it is a branch.
+    pub fn add_expense(&mut self, expense: Expense) {
+        self.edit_active(|scenario| match &mut scenario.branch {
+            Some(branch) => branch.push(BranchChange::AddExpense(expense)),
+            None => scenario.simulator.add_expense(expense),
+        });
+    }
+
+    /// Adds an income to the active scenario, as a branch change if it is a branch.
+    pub fn add_income(&mut self, income: Income) {
+        self.edit_active(|scenario| match &mut scenario.branch {
+            Some(branch) => branch.push(BranchChange::AddIncome(income)),
+            None => scenario.simulator.add_income(income),
+        });
+    }
+
+    /// Adds a pension to the active scenario, as a branch change if it is a branch.
+    pub fn add_pension(&mut self, pension: Pension) {
+        self.edit_active(|scenario| match &mut scenario.branch {
+            Some(branch) => branch.push(BranchChange::AddPension(pension)),
+            None => scenario.simulator.add_pension(pension),
+        });
+    }
+
+    /// Removes a pension from the active scenario, as a branch change if it is a branch.
+    pub fn remove_pension(&mut self, index: usize) {
+        self.edit_active(|scenario| match &mut scenario.branch {
+            Some(branch) => {
+                let pensions = &scenario.simulator.get_person().pensions;
+                let Some(removed) = pensions.get(index) else {
+                    return;
+                };
+                // Pensions are removed by name, so the others sharing it are added back
+                let kept: Vec<Pension> = pensions
+                    .iter()
+                    .enumerate()
+                    .filter(|(i, p)| *i != index && p.name == removed.name)
+                    .map(|(_, p)| p.clone())
+                    .collect();
+                branch.push(BranchChange::RemovePension {
+                    name: removed.name.clone(),
+                });
+                for pension in kept {
+                    branch.push(BranchChange::AddPension(pension));
+                }
+            }
+            None => {
+                scenario.simulator.remove_pension(index);
+            }
+        });
+    }
+
+    /// Makes a what-if variation the new state of the active scenario. In a branch, a changed
+    /// item amount is recorded as a replacement from the fork age.
+    pub fn save_what_if(&mut self, what_if: &WhatIf) {
+        self.edit_active(|scenario| {
+            scenario
+                .simulator
+                .set_assumptions(what_if.assumptions.clone());
+            let Some((item, amount)) = what_if.item_amount else {
+                return;
+            };
+            let person = scenario.simulator.get_person_mut();
+            match (&mut scenario.branch, item) {
+                (Some(branch), ItemRef::Expense(index)) => {
+                    if let Some(expense) = person.expenses.get(index) {
+                        let mut replacement = expense.clone();
+                        replacement.amount = amount;
+                        branch.push(BranchChange::ReplaceExpense {
+                            name: expense.name.clone(),
+                            with: Some(replacement),
+                        });
+                    }
+                }
+                (Some(branch), ItemRef::Income(index)) => {
+                    if let Some(income) = person.incomes.get(index) {
+                        let mut replacement = income.clone();
+                        replacement.amount = amount;
+                        branch.push(BranchChange::ReplaceIncome {
+                            name: income.name.clone(),
+                            with: Some(replacement),
+                        });
+                    }
+                }
+                (None, ItemRef::Expense(index)) => {
+                    if let Some(expense) = person.expenses.get_mut(index) {
+                        expense.amount = amount;
+                    }
+                }
+                (None, ItemRef::Income(index)) => {
+                    if let Some(income) = person.incomes.get_mut(index) {
+                        income.amount = amount;
+                    }
+                }
+            }
+            scenario.simulator.invalidate();
+        });
+    }
+
+    /// Records a change on the active scenario if it is a branch.
+    pub fn push_branch_change(&mut self, change: BranchChange) {
+        self.edit_active(|scenario| {
+            if let Some(branch) = &mut scenario.branch {
+                branch.push(change);
+            }
+        });
+    }
+
+    /// Drops the change at `position` from the active branch.
+    pub fn remove_branch_change(&mut self, position: usize) {
+        self.edit_active(|scenario| {
+            if let Some(branch) = &mut scenario.branch
+                && position < branch.changes.len()
+            {
+                branch.changes.remove(position);
+            }
+        });
+    }
+
+    fn edit_active(&mut self, edit: impl FnOnce(&mut Scenario)) {
+        // Direct edits made before this one are recorded first
+        self.refresh_branches();
+        if let Some(scenario) = self.active.and_then(|index| self.scenarios.get_mut(index)) {
+            edit(scenario);
+            // The branch changes are what changed, so the branch is rebuilt from them
+            scenario.built = None;
+            self.refresh_branches();
+        }
+    }
+
+    /// Rebuilds every branch whose parent changed since the last refresh, so edits to a parent
+    /// show up in its children unless the children override them. Edits made directly to a
+    /// branch since it was last built are recorded as branch changes first.
+    pub fn refresh_branches(&mut self) {
+        // Parents are always rebuilt before their children, however deep the chain goes
+        let mut rebuilt: Vec<usize> = Vec::new();
+        let mut progress = true;
+        while progress {
+            progress = false;
+            for index in 0..self.scenarios.len() {
+                let Some(branch) = &self.scenarios[index].branch else {
+                    continue;
+                };
+                let Some(parent) = self.scenarios.iter().position(|s| s.name == branch.parent)
+                else {
+                    continue;
+                };
+                let parent_pending = self.scenarios[parent].branch.is_some()
+                    && !rebuilt.contains(&parent)
+                    && self.has_parent_in_set(parent);
+                if rebuilt.contains(&index) || parent_pending {
+                    continue;
+                }
+                self.rebuild_branch(index, parent);
+                rebuilt.push(index);
+                progress = true;
+            }
+        }
+    }
+
+    fn rebuild_branch(&mut self, index: usize, parent: usize) {
+        let parent_simulator = &self.scenarios[parent].simulator;
+        let parent_revision = parent_simulator.revision();
+        let parent_person = parent_simulator.get_person().clone();
+        let parent_household = parent_simulator.get_household().clone();
+
+        let scenario = &mut self.scenarios[index];
+        let Some(branch) = &mut scenario.branch else {
+            return;
+        };
+        let revision = scenario.simulator.revision();
+        // Only rebuild on actual changes so projected balances stay cached
+        if let Some(built) = &scenario.built {
+            if built.revision == revision && built.parent_revision == parent_revision {
+                return;
+            }
+            if built.revision != revision {
+                let simulator = &scenario.simulator;
+                branch.record_edits(&parent_person, &built.person, simulator.get_person());
+                let household = simulator.get_household();
+                if serde_json::to_value(household).ok()
+                    != serde_json::to_value(&built.household).ok()
+                {
+                    branch.push(BranchChange::Household(household.clone()));
+                }
+            }
+        }
+
+        // The household is shared with the parent as a whole, unless the branch keeps its own
+        let person = branch.apply(&parent_person);
+        let household = branch.own_household().cloned().unwrap_or(parent_household);
+        let simulator = &mut scenario.simulator;
+        simulator.set_person(person);
+        simulator.set_household(household);
+        scenario.built = Some(Built {
+            parent_revision,
+            revision: simulator.revision(),
+            person: simulator.get_person().clone(),
+            household: simulator.get_household().clone(),
+        });
+    }
+
+    fn has_parent_in_set(&self, index: usize) -> bool {
+        self.scenarios[index]
+            .branch
+            .as_ref()
+            .is_some_and(|branch| self.scenarios.iter().any(|s| s.name == branch.parent))
+    }
+
+    /// Copies a scenario under a new name and makes the copy active.
+    pub fn duplicate(&mut self, index: usize) -> Option<usize> {
+        let mut copy = self.scenarios.get(index)?.clone();
+        copy.name = format!("{} (copy)", copy.name);
+        Some(self.add(copy))
+    }
+
+    pub fn remove(&mut self, index: usize) -> Option<Scenario> {
+        if index >= self.scenarios.len() {
+            return None;
+        }
+        let removed = self.scenarios.remove(index);
+        // Children of a removed scenario keep their current items as a standalone plan
+        for scenario in self.scenarios.iter_mut() {
+            if scenario
+                .branch
+                .as_ref()
+                .is_some_and(|branch| branch.parent == removed.name)
+            {
+                scenario.branch = None;
+            }
+        }
+        self.active = match self.active {
+            _ if self.scenarios.is_empty() => None,
+            Some(active) if active > index => Some(active - 1),
+            Some(active) if active == index => Some(index.min(self.scenarios.len() - 1)),
+            other => other,
+        };
+        Some(removed)
+    }
+
+    pub fn rename(&mut self, index: usize, name: &str) {
+        let Some(old_name) = self.scenarios.get(index).map(|s| s.name.clone()) else {
+            return;
+        };
+        if old_name == name.trim() {
+            return;
+        }
+        let name = self.unique_name(name);
+        for scenario in self.scenarios.iter_mut() {
+            if let Some(branch) = &mut scenario.branch
+                && branch.parent == old_name
+            {
+                branch.parent = name.clone();
+            }
+        }
+        self.scenarios[index].name = name;
+    }
+
+    pub fn select(&mut self, index: usize) {
+        if index < self.scenarios.len() {
+            self.active = Some(index);
+        }
+    }
+
+    pub fn active_index(&self) -> Option<usize> {
+        self.active
+    }
+
+    pub fn active_scenario(&self) -> Option<&Scenario> {
+        self.active.and_then(|index| self.scenarios.get(index))
+    }
+
+    pub fn active_scenario_mut(&mut self) -> Option<&mut Scenario> {
+        self.active.and_then(|index| self.scenarios.get_mut(index))
+    }
+
+    pub fn active(&self) -> Option<&LifeSimulator> {
+        self.active_scenario().map(|scenario| &scenario.simulator)
+    }
+
+    pub fn active_mut(&mut self) -> Option<&mut LifeSimulator> {
+        self.active
+            .and_then(|index| self.scenarios.get_mut(index))
+            .map(|scenario| &mut scenario.simulator)
+    }
+
+    pub fn get(&self, index: usize) -> Option<&Scenario> {
+        self.scenarios.get(index)
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut Scenario> {
+        self.scenarios.get_mut(index)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Scenario> {
+        self.scenarios.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut Scenario> {
+        self.scenarios.iter_mut()
+    }
+
+    pub fn len(&self) -> usize {
+        self.scenarios.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.scenarios.is_empty()
+    }
+
+    fn unique_name(&self, name: &str) -> String {
+        let base = if name.trim().is_empty() {
+            "Scenario"
+        } else {
+            name.trim()
+        };
+        let taken = |candidate: &str| self.scenarios.iter().any(|s| s.name == candidate);
+        if !taken(base) {
+            return base.to_string();
+        }
+        (2..)
+            .map(|n| format!("{} {}", base, n))
+            .find(|candidate| !taken(candidate))
+            .unwrap()
+    }
+}
@@ -0,0 +1,92 @@
+use crate::domain::entities::assumptions::Assumptions;
+use crate::domain::services::life_simulator::LifeSimulator;
+
+/// Points at one income or expense of a person by its position.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ItemRef {
+    Expense(usize),
+    Income(usize),
+}
+
+/// A temporary variation of a scenario that is explored without changing it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WhatIf {
+    pub assumptions: Assumptions,
+    pub end_age: u32,
+    /// A selected item and the amount per period it is tried with.
+    pub item_amount: Option<(ItemRef, f64)>,
+}
+
+impl WhatIf {
+    /// A what-if that matches the scenario exactly.
+    pub fn from_simulator(simulator: &LifeSimulator, end_age: u32) -> Self {
+        WhatIf {
+            assumptions: simulator.get_assumptions().clone(),
+            end_age,
+            item_amount: None,
+        }
+    }
+
+    /// Builds a copy of `baseline` with this what-if's assumptions and item amount.
+    pub fn apply(&self, baseline: &LifeSimulator) -> LifeSimulator {
+        let mut simulator = baseline.clone();
+        simulator.set_assumptions(self.assumptions.clone());
+        if let Some((item, amount)) = self.item_amount {
+            let person = simulator.get_person_mut();
+            match item {
+                ItemRef::Expense(index) => {
+                    if let Some(expense) = person.expenses.get_mut(index) {
+                        expense.amount = amount;
+                    }
+                }
+                ItemRef::Income(index) => {
+                    if let Some(income) = person.incomes.get_mut(index) {
+                        income.amount = amount;
+                    }
+                }
+            }
+            simulator.invalidate();
+        }
+        simulator
+    }
+
+    /// Projects both the baseline and this what-if up to the end age.
+    pub fn evaluate(&self, baseline: &LifeSimulator) -> WhatIfOutcome {
+        let mut baseline = baseline.clone();
+        let mut varied = self.apply(&baseline);
+        WhatIfOutcome {
+            baseline: baseline.balance_series(self.end_age),
+            what_if: varied.balance_series(self.end_age),
+        }
+    }
+}
+
+/// Balance series of a baseline and its what-if variation, aligned by age.
+#[derive(Debug, Clone, Default)]
+pub struct WhatIfOutcome {
+    pub baseline: Vec<(u32, f64)>,
+    pub what_if: Vec<(u32, f64)>,
+}
+
+impl WhatIfOutcome {
+    pub fn final_delta(&self) -> f64 {
+        match (self.baseline.last(), self.what_if.last()) {
+            (Some((_, baseline)), Some((_, what_if))) => what_if - baseline,
+            _ => 0.0,
+        }
+    }
+
+    /// Difference between the what-if and the baseline at `age`, if both reach it.
+    pub fn delta_at(&self, age: u32) -> Option<f64> {
+        let find = |series: &[(u32, f64)]| series.iter().find(|(a, _)| *a == age).map(|(_, b)| *b);
+        Some(find(&self.what_if)? - find(&self.baseline)?)
+    }
+
+    /// Lowest balance of the what-if series and the age it occurs at.
+    pub fn lowest_balance(&self) -> Option<(u32, f64)> {
+        self.what_if
+            .iter()
+            .copied()
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+    }
+}
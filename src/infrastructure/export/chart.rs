@@ -0,0 +1,614 @@
+use crate::domain::{Category, LifeSimulator};
+use crate::infrastructure::export::format::{compact_number, escape_xml};
+use crate::infrastructure::export::png::render_png;
+use std::fmt::{self, Write};
+use std::path::Path;
+
+// Space around the plot area for the axis labels and the legend
+const MARGIN_LEFT: f64 = 80.0;
+const MARGIN_RIGHT: f64 = 20.0;
+const MARGIN_TOP: f64 = 40.0;
+const MARGIN_BOTTOM: f64 = 40.0;
+
+/// What a chart line shows, so exports can keep the same lines as the screen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SeriesKind {
+    Balance,
+    NetWorth,
+    WhatIf,
+    Expenses,
+    Income,
+    Interest,
+    Debt,
+}
+
+impl SeriesKind {
+    pub const ALL: [SeriesKind; 7] = [
+        SeriesKind::Balance,
+        SeriesKind::NetWorth,
+        SeriesKind::WhatIf,
+        SeriesKind::Expenses,
+        SeriesKind::Income,
+        SeriesKind::Interest,
+        SeriesKind::Debt,
+    ];
+
+    /// Parses a series name as given on the command line.
+    pub fn parse(name: &str) -> Result<Self, String> {
+        SeriesKind::ALL
+            .into_iter()
+            .find(|kind| kind.to_string().eq_ignore_ascii_case(name.trim()))
+            .ok_or_else(|| {
+                format!(
+                    "Unknown series \"{}\" (expected balance, net-worth, what-if, expenses, income, interest or debt)",
+                    name
+                )
+            })
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            SeriesKind::Balance => "Balance",
+            SeriesKind::NetWorth => "Net worth",
+            SeriesKind::WhatIf => "What-if balance",
+            SeriesKind::Expenses => "Expenses",
+            SeriesKind::Income => "Income",
+            SeriesKind::Interest => "Interest",
+            SeriesKind::Debt => "Credit line debt",
+        }
+    }
+}
+
+impl fmt::Display for SeriesKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SeriesKind::Balance => write!(f, "balance"),
+            SeriesKind::NetWorth => write!(f, "net-worth"),
+            SeriesKind::WhatIf => write!(f, "what-if"),
+            SeriesKind::Expenses => write!(f, "expenses"),
+            SeriesKind::Income => write!(f, "income"),
+            SeriesKind::Interest => write!(f, "interest"),
+            SeriesKind::Debt => write!(f, "debt"),
+        }
+    }
+}
+
+/// One line of a chart.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChartSeries {
+    pub kind: SeriesKind,
+    pub name: String,
+    pub color: [u8; 3],
+    pub dashed: bool,
+    pub points: Vec<[f64; 2]>,
+}
+
+/// A line chart that can be drawn without a window, mirroring the balance plot of the
+/// Simulation tab.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chart {
+    pub title: String,
+    pub series: Vec<ChartSeries>,
+    /// Visible ages, or all of them when not set.
+    pub x_range: Option<[f64; 2]>,
+    /// Visible amounts, or all of them when not set.
+    pub y_range: Option<[f64; 2]>,
+}
+
+/// Image formats a chart can be written in.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ChartFormat {
+    Svg,
+    Png,
+}
+
+impl ChartFormat {
+    pub const ALL: [ChartFormat; 2] = [ChartFormat::Svg, ChartFormat::Png];
+
+    /// Picks the format from the file extension.
+    pub fn from_path(path: &Path) -> Result<Self, String> {
+        let extension = path
+            .extension()
+            .map(|extension| extension.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        ChartFormat::ALL
+            .into_iter()
+            .find(|format| format.extension() == extension)
+            .ok_or_else(|| {
+                format!(
+                    "Cannot tell the image format of {} (expected .svg or .png)",
+                    path.display()
+                )
+            })
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ChartFormat::Svg => "svg",
+            ChartFormat::Png => "png",
+        }
+    }
+
+    /// Renders `chart` at `width` x `height`; PNG images get `scale` pixels per unit.
+    pub fn render(&self, chart: &Chart, width: u32, height: u32, scale: u32) -> Vec<u8> {
+        match self {
+            ChartFormat::Svg => chart.to_svg(width, height).into_bytes(),
+            ChartFormat::Png => chart.to_png(width, height, scale),
+        }
+    }
+}
+
+/// Where text is placed relative to its position.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Anchor {
+    Start,
+    Middle,
+    End,
+}
+
+/// Drawing primitive shared by the SVG and PNG renderers.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Shape {
+    Rect {
+        x: f64,
+        y: f64,
+        width: f64,
+        height: f64,
+        color: [u8; 3],
+    },
+    Line {
+        points: Vec<[f64; 2]>,
+        color: [u8; 3],
+        width: f64,
+        dashed: bool,
+        /// Whether the line is cut off at the plot area.
+        clipped: bool,
+    },
+    /// Text with its baseline at `y`.
+    Text {
+        x: f64,
+        y: f64,
+        text: String,
+        size: f64,
+        bold: bool,
+        anchor: Anchor,
+    },
+}
+
+/// A chart laid out for a given size, with the plot area as `[x, y, width, height]`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Scene {
+    pub width: f64,
+    pub height: f64,
+    pub plot_area: [f64; 4],
+    pub shapes: Vec<Shape>,
+}
+
+impl Chart {
+    /// Balance, yearly expenses and incomes per age up to `end_age`, plus the net worth with
+    /// property equity when there is property and the interest and debt of the credit line when
+    /// there is one. `filter` limits expenses and incomes to a
+    /// category like the Simulation tab does.
+    pub fn balance(
+        title: &str,
+        simulator: &mut LifeSimulator,
+        end_age: u32,
+        filter: Option<&Category>,
+    ) -> Self {
+        let balances = simulator.balance_series(end_age);
+        let mut incomes = Vec::new();
+        let mut expenses = Vec::new();
+        let mut interest = Vec::new();
+        let mut debt = Vec::new();
+        for &(age, _) in &balances {
+            let totals = simulator.yearly_totals(age, filter);
+            incomes.push([age as f64, totals.incomes]);
+            expenses.push([age as f64, totals.expenses]);
+            if simulator.get_credit_line().is_some()
+                && let Some(result) = simulator.year_result(age)
+            {
+                interest.push([age as f64, result.interest]);
+                debt.push([age as f64 + 1.0, result.debt]);
+            }
+        }
+
+        let mut series = vec![ChartSeries {
+            kind: SeriesKind::Balance,
+            name: "Balance over time".to_string(),
+            color: [31, 119, 180],
+            dashed: false,
+            points: balances
+                .iter()
+                .map(|&(age, balance)| [age as f64, balance])
+                .collect(),
+        }];
+        if !simulator.get_properties().is_empty() {
+            series.push(ChartSeries {
+                kind: SeriesKind::NetWorth,
+                name: "Net worth with property".to_string(),
+                color: [140, 86, 75],
+                dashed: false,
+                points: simulator
+                    .net_worth_series(end_age)
+                    .iter()
+                    .map(|&(age, net_worth)| [age as f64, net_worth])
+                    .collect(),
+            });
+        }
+        series.extend([
+            ChartSeries {
+                kind: SeriesKind::Expenses,
+                name: match filter {
+                    Some(category) => format!("Expenses: {}", category),
+                    None => "Total Expenses".to_string(),
+                },
+                color: [214, 39, 40],
+                dashed: true,
+                points: expenses,
+            },
+            ChartSeries {
+                kind: SeriesKind::Income,
+                name: match filter {
+                    Some(category) => format!("Income: {}", category),
+                    None => "Total Income".to_string(),
+                },
+                color: [44, 160, 44],
+                dashed: true,
+                points: incomes,
+            },
+        ]);
+        if !interest.is_empty() {
+            series.push(ChartSeries {
+                kind: SeriesKind::Interest,
+                name: "Interest".to_string(),
+                color: [200, 120, 0],
+                dashed: true,
+                points: interest,
+            });
+            series.push(ChartSeries {
+                kind: SeriesKind::Debt,
+                name: "Credit line debt".to_string(),
+                color: [150, 60, 150],
+                dashed: false,
+                points: debt,
+            });
+        }
+
+        Chart {
+            title: title.to_string(),
+            series,
+            x_range: None,
+            y_range: None,
+        }
+    }
+
+    /// Adds the balance of a what-if projection after the baseline balance.
+    pub fn with_what_if(mut self, balances: &[(u32, f64)]) -> Self {
+        self.series.insert(
+            1.min(self.series.len()),
+            ChartSeries {
+                kind: SeriesKind::WhatIf,
+                name: "What-if balance".to_string(),
+                color: [255, 215, 0],
+                dashed: false,
+                points: balances
+                    .iter()
+                    .map(|&(age, balance)| [age as f64, balance])
+                    .collect(),
+            },
+        );
+        self
+    }
+
+    /// Keeps only the series of the given kinds.
+    pub fn retain(mut self, kinds: &[SeriesKind]) -> Self {
+        self.series.retain(|series| kinds.contains(&series.kind));
+        self
+    }
+
+    /// Drops the points before `age`, e.g. to start at the configured start age.
+    pub fn starting_at(mut self, age: u32) -> Self {
+        for series in &mut self.series {
+            series.points.retain(|point| point[0] >= age as f64);
+        }
+        self
+    }
+
+    /// Limits the drawn area, e.g. to the zoom of the plot on screen.
+    pub fn with_view(mut self, x_range: Option<[f64; 2]>, y_range: Option<[f64; 2]>) -> Self {
+        self.x_range = x_range;
+        self.y_range = y_range;
+        self
+    }
+
+    /// Smallest and largest x and y over all series, as `([x_min, x_max], [y_min, y_max])`.
+    /// Zero is always included on the y axis.
+    pub fn bounds(&self) -> ([f64; 2], [f64; 2]) {
+        let points = self.series.iter().flat_map(|series| &series.points);
+        let mut x = [f64::INFINITY, f64::NEG_INFINITY];
+        let mut y = [0.0_f64, 0.0_f64];
+        for point in points {
+            x = [x[0].min(point[0]), x[1].max(point[0])];
+            y = [y[0].min(point[1]), y[1].max(point[1])];
+        }
+        if !x[0].is_finite() {
+            x = [0.0, 1.0];
+        }
+        if x[0] == x[1] {
+            x[1] += 1.0;
+        }
+        if y[0] == y[1] {
+            y[1] += 1.0;
+        }
+        (x, y)
+    }
+
+    /// The drawn ranges: the view where set, the bounds of the data otherwise.
+    pub fn view(&self) -> ([f64; 2], [f64; 2]) {
+        let (x_bounds, y_bounds) = self.bounds();
+        let valid = |range: Option<[f64; 2]>| range.filter(|[min, max]| min < max);
+        (
+            valid(self.x_range).unwrap_or(x_bounds),
+            valid(self.y_range).unwrap_or(y_bounds),
+        )
+    }
+
+    /// Lays the chart out on a `width` x `height` canvas.
+    pub fn scene(&self, width: u32, height: u32) -> Scene {
+        let (x_range, y_range) = self.view();
+        let (width, height) = (width as f64, height as f64);
+        let plot_width = (width - MARGIN_LEFT - MARGIN_RIGHT).max(1.0);
+        let plot_height = (height - MARGIN_TOP - MARGIN_BOTTOM).max(1.0);
+        let to_x = |x: f64| MARGIN_LEFT + (x - x_range[0]) / (x_range[1] - x_range[0]) * plot_width;
+        let to_y = |y: f64| {
+            MARGIN_TOP + plot_height - (y - y_range[0]) / (y_range[1] - y_range[0]) * plot_height
+        };
+        let text = |x: f64, y: f64, text: String, anchor: Anchor| Shape::Text {
+            x,
+            y,
+            text,
+            size: 12.0,
+            bold: false,
+            anchor,
+        };
+        let rule = |points: Vec<[f64; 2]>, color: [u8; 3]| Shape::Line {
+            points,
+            color,
+            width: 1.0,
+            dashed: false,
+            clipped: false,
+        };
+
+        let mut shapes = vec![
+            Shape::Rect {
+                x: 0.0,
+                y: 0.0,
+                width,
+                height,
+                color: [255, 255, 255],
+            },
+            Shape::Text {
+                x: MARGIN_LEFT,
+                y: 20.0,
+                text: self.title.clone(),
+                size: 14.0,
+                bold: true,
+                anchor: Anchor::Start,
+            },
+        ];
+
+        // Grid lines with their labels
+        for tick in ticks(y_range[0], y_range[1]) {
+            let y = to_y(tick);
+            let color = if tick == 0.0 {
+                [136, 136, 136]
+            } else {
+                [221, 221, 221]
+            };
+            shapes.push(rule(
+                vec![[MARGIN_LEFT, y], [MARGIN_LEFT + plot_width, y]],
+                color,
+            ));
+            shapes.push(text(
+                MARGIN_LEFT - 6.0,
+                y + 4.0,
+                compact_number(tick),
+                Anchor::End,
+            ));
+        }
+        for tick in ticks(x_range[0], x_range[1]) {
+            let x = to_x(tick);
+            shapes.push(rule(
+                vec![[x, MARGIN_TOP], [x, MARGIN_TOP + plot_height]],
+                [238, 238, 238],
+            ));
+            shapes.push(text(
+                x,
+                MARGIN_TOP + plot_height + 18.0,
+                tick.round().to_string(),
+                Anchor::Middle,
+            ));
+        }
+        shapes.push(text(
+            MARGIN_LEFT + plot_width / 2.0,
+            height - 6.0,
+            "Age".to_string(),
+            Anchor::Middle,
+        ));
+
+        for series in &self.series {
+            shapes.push(Shape::Line {
+                points: series
+                    .points
+                    .iter()
+                    .map(|point| [to_x(point[0]), to_y(point[1])])
+                    .collect(),
+                color: series.color,
+                width: 2.0,
+                dashed: series.dashed,
+                clipped: true,
+            });
+        }
+
+        // Legend along the top right
+        let mut legend_x = width - MARGIN_RIGHT;
+        for series in self.series.iter().rev() {
+            let text_width = series.name.chars().count() as f64 * 7.0;
+            legend_x -= text_width + 30.0;
+            shapes.push(Shape::Line {
+                points: vec![[legend_x, 16.0], [legend_x + 18.0, 16.0]],
+                color: series.color,
+                width: 3.0,
+                dashed: false,
+                clipped: false,
+            });
+            shapes.push(text(
+                legend_x + 22.0,
+                20.0,
+                series.name.clone(),
+                Anchor::Start,
+            ));
+        }
+
+        Scene {
+            width,
+            height,
+            plot_area: [MARGIN_LEFT, MARGIN_TOP, plot_width, plot_height],
+            shapes,
+        }
+    }
+
+    /// Renders the chart as a standalone SVG document.
+    pub fn to_svg(&self, width: u32, height: u32) -> String {
+        let scene = self.scene(width, height);
+        let mut svg = String::new();
+        let _ = writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}" font-family="sans-serif" font-size="12">"#,
+            w = scene.width,
+            h = scene.height
+        );
+        let [x, y, plot_width, plot_height] = scene.plot_area;
+        let _ = writeln!(
+            svg,
+            r#"<clipPath id="plot-area"><rect x="{}" y="{}" width="{}" height="{}"/></clipPath>"#,
+            x, y, plot_width, plot_height
+        );
+
+        for shape in &scene.shapes {
+            match shape {
+                Shape::Rect {
+                    x,
+                    y,
+                    width,
+                    height,
+                    color,
+                } => {
+                    let _ = writeln!(
+                        svg,
+                        r#"<rect x="{}" y="{}" width="{}" height="{}" fill="{}"/>"#,
+                        x,
+                        y,
+                        width,
+                        height,
+                        hex_color(*color)
+                    );
+                }
+                Shape::Line {
+                    points,
+                    color,
+                    width,
+                    dashed,
+                    clipped,
+                } => {
+                    let points: Vec<String> = points
+                        .iter()
+                        .map(|point| format!("{:.1},{:.1}", point[0], point[1]))
+                        .collect();
+                    let _ = writeln!(
+                        svg,
+                        r#"<polyline{} fill="none" stroke="{}" stroke-width="{}"{} points="{}"/>"#,
+                        if *clipped {
+                            r#" clip-path="url(#plot-area)""#
+                        } else {
+                            ""
+                        },
+                        hex_color(*color),
+                        width,
+                        if *dashed {
+                            r#" stroke-dasharray="6 3""#
+                        } else {
+                            ""
+                        },
+                        points.join(" ")
+                    );
+                }
+                Shape::Text {
+                    x,
+                    y,
+                    text,
+                    size,
+                    bold,
+                    anchor,
+                } => {
+                    let anchor = match anchor {
+                        Anchor::Start => "",
+                        Anchor::Middle => r#" text-anchor="middle""#,
+                        Anchor::End => r#" text-anchor="end""#,
+                    };
+                    let size = if *size == 12.0 {
+                        String::new()
+                    } else {
+                        format!(r#" font-size="{}""#, size)
+                    };
+                    let weight = if *bold { r#" font-weight="bold""# } else { "" };
+                    let _ = writeln!(
+                        svg,
+                        r#"<text x="{:.1}" y="{:.1}"{}{}{}>{}</text>"#,
+                        x,
+                        y,
+                        size,
+                        weight,
+                        anchor,
+                        escape_xml(text)
+                    );
+                }
+            }
+        }
+
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    /// Renders the chart as a PNG image of `width * scale` x `height * scale` pixels.
+    pub fn to_png(&self, width: u32, height: u32, scale: u32) -> Vec<u8> {
+        render_png(&self.scene(width, height), scale.max(1))
+    }
+}
+
+/// Evenly spaced round values covering `[min, max]`, about six of them.
+pub fn ticks(min: f64, max: f64) -> Vec<f64> {
+    let raw_step = (max - min) / 6.0;
+    if raw_step <= 0.0 || !raw_step.is_finite() {
+        return vec![min];
+    }
+    let magnitude = 10f64.powf(raw_step.log10().floor());
+    let step = [1.0, 2.0, 5.0, 10.0]
+        .into_iter()
+        .map(|factor| factor * magnitude)
+        .find(|step| *step >= raw_step)
+        .unwrap_or(10.0 * magnitude);
+
+    let mut ticks = Vec::new();
+    let mut tick = (min / step).ceil() * step;
+    while tick <= max + step * 1e-9 {
+        // Avoid printing -0
+        ticks.push(if tick.abs() < step * 1e-9 { 0.0 } else { tick });
+        tick += step;
+    }
+    ticks
+}
+
+fn hex_color([r, g, b]: [u8; 3]) -> String {
+    format!("#{:02x}{:02x}{:02x}", r, g, b)
+}
@@ -0,0 +1,56 @@
+/// Amount with thousands separators and two decimals, e.g. `-12,345.60`.
+pub fn format_amount(value: f64) -> String {
+    let text = format!("{:.2}", value.abs());
+    let (whole, fraction) = text.split_once('.').unwrap_or((&text, "00"));
+    let mut grouped = String::new();
+    for (index, digit) in whole.chars().enumerate() {
+        if index > 0 && (whole.len() - index) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    let sign = if value < 0.0 && text != "0.00" {
+        "-"
+    } else {
+        ""
+    };
+    format!("{}{}.{}", sign, grouped, fraction)
+}
+
+/// Rate as a percentage with one decimal, e.g. `4.0%`.
+pub fn format_percent(rate: f64) -> String {
+    format!("{:.1}%", rate * 100.0)
+}
+
+/// Short axis label such as `250k` or `1.5M`.
+pub fn compact_number(value: f64) -> String {
+    let magnitude = value.abs();
+    if magnitude >= 1e6 {
+        format!("{}M", trim_zeros(value / 1e6))
+    } else if magnitude >= 1e3 {
+        format!("{}k", trim_zeros(value / 1e3))
+    } else {
+        trim_zeros(value)
+    }
+}
+
+fn trim_zeros(value: f64) -> String {
+    let text = format!("{:.2}", value);
+    text.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+/// Escapes text for use inside XML and HTML elements and attributes.
+pub fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -0,0 +1,440 @@
+use crate::domain::{
+    Expense, Horizon, Income, LifeSimulator, Scenario, balance_alert, configured_projection,
+    evaluate_goals, longevity_report, projection, retirement_report,
+};
+use crate::infrastructure::export::chart::Chart;
+use crate::infrastructure::export::format::{escape_xml, format_amount, format_percent};
+use std::fmt::Write;
+
+const STYLE: &str = "body{font-family:sans-serif;margin:2em auto;max-width:1000px;color:#222}\
+h1{margin-bottom:0}h2{border-bottom:1px solid #ccc;padding-bottom:4px;margin-top:2em}\
+table{border-collapse:collapse;margin:0.5em 0}th,td{padding:3px 10px;border-bottom:1px solid #eee}\
+th{text-align:left;background:#f4f4f4}td.num{text-align:right;font-variant-numeric:tabular-nums}\
+.warning{background:#fbe9e9;border-left:4px solid #c33;padding:6px 10px;margin:6px 0}\
+.met{color:#282}.missed{color:#c33}.muted{color:#777}";
+
+/// Renders a scenario as a self-contained HTML page: summary, assumptions, every income and
+/// expense, the balance chart as inline SVG, the per-age table, goals and warnings.
+pub fn html_report(scenario: &mut Scenario, end_age: u32) -> String {
+    let simulator = &mut scenario.simulator;
+    let rows = projection(simulator, end_age);
+    // The table follows the scenario's start age and row spacing
+    let mut config = simulator.get_simulation().clone();
+    config.horizon = Horizon::EndAge(end_age);
+    let table = configured_projection(simulator, &config);
+    let chart = Chart::balance("Balance over time", simulator, end_age, None).to_svg(960, 400);
+    let goals = evaluate_goals(simulator, &scenario.goals);
+    let alert = balance_alert(simulator, end_age);
+    let retirement = retirement_report(simulator, end_age);
+    let mortality = simulator.get_simulation().mortality().cloned();
+    let longevity = mortality
+        .as_ref()
+        .and_then(|mortality| longevity_report(simulator, mortality).ok());
+    let person = simulator.get_person();
+    let household = simulator.get_household();
+    let assumptions = simulator.get_assumptions();
+
+    let mut html = String::new();
+    let _ = writeln!(
+        html,
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>{}</style>\n</head>\n<body>",
+        escape_xml(&scenario.name),
+        STYLE
+    );
+    let _ = writeln!(html, "<h1>{}</h1>", escape_xml(&scenario.name));
+    let _ = writeln!(
+        html,
+        "<p class=\"muted\">Projection from age {} to {}</p>",
+        person.age, end_age
+    );
+
+    // Summary
+    let _ = writeln!(html, "<h2>Summary</h2>\n<table>");
+    row(&mut html, "Name", &escape_xml(&person.name));
+    row(&mut html, "Age", &person.age.to_string());
+    row(
+        &mut html,
+        "Starting capital",
+        &format_amount(person.capital),
+    );
+    for member in &household.members {
+        row(
+            &mut html,
+            &member.role.to_string(),
+            &format!(
+                "{}, age {}, capital {}",
+                escape_xml(&member.person.name),
+                member.person.age,
+                format_amount(member.person.capital)
+            ),
+        );
+    }
+    for child in &household.children {
+        row(
+            &mut html,
+            "Child",
+            &format!(
+                "{}, born when you are {}",
+                escape_xml(&child.name),
+                child.born_at
+            ),
+        );
+    }
+    if let Some(last) = rows.last() {
+        row(
+            &mut html,
+            &format!("Balance at {}", last.age),
+            &format_amount(last.balance),
+        );
+    }
+    if let Some(longevity) = &longevity {
+        row(
+            &mut html,
+            "Life expectancy",
+            &format!("{:.1}", longevity.life_expectancy),
+        );
+        row(
+            &mut html,
+            "Chance of outliving the money",
+            &format_percent(longevity.ruin_probability),
+        );
+        row(
+            &mut html,
+            "Expected bequest",
+            &format_amount(longevity.expected_bequest),
+        );
+    }
+    let _ = writeln!(html, "</table>");
+
+    // Assumptions
+    let _ = writeln!(html, "<h2>Assumptions</h2>\n<table>");
+    match &assumptions.allocation {
+        Some(allocation) => {
+            let current_age = simulator.get_current_age();
+            row(
+                &mut html,
+                "Annual return",
+                &format!(
+                    "{} at age {}, following the glide path",
+                    format_percent(allocation.return_at(current_age)),
+                    current_age
+                ),
+            );
+            row(
+                &mut html,
+                "Asset allocation (stocks/bonds/cash %)",
+                &allocation.glide_path.to_string(),
+            );
+        }
+        None => row(
+            &mut html,
+            "Annual return",
+            &format_percent(assumptions.annual_return),
+        ),
+    }
+    row(
+        &mut html,
+        "Inflation",
+        &format_percent(assumptions.inflation),
+    );
+    row(
+        &mut html,
+        "Retirement age",
+        &assumptions
+            .retirement_age
+            .map_or("not set".to_string(), |age| age.to_string()),
+    );
+    row(
+        &mut html,
+        "Withdrawal strategy",
+        assumptions.withdrawal.name(),
+    );
+    if let Some(mortality) = &mortality {
+        row(&mut html, "Mortality", &escape_xml(&mortality.to_string()));
+    }
+    row(
+        &mut html,
+        "Minimum cash buffer",
+        &format_amount(simulator.get_cash_buffer()),
+    );
+    if let Some(credit_line) = simulator.get_credit_line() {
+        row(
+            &mut html,
+            "Credit line",
+            &format!(
+                "limit {} at {}",
+                format_amount(credit_line.limit),
+                format_percent(credit_line.interest_rate)
+            ),
+        );
+    }
+    for property in simulator.get_properties() {
+        let mut terms = format!(
+            "{} bought at {}, {} a year appreciation",
+            format_amount(property.purchase_price),
+            property.purchase_age,
+            format_percent(property.appreciation)
+        );
+        if let Some(mortgage) = &property.mortgage {
+            let _ = write!(
+                terms,
+                ", {} down and a mortgage at {} over {} years",
+                format_percent(property.down_payment),
+                format_percent(mortgage.interest_rate),
+                mortgage.years
+            );
+        }
+        if let Some(age) = property.sale_age {
+            let _ = write!(terms, ", sold at {}", age);
+        }
+        row(
+            &mut html,
+            &format!("Property: {}", escape_xml(&property.name)),
+            &terms,
+        );
+    }
+    let _ = writeln!(html, "</table>");
+
+    let (incomes, expenses) = items_by_owner(simulator);
+
+    let _ = writeln!(html, "<h2>Incomes</h2>");
+    item_table(
+        &mut html,
+        incomes.iter().map(|(owner, income)| {
+            (
+                owner.as_str(),
+                income.name.as_str(),
+                income.amount,
+                format!("{:?}", income.frequency),
+                income.start_age,
+                income.end_age,
+                income.category.to_string(),
+            )
+        }),
+    );
+    let _ = writeln!(html, "<h2>Expenses</h2>");
+    item_table(
+        &mut html,
+        expenses.iter().map(|(owner, expense)| {
+            (
+                owner.as_str(),
+                expense.name.as_str(),
+                expense.amount,
+                format!("{:?}", expense.frequency),
+                expense.start_age,
+                expense.end_age,
+                expense.category.to_string(),
+            )
+        }),
+    );
+
+    // Warnings
+    let _ = writeln!(html, "<h2>Warnings</h2>");
+    let mut warnings = Vec::new();
+    for period in &alert.periods {
+        let threshold = if alert.cash_buffer > 0.0 {
+            format!(
+                "below the cash buffer of {}",
+                format_amount(alert.cash_buffer)
+            )
+        } else {
+            "negative".to_string()
+        };
+        let until = period
+            .recovered_at
+            .map_or(format!("through age {}", end_age), |age| {
+                format!("until age {}", age)
+            });
+        warnings.push(format!(
+            "The balance is {} from age {} {}; lowest {} at age {}.",
+            threshold,
+            period.start_age,
+            until,
+            format_amount(period.lowest_balance),
+            period.lowest_age
+        ));
+    }
+    if let Some(report) = &retirement {
+        if let Some(age) = report.survival_age {
+            warnings.push(format!("The portfolio runs out at age {}.", age));
+        }
+        if report.shortfall_years() > 0 {
+            warnings.push(format!(
+                "The withdrawal leaves expenses unpaid in {} retirement years.",
+                report.shortfall_years()
+            ));
+        }
+        if report.excess_years() > 0 {
+            warnings.push(format!(
+                "The withdrawal is spent beyond the expenses in {} retirement years.",
+                report.excess_years()
+            ));
+        }
+    }
+    for goal in goals.iter().filter(|goal| !goal.is_met()) {
+        warnings.push(format!(
+            "Goal \"{}\" is missed by {}.",
+            escape_xml(&goal.goal.name),
+            format_amount(goal.shortfall())
+        ));
+    }
+    if warnings.is_empty() {
+        let _ = writeln!(html, "<p>No warnings.</p>");
+    }
+    for warning in warnings {
+        let _ = writeln!(html, "<div class=\"warning\">{}</div>", warning);
+    }
+
+    // Chart
+    let _ = writeln!(html, "<h2>Balance Chart</h2>\n{}", chart);
+
+    // Goals
+    let _ = writeln!(html, "<h2>Goals</h2>");
+    if goals.is_empty() {
+        let _ = writeln!(html, "<p>No goals set.</p>");
+    } else {
+        let _ = writeln!(
+            html,
+            "<table>\n<tr><th>Goal</th><th>Target</th><th>By age</th><th>Projected</th><th>Status</th><th>Extra saving / month</th></tr>"
+        );
+        for goal in &goals {
+            let (class, status) = if goal.is_met() {
+                ("met", "Met")
+            } else {
+                ("missed", "Missed")
+            };
+            let _ = writeln!(
+                html,
+                "<tr><td>{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td><td class=\"{}\">{}</td><td class=\"num\">{}</td></tr>",
+                escape_xml(&goal.goal.name),
+                format_amount(goal.goal.target_amount),
+                goal.goal.by_age,
+                format_amount(goal.projected),
+                class,
+                status,
+                match goal.required_monthly_saving {
+                    _ if goal.is_met() => "-".to_string(),
+                    Some(amount) => format_amount(amount),
+                    None => "too late".to_string(),
+                }
+            );
+        }
+        let _ = writeln!(html, "</table>");
+    }
+
+    // Per-age table
+    let _ = writeln!(
+        html,
+        "<h2>Per-Age Projection</h2>\n<table>\n<tr><th>Age</th><th>Capital</th><th>Total Expenses</th><th>Total Income</th><th>Net Change</th></tr>"
+    );
+    for projected in &table {
+        let _ = writeln!(
+            html,
+            "<tr><td>{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td></tr>",
+            projected.age,
+            format_amount(projected.balance),
+            format_amount(projected.expenses),
+            format_amount(projected.incomes),
+            format_amount(projected.net_change)
+        );
+    }
+    let _ = writeln!(html, "</table>\n</body>\n</html>");
+    html
+}
+
+/// Incomes and expenses, each with the name of its owner.
+pub type OwnedItems = (Vec<(String, Income)>, Vec<(String, Expense)>);
+
+/// Every income and expense of the scenario with the name of its owner, including those of
+/// other household members and the incomes of pensions; shared and children's items belong to
+/// "Household".
+pub fn items_by_owner(simulator: &LifeSimulator) -> OwnedItems {
+    let person = simulator.get_person();
+    let household = simulator.get_household();
+    let mut incomes: Vec<(String, Income)> = person
+        .incomes
+        .iter()
+        .map(|income| (person.name.clone(), income.clone()))
+        .chain(person.pensions.iter().map(|pension| {
+            (
+                person.name.clone(),
+                simulator.pension_income(person, pension),
+            )
+        }))
+        .collect();
+    let mut expenses: Vec<(String, Expense)> = person
+        .expenses
+        .iter()
+        .map(|expense| (person.name.clone(), expense.clone()))
+        .collect();
+    for member in &household.members {
+        incomes.extend(
+            member
+                .person
+                .incomes
+                .iter()
+                .map(|income| (member.person.name.clone(), income.clone())),
+        );
+        incomes.extend(member.person.pensions.iter().map(|pension| {
+            (
+                member.person.name.clone(),
+                simulator.pension_income(&member.person, pension),
+            )
+        }));
+        expenses.extend(
+            member
+                .person
+                .expenses
+                .iter()
+                .map(|expense| (member.person.name.clone(), expense.clone())),
+        );
+    }
+    expenses.extend(
+        household
+            .shared_expenses
+            .iter()
+            .cloned()
+            .chain(household.children.iter().flat_map(|child| child.expenses()))
+            .map(|expense| ("Household".to_string(), expense)),
+    );
+    incomes.extend(
+        household
+            .children
+            .iter()
+            .flat_map(|child| child.incomes())
+            .map(|income| ("Household".to_string(), income)),
+    );
+    (incomes, expenses)
+}
+
+fn row(html: &mut String, label: &str, value: &str) {
+    let _ = writeln!(html, "<tr><th>{}</th><td>{}</td></tr>", label, value);
+}
+
+type ItemRow<'a> = (&'a str, &'a str, f64, String, u32, Option<u32>, String);
+
+fn item_table<'a>(html: &mut String, items: impl Iterator<Item = ItemRow<'a>>) {
+    let mut items = items.peekable();
+    if items.peek().is_none() {
+        let _ = writeln!(html, "<p>None.</p>");
+        return;
+    }
+    let _ = writeln!(
+        html,
+        "<table>\n<tr><th>Owner</th><th>Name</th><th>Amount</th><th>Frequency</th><th>Ages</th><th>Category</th></tr>"
+    );
+    for (owner, name, amount, frequency, start_age, end_age, category) in items {
+        let _ = writeln!(
+            html,
+            "<tr><td>{}</td><td>{}</td><td class=\"num\">{}</td><td>{}</td><td>{} to {}</td><td>{}</td></tr>",
+            escape_xml(owner),
+            escape_xml(name),
+            format_amount(amount),
+            frequency,
+            start_age,
+            end_age.map_or("ongoing".to_string(), |age| age.to_string()),
+            escape_xml(&category)
+        );
+    }
+    let _ = writeln!(html, "</table>");
+}
@@ -0,0 +1,14 @@
+pub mod chart;
+pub mod deflate;
+pub mod format;
+pub mod html_report;
+pub mod png;
+pub mod report;
+pub mod summary_report;
+pub mod xlsx;
+pub mod zip;
+pub use chart::{Chart, ChartFormat, ChartSeries, SeriesKind};
+pub use html_report::html_report;
+pub use report::ReportFormat;
+pub use summary_report::{Summary, markdown_report, text_report};
+pub use xlsx::{xlsx_formula_balances, xlsx_workbook};
@@ -0,0 +1,302 @@
+// Headless rasterizer for chart scenes. Lines are anti-aliased by their distance to each
+// segment and text uses a built-in 5x7 pixel font, so no window or font files are needed.
+
+use crate::infrastructure::export::chart::{Anchor, Scene, Shape};
+use crate::infrastructure::export::deflate::{crc32, zlib};
+
+const GLYPH_WIDTH: usize = 5;
+const GLYPH_HEIGHT: usize = 7;
+// Glyph width plus one column of spacing
+const ADVANCE: usize = GLYPH_WIDTH + 1;
+const DASH: [f64; 2] = [6.0, 3.0];
+
+/// Rows of the 5x7 glyph for `c`, top first, with the leftmost pixel in bit 4. Lowercase
+/// letters are drawn as capitals.
+fn glyph(c: char) -> [u8; GLYPH_HEIGHT] {
+    match c.to_ascii_uppercase() {
+        ' ' => [0; 7],
+        '0' => [0x0E, 0x11, 0x13, 0x15, 0x19, 0x11, 0x0E],
+        '1' => [0x04, 0x0C, 0x04, 0x04, 0x04, 0x04, 0x0E],
+        '2' => [0x0E, 0x11, 0x01, 0x02, 0x04, 0x08, 0x1F],
+        '3' => [0x1F, 0x02, 0x04, 0x02, 0x01, 0x11, 0x0E],
+        '4' => [0x02, 0x06, 0x0A, 0x12, 0x1F, 0x02, 0x02],
+        '5' => [0x1F, 0x10, 0x1E, 0x01, 0x01, 0x11, 0x0E],
+        '6' => [0x06, 0x08, 0x10, 0x1E, 0x11, 0x11, 0x0E],
+        '7' => [0x1F, 0x01, 0x02, 0x04, 0x08, 0x08, 0x08],
+        '8' => [0x0E, 0x11, 0x11, 0x0E, 0x11, 0x11, 0x0E],
+        '9' => [0x0E, 0x11, 0x11, 0x0F, 0x01, 0x02, 0x0C],
+        'A' => [0x0E, 0x11, 0x11, 0x1F, 0x11, 0x11, 0x11],
+        'B' => [0x1E, 0x11, 0x11, 0x1E, 0x11, 0x11, 0x1E],
+        'C' => [0x0E, 0x11, 0x10, 0x10, 0x10, 0x11, 0x0E],
+        'D' => [0x1C, 0x12, 0x11, 0x11, 0x11, 0x12, 0x1C],
+        'E' => [0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x1F],
+        'F' => [0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x10],
+        'G' => [0x0E, 0x11, 0x10, 0x17, 0x11, 0x11, 0x0F],
+        'H' => [0x11, 0x11, 0x11, 0x1F, 0x11, 0x11, 0x11],
+        'I' => [0x0E, 0x04, 0x04, 0x04, 0x04, 0x04, 0x0E],
+        'J' => [0x07, 0x02, 0x02, 0x02, 0x02, 0x12, 0x0C],
+        'K' => [0x11, 0x12, 0x14, 0x18, 0x14, 0x12, 0x11],
+        'L' => [0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x1F],
+        'M' => [0x11, 0x1B, 0x15, 0x15, 0x11, 0x11, 0x11],
+        'N' => [0x11, 0x11, 0x19, 0x15, 0x13, 0x11, 0x11],
+        'O' => [0x0E, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E],
+        'P' => [0x1E, 0x11, 0x11, 0x1E, 0x10, 0x10, 0x10],
+        'Q' => [0x0E, 0x11, 0x11, 0x11, 0x15, 0x12, 0x0D],
+        'R' => [0x1E, 0x11, 0x11, 0x1E, 0x14, 0x12, 0x11],
+        'S' => [0x0F, 0x10, 0x10, 0x0E, 0x01, 0x01, 0x1E],
+        'T' => [0x1F, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04],
+        'U' => [0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E],
+        'V' => [0x11, 0x11, 0x11, 0x11, 0x11, 0x0A, 0x04],
+        'W' => [0x11, 0x11, 0x11, 0x15, 0x15, 0x15, 0x0A],
+        'X' => [0x11, 0x11, 0x0A, 0x04, 0x0A, 0x11, 0x11],
+        'Y' => [0x11, 0x11, 0x11, 0x0A, 0x04, 0x04, 0x04],
+        'Z' => [0x1F, 0x01, 0x02, 0x04, 0x08, 0x10, 0x1F],
+        '.' => [0x00, 0x00, 0x00, 0x00, 0x00, 0x0C, 0x0C],
+        ',' => [0x00, 0x00, 0x00, 0x00, 0x0C, 0x04, 0x08],
+        ':' => [0x00, 0x0C, 0x0C, 0x00, 0x0C, 0x0C, 0x00],
+        '-' => [0x00, 0x00, 0x00, 0x1F, 0x00, 0x00, 0x00],
+        '+' => [0x00, 0x04, 0x04, 0x1F, 0x04, 0x04, 0x00],
+        '=' => [0x00, 0x00, 0x1F, 0x00, 0x1F, 0x00, 0x00],
+        '_' => [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x1F],
+        '%' => [0x18, 0x19, 0x02, 0x04, 0x08, 0x13, 0x03],
+        '/' => [0x00, 0x01, 0x02, 0x04, 0x08, 0x10, 0x00],
+        '(' => [0x02, 0x04, 0x08, 0x08, 0x08, 0x04, 0x02],
+        ')' => [0x08, 0x04, 0x02, 0x02, 0x02, 0x04, 0x08],
+        '<' => [0x02, 0x04, 0x08, 0x10, 0x08, 0x04, 0x02],
+        '>' => [0x08, 0x04, 0x02, 0x01, 0x02, 0x04, 0x08],
+        '\'' => [0x04, 0x04, 0x08, 0x00, 0x00, 0x00, 0x00],
+        '"' => [0x0A, 0x0A, 0x00, 0x00, 0x00, 0x00, 0x00],
+        '!' => [0x04, 0x04, 0x04, 0x04, 0x04, 0x00, 0x04],
+        '&' => [0x0C, 0x12, 0x14, 0x08, 0x15, 0x12, 0x0D],
+        '#' => [0x0A, 0x0A, 0x1F, 0x0A, 0x1F, 0x0A, 0x0A],
+        '$' => [0x04, 0x0F, 0x14, 0x0E, 0x05, 0x1E, 0x04],
+        _ => [0x0E, 0x11, 0x01, 0x02, 0x04, 0x00, 0x04],
+    }
+}
+
+struct Canvas {
+    width: usize,
+    height: usize,
+    /// RGB bytes, row by row.
+    pixels: Vec<u8>,
+}
+
+impl Canvas {
+    fn new(width: usize, height: usize) -> Self {
+        Canvas {
+            width,
+            height,
+            pixels: vec![255; width * height * 3],
+        }
+    }
+
+    fn blend(&mut self, x: usize, y: usize, color: [u8; 3], alpha: f64) {
+        let offset = (y * self.width + x) * 3;
+        for (channel, value) in self.pixels[offset..offset + 3].iter_mut().zip(color) {
+            *channel = (*channel as f64 * (1.0 - alpha) + value as f64 * alpha).round() as u8;
+        }
+    }
+
+    fn fill_rect(&mut self, [x0, y0, x1, y1]: [f64; 4], color: [u8; 3]) {
+        let (x0, x1) = (self.clamp_x(x0), self.clamp_x(x1));
+        let (y0, y1) = (self.clamp_y(y0), self.clamp_y(y1));
+        for y in y0..y1 {
+            for x in x0..x1 {
+                self.blend(x, y, color, 1.0);
+            }
+        }
+    }
+
+    /// Draws one anti-aliased segment, limited to `clip` as `[x0, y0, x1, y1]`.
+    fn segment(&mut self, a: [f64; 2], b: [f64; 2], width: f64, color: [u8; 3], clip: [f64; 4]) {
+        let reach = width / 2.0 + 1.0;
+        let x0 = self
+            .clamp_x(a[0].min(b[0]) - reach)
+            .max(self.clamp_x(clip[0]));
+        let x1 = self
+            .clamp_x(a[0].max(b[0]) + reach)
+            .min(self.clamp_x(clip[2]));
+        let y0 = self
+            .clamp_y(a[1].min(b[1]) - reach)
+            .max(self.clamp_y(clip[1]));
+        let y1 = self
+            .clamp_y(a[1].max(b[1]) + reach)
+            .min(self.clamp_y(clip[3]));
+
+        let (dx, dy) = (b[0] - a[0], b[1] - a[1]);
+        let length_squared = dx * dx + dy * dy;
+        for y in y0..y1 {
+            for x in x0..x1 {
+                let (px, py) = (x as f64 + 0.5, y as f64 + 0.5);
+                let t = if length_squared > 0.0 {
+                    (((px - a[0]) * dx + (py - a[1]) * dy) / length_squared).clamp(0.0, 1.0)
+                } else {
+                    0.0
+                };
+                let distance = (px - a[0] - t * dx).hypot(py - a[1] - t * dy);
+                let coverage = (width / 2.0 + 0.5 - distance).clamp(0.0, 1.0);
+                if coverage > 0.0 {
+                    self.blend(x, y, color, coverage);
+                }
+            }
+        }
+    }
+
+    fn text(&mut self, x: f64, y: f64, text: &str, pixel: usize, bold: bool, anchor: Anchor) {
+        let width = (text.chars().count() * ADVANCE * pixel) as f64;
+        let left = match anchor {
+            Anchor::Start => x,
+            Anchor::Middle => x - width / 2.0,
+            Anchor::End => x - width,
+        };
+        let top = y - (GLYPH_HEIGHT * pixel) as f64;
+        for (index, c) in text.chars().enumerate() {
+            let glyph_left = left + (index * ADVANCE * pixel) as f64;
+            for (row, bits) in glyph(c).iter().enumerate() {
+                for column in 0..GLYPH_WIDTH {
+                    if bits & (0x10 >> column) == 0 {
+                        continue;
+                    }
+                    let px = glyph_left + (column * pixel) as f64;
+                    let py = top + (row * pixel) as f64;
+                    let right = px + (pixel + bold as usize) as f64;
+                    self.fill_rect([px, py, right, py + pixel as f64], [34, 34, 34]);
+                }
+            }
+        }
+    }
+
+    fn clamp_x(&self, x: f64) -> usize {
+        x.floor().clamp(0.0, self.width as f64) as usize
+    }
+
+    fn clamp_y(&self, y: f64) -> usize {
+        y.floor().clamp(0.0, self.height as f64) as usize
+    }
+}
+
+/// Splits a polyline into the visible pieces of a dash pattern.
+fn dashes(points: &[[f64; 2]], pattern: [f64; 2]) -> Vec<[[f64; 2]; 2]> {
+    let mut pieces = Vec::new();
+    let mut drawing = true;
+    let mut left = pattern[0];
+    for pair in points.windows(2) {
+        let (mut start, end) = (pair[0], pair[1]);
+        let mut length = (end[0] - start[0]).hypot(end[1] - start[1]);
+        while length > 0.0 {
+            let step = left.min(length);
+            let t = step / length;
+            let next = [
+                start[0] + (end[0] - start[0]) * t,
+                start[1] + (end[1] - start[1]) * t,
+            ];
+            if drawing {
+                pieces.push([start, next]);
+            }
+            left -= step;
+            length -= step;
+            start = next;
+            if left <= 0.0 {
+                drawing = !drawing;
+                left = if drawing { pattern[0] } else { pattern[1] };
+            }
+        }
+    }
+    pieces
+}
+
+/// Rasterizes a scene with `scale` pixels per unit and encodes it as PNG.
+pub fn render_png(scene: &Scene, scale: u32) -> Vec<u8> {
+    let scale = scale as f64;
+    let mut canvas = Canvas::new(
+        (scene.width * scale).round() as usize,
+        (scene.height * scale).round() as usize,
+    );
+    let everywhere = [0.0, 0.0, canvas.width as f64, canvas.height as f64];
+    let [x, y, width, height] = scene.plot_area.map(|value| value * scale);
+    let plot_area = [x, y, x + width, y + height];
+    let at = |point: &[f64; 2]| [point[0] * scale, point[1] * scale];
+
+    for shape in &scene.shapes {
+        match shape {
+            Shape::Rect {
+                x,
+                y,
+                width,
+                height,
+                color,
+            } => canvas.fill_rect(
+                [
+                    x * scale,
+                    y * scale,
+                    (x + width) * scale,
+                    (y + height) * scale,
+                ],
+                *color,
+            ),
+            Shape::Line {
+                points,
+                color,
+                width,
+                dashed,
+                clipped,
+            } => {
+                let points: Vec<[f64; 2]> = points.iter().map(at).collect();
+                let clip = if *clipped { plot_area } else { everywhere };
+                let pieces = if *dashed {
+                    dashes(&points, DASH.map(|length| length * scale))
+                } else {
+                    points.windows(2).map(|pair| [pair[0], pair[1]]).collect()
+                };
+                for [a, b] in pieces {
+                    canvas.segment(a, b, width * scale, *color, clip);
+                }
+            }
+            Shape::Text {
+                x,
+                y,
+                text,
+                size,
+                bold,
+                anchor,
+            } => {
+                // The font is 7 pixels tall, about right for 12 point text
+                let pixel = ((size / 12.0) * scale).round().max(1.0) as usize;
+                canvas.text(x * scale, y * scale, text, pixel, *bold, *anchor);
+            }
+        }
+    }
+
+    encode_png(canvas.width, canvas.height, &canvas.pixels)
+}
+
+/// Encodes 8-bit RGB pixels, row by row, as a PNG file.
+pub fn encode_png(width: usize, height: usize, pixels: &[u8]) -> Vec<u8> {
+    let mut raw = Vec::with_capacity((width * 3 + 1) * height);
+    for row in pixels.chunks(width * 3).take(height) {
+        // Filter type 0: the row is stored as is
+        raw.push(0);
+        raw.extend_from_slice(row);
+    }
+
+    let mut header = Vec::with_capacity(13);
+    header.extend((width as u32).to_be_bytes());
+    header.extend((height as u32).to_be_bytes());
+    // Bit depth 8, color type 2 (RGB), default compression, filtering and no interlace
+    header.extend([8, 2, 0, 0, 0]);
+
+    let mut png = vec![0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1A, b'\n'];
+    write_chunk(&mut png, b"IHDR", &header);
+    write_chunk(&mut png, b"IDAT", &zlib(&raw));
+    write_chunk(&mut png, b"IEND", &[]);
+    png
+}
+
+fn write_chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    png.extend((data.len() as u32).to_be_bytes());
+    let start = png.len();
+    png.extend_from_slice(kind);
+    png.extend_from_slice(data);
+    let crc = crc32(&png[start..]);
+    png.extend(crc.to_be_bytes());
+}
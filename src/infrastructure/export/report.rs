@@ -0,0 +1,85 @@
+use crate::domain::Scenario;
+use crate::infrastructure::export::html_report::html_report;
+use crate::infrastructure::export::summary_report::{markdown_report, text_report};
+use crate::infrastructure::export::xlsx::xlsx_workbook;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReportFormat {
+    Html,
+    Markdown,
+    Text,
+    Xlsx,
+}
+
+impl ReportFormat {
+    pub const ALL: [ReportFormat; 4] = [
+        ReportFormat::Html,
+        ReportFormat::Markdown,
+        ReportFormat::Text,
+        ReportFormat::Xlsx,
+    ];
+
+    /// Parses a format name as given on the command line; the file extension works too.
+    pub fn parse(name: &str) -> Result<Self, String> {
+        let name = name.trim();
+        ReportFormat::ALL
+            .into_iter()
+            .find(|format| {
+                format.to_string().eq_ignore_ascii_case(name)
+                    || format.extension().eq_ignore_ascii_case(name)
+            })
+            .ok_or_else(|| {
+                format!(
+                    "Unknown report format \"{}\" (expected html, markdown, text or xlsx)",
+                    name
+                )
+            })
+    }
+
+    /// Name shown in menus.
+    pub fn label(&self) -> &'static str {
+        match self {
+            ReportFormat::Html => "HTML",
+            ReportFormat::Markdown => "Markdown",
+            ReportFormat::Text => "plain-text",
+            ReportFormat::Xlsx => "XLSX",
+        }
+    }
+
+    /// File extension for reports of this format.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ReportFormat::Html => "html",
+            ReportFormat::Markdown => "md",
+            ReportFormat::Text => "txt",
+            ReportFormat::Xlsx => "xlsx",
+        }
+    }
+
+    /// Whether the report is text that can be printed or pasted.
+    pub fn is_text(&self) -> bool {
+        *self != ReportFormat::Xlsx
+    }
+
+    /// Renders the projection of `scenario` up to `end_age`.
+    pub fn render(&self, scenario: &mut Scenario, end_age: u32) -> Vec<u8> {
+        match self {
+            ReportFormat::Html => html_report(scenario, end_age).into_bytes(),
+            ReportFormat::Markdown => markdown_report(scenario, end_age).into_bytes(),
+            ReportFormat::Text => text_report(scenario, end_age).into_bytes(),
+            ReportFormat::Xlsx => xlsx_workbook(scenario, end_age),
+        }
+    }
+}
+
+impl fmt::Display for ReportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReportFormat::Html => write!(f, "html"),
+            ReportFormat::Markdown => write!(f, "markdown"),
+            ReportFormat::Text => write!(f, "text"),
+            ReportFormat::Xlsx => write!(f, "xlsx"),
+        }
+    }
+}
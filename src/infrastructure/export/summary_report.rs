@@ -0,0 +1,389 @@
+use crate::domain::{
+    Category, CategoryTotals, FireVariant, LifeSimulator, LongevityReport, Scenario, fire_report,
+    longevity_report, projection,
+};
+use crate::infrastructure::export::format::{format_amount, format_percent};
+use std::fmt::Write;
+
+// Withdrawal rate used for the FIRE age in summaries
+const FIRE_WITHDRAWAL_RATE: f64 = 0.04;
+
+/// Ten years of the projection.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecadeRow {
+    pub from_age: u32,
+    pub to_age: u32,
+    /// Balance at `to_age`.
+    pub balance: f64,
+    pub incomes: f64,
+    pub expenses: f64,
+}
+
+/// Key numbers of a projection, shared by the Markdown and plain-text reports.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Summary {
+    pub name: String,
+    pub start_age: u32,
+    pub end_age: u32,
+    /// Label, age and balance of the ages worth calling out.
+    pub milestones: Vec<(String, u32, f64)>,
+    /// Balance plus property equity at the end, if the scenario holds property.
+    pub net_worth: Option<f64>,
+    /// First age with a negative balance.
+    pub depletion_age: Option<u32>,
+    pub fire_age: Option<u32>,
+    pub fire_number: Option<f64>,
+    /// Lifetime totals per top-level category, largest first.
+    pub categories: Vec<(Category, CategoryTotals)>,
+    pub lifetime: CategoryTotals,
+    pub decades: Vec<DecadeRow>,
+    /// Outcome weighed by the scenario's mortality, if it has one.
+    pub longevity: Option<LongevityReport>,
+}
+
+impl Summary {
+    pub fn new(scenario: &mut Scenario, end_age: u32) -> Self {
+        let simulator = &mut scenario.simulator;
+        let rows = projection(simulator, end_age);
+        let fire = fire_report(
+            simulator,
+            FireVariant::Regular,
+            FIRE_WITHDRAWAL_RATE,
+            end_age,
+        );
+        let start_age = simulator.get_current_age();
+        let end_age = end_age.max(start_age);
+        let longevity = simulator
+            .get_simulation()
+            .mortality()
+            .cloned()
+            .and_then(|mortality| longevity_report(simulator, &mortality).ok());
+        let retirement_age = simulator.get_assumptions().retirement_age;
+
+        let balance_at = |age: u32| {
+            rows.iter()
+                .find(|row| row.age == age)
+                .map(|row| row.balance)
+        };
+        let mut milestones = vec![(format!("Balance today (age {})", start_age), start_age)];
+        if let Some(age) = retirement_age {
+            milestones.push((format!("Balance at retirement (age {})", age), age));
+        }
+        for age in [65, 80] {
+            milestones.push((format!("Balance at age {}", age), age));
+        }
+        milestones.push((format!("Balance at the end (age {})", end_age), end_age));
+        milestones.sort_by_key(|(_, age)| *age);
+        milestones.dedup_by_key(|(_, age)| *age);
+        let milestones = milestones
+            .into_iter()
+            .filter_map(|(label, age)| balance_at(age).map(|balance| (label, age, balance)))
+            .collect();
+
+        let net_worth =
+            (!simulator.get_properties().is_empty()).then(|| simulator.net_worth_at(end_age));
+
+        let breakdown = simulator.category_totals_between(start_age, end_age + 1);
+        let mut categories = breakdown.composition(None);
+        categories
+            .sort_by(|a, b| (b.1.expenses + b.1.incomes).total_cmp(&(a.1.expenses + a.1.incomes)));
+
+        let mut decades: Vec<DecadeRow> = Vec::new();
+        for row in &rows {
+            match decades.last_mut() {
+                Some(decade) if decade.from_age / 10 == row.age / 10 => {
+                    decade.to_age = row.age;
+                    decade.balance = row.balance;
+                    decade.incomes += row.incomes;
+                    decade.expenses += row.expenses;
+                }
+                _ => decades.push(DecadeRow {
+                    from_age: row.age,
+                    to_age: row.age,
+                    balance: row.balance,
+                    incomes: row.incomes,
+                    expenses: row.expenses,
+                }),
+            }
+        }
+
+        Summary {
+            name: scenario.name.clone(),
+            start_age,
+            end_age,
+            milestones,
+            net_worth,
+            depletion_age: LifeSimulator::depletion_age(&simulator.balance_series(end_age)),
+            fire_age: fire.fire_age,
+            fire_number: fire.current_fire_number(),
+            categories,
+            lifetime: breakdown.total(),
+            decades,
+            longevity,
+        }
+    }
+
+    fn key_numbers(&self) -> Vec<(String, String)> {
+        let mut numbers: Vec<(String, String)> = self
+            .milestones
+            .iter()
+            .map(|(label, _, balance)| (label.clone(), format_amount(*balance)))
+            .collect();
+        if let Some(net_worth) = self.net_worth {
+            numbers.push((
+                format!("Net worth with property at the end (age {})", self.end_age),
+                format_amount(net_worth),
+            ));
+        }
+        numbers.push((
+            "Money runs out".to_string(),
+            match self.depletion_age {
+                Some(age) => format!("at age {}", age),
+                None => format!("never (through age {})", self.end_age),
+            },
+        ));
+        numbers.push((
+            "FIRE age".to_string(),
+            match (self.fire_age, self.fire_number) {
+                (Some(age), Some(number)) => format!(
+                    "{} (FIRE number today {} at a {} withdrawal rate)",
+                    age,
+                    format_amount(number),
+                    format_percent(FIRE_WITHDRAWAL_RATE)
+                ),
+                (Some(age), None) => age.to_string(),
+                (None, _) => format!("not reached by age {}", self.end_age),
+            },
+        ));
+        if let Some(longevity) = &self.longevity {
+            numbers.push((
+                "Life expectancy".to_string(),
+                format!("{:.1}", longevity.life_expectancy),
+            ));
+            numbers.push((
+                "Chance of outliving the money".to_string(),
+                format_percent(longevity.ruin_probability),
+            ));
+            numbers.push((
+                "Expected bequest".to_string(),
+                format_amount(longevity.expected_bequest),
+            ));
+        }
+        numbers.push((
+            "Lifetime income".to_string(),
+            format_amount(self.lifetime.incomes),
+        ));
+        numbers.push((
+            "Lifetime expenses".to_string(),
+            format_amount(self.lifetime.expenses),
+        ));
+        numbers
+    }
+
+    fn category_rows(&self) -> Vec<Vec<String>> {
+        self.categories
+            .iter()
+            .map(|(category, totals)| {
+                vec![
+                    category.to_string(),
+                    format_amount(totals.incomes),
+                    format_amount(totals.expenses),
+                ]
+            })
+            .collect()
+    }
+
+    fn decade_rows(&self) -> Vec<Vec<String>> {
+        self.decades
+            .iter()
+            .map(|decade| {
+                vec![
+                    if decade.from_age == decade.to_age {
+                        decade.from_age.to_string()
+                    } else {
+                        format!("{}-{}", decade.from_age, decade.to_age)
+                    },
+                    format_amount(decade.balance),
+                    format_amount(decade.incomes),
+                    format_amount(decade.expenses),
+                    format_amount(decade.incomes - decade.expenses),
+                ]
+            })
+            .collect()
+    }
+
+    /// Renders the summary as Markdown, for wikis and chat.
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "# {}\n", escape_markdown(&self.name));
+        let _ = writeln!(
+            out,
+            "Projection from age {} to {}.\n",
+            self.start_age, self.end_age
+        );
+
+        let _ = writeln!(out, "## Key Numbers\n");
+        for (label, value) in self.key_numbers() {
+            let _ = writeln!(out, "- **{}:** {}", label, value);
+        }
+
+        let _ = writeln!(out, "\n## Lifetime Income and Expenses by Category\n");
+        let rows: Vec<Vec<String>> = self
+            .category_rows()
+            .into_iter()
+            .map(|row| row.into_iter().map(|cell| escape_markdown(&cell)).collect())
+            .collect();
+        table(&mut out, &["Category", "Income", "Expenses"], &rows, true);
+
+        let _ = writeln!(out, "\n## By Decade\n");
+        table(
+            &mut out,
+            &["Ages", "Balance at end", "Income", "Expenses", "Net"],
+            &self.decade_rows(),
+            true,
+        );
+        out
+    }
+
+    /// Renders the summary as plain text with aligned columns.
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+        heading(&mut out, &self.name, '=');
+        let _ = writeln!(
+            out,
+            "Projection from age {} to {}.\n",
+            self.start_age, self.end_age
+        );
+
+        heading(&mut out, "Key numbers", '-');
+        let width = self
+            .key_numbers()
+            .iter()
+            .map(|(label, _)| label.chars().count())
+            .max()
+            .unwrap_or(0);
+        for (label, value) in self.key_numbers() {
+            let _ = writeln!(
+                out,
+                "{:<width$}  {}",
+                format!("{}:", label),
+                value,
+                width = width + 1
+            );
+        }
+
+        let _ = writeln!(out);
+        heading(&mut out, "Lifetime income and expenses by category", '-');
+        table(
+            &mut out,
+            &["Category", "Income", "Expenses"],
+            &self.category_rows(),
+            false,
+        );
+
+        let _ = writeln!(out);
+        heading(&mut out, "By decade", '-');
+        table(
+            &mut out,
+            &["Ages", "Balance at end", "Income", "Expenses", "Net"],
+            &self.decade_rows(),
+            false,
+        );
+        out
+    }
+}
+
+/// Markdown summary of a scenario: key numbers, lifetime totals by category and a
+/// per-decade table.
+pub fn markdown_report(scenario: &mut Scenario, end_age: u32) -> String {
+    Summary::new(scenario, end_age).to_markdown()
+}
+
+/// Plain-text version of [`markdown_report`].
+pub fn text_report(scenario: &mut Scenario, end_age: u32) -> String {
+    Summary::new(scenario, end_age).to_text()
+}
+
+fn heading(out: &mut String, title: &str, underline: char) {
+    let _ = writeln!(
+        out,
+        "{}\n{}",
+        title,
+        underline.to_string().repeat(title.chars().count())
+    );
+}
+
+// Writes a table with padded columns; the first column is left aligned, the others right
+fn table(out: &mut String, headers: &[&str], rows: &[Vec<String>], markdown: bool) {
+    let mut widths: Vec<usize> = headers
+        .iter()
+        .map(|header| header.chars().count())
+        .collect();
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    let line = |cells: Vec<String>| {
+        if markdown {
+            format!("| {} |", cells.join(" | "))
+        } else {
+            cells.join("  ").trim_end().to_string()
+        }
+    };
+    let pad = |index: usize, cell: &str| {
+        if index == 0 {
+            format!("{:<width$}", cell, width = widths[index])
+        } else {
+            format!("{:>width$}", cell, width = widths[index])
+        }
+    };
+
+    let _ = writeln!(
+        out,
+        "{}",
+        line(
+            headers
+                .iter()
+                .enumerate()
+                .map(|(index, header)| pad(index, header))
+                .collect()
+        )
+    );
+    let rule = widths
+        .iter()
+        .enumerate()
+        .map(|(index, width)| match (markdown, index) {
+            (true, 0) => format!(":{}", "-".repeat(width - 1)),
+            (true, _) => format!("{}:", "-".repeat(width - 1)),
+            (false, _) => "-".repeat(*width),
+        })
+        .collect();
+    let _ = writeln!(out, "{}", line(rule));
+    for row in rows {
+        let _ = writeln!(
+            out,
+            "{}",
+            line(
+                row.iter()
+                    .enumerate()
+                    .map(|(index, cell)| pad(index, cell))
+                    .collect()
+            )
+        );
+    }
+}
+
+fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(
+            c,
+            '\\' | '|' | '*' | '_' | '`' | '[' | ']' | '<' | '>' | '#'
+        ) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
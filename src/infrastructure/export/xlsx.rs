@@ -0,0 +1,667 @@
+use crate::domain::{Category, Scenario, TimeStep, WithdrawalStrategy, projection};
+use crate::infrastructure::export::format::escape_xml;
+use crate::infrastructure::export::html_report::items_by_owner;
+use crate::infrastructure::export::zip::ZipWriter;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+const MAIN_NAMESPACE: &str = "http://schemas.openxmlformats.org/spreadsheetml/2006/main";
+const RELATIONSHIP_NAMESPACE: &str =
+    "http://schemas.openxmlformats.org/officeDocument/2006/relationships";
+
+// Cells of the Inputs sheet that the formulas refer to
+const CURRENT_AGE: &str = "Inputs!$B$3";
+const STARTING_CAPITAL: &str = "Inputs!$B$4";
+const ANNUAL_RETURN: &str = "Inputs!$B$5";
+const INFLATION: &str = "Inputs!$B$6";
+const RETIREMENT_AGE: &str = "Inputs!$B$7";
+
+const STYLES: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<styleSheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><numFmts count="1"><numFmt numFmtId="164" formatCode="0.0000"/></numFmts><fonts count="2"><font><sz val="11"/><name val="Calibri"/></font><font><b/><sz val="11"/><name val="Calibri"/></font></fonts><fills count="2"><fill><patternFill patternType="none"/></fill><fill><patternFill patternType="gray125"/></fill></fills><borders count="1"><border><left/><right/><top/><bottom/><diagonal/></border></borders><cellStyleXfs count="1"><xf numFmtId="0" fontId="0" fillId="0" borderId="0"/></cellStyleXfs><cellXfs count="5"><xf numFmtId="0" fontId="0" fillId="0" borderId="0" xfId="0"/><xf numFmtId="0" fontId="1" fillId="0" borderId="0" xfId="0" applyFont="1"/><xf numFmtId="4" fontId="0" fillId="0" borderId="0" xfId="0" applyNumberFormat="1"/><xf numFmtId="10" fontId="0" fillId="0" borderId="0" xfId="0" applyNumberFormat="1"/><xf numFmtId="164" fontId="0" fillId="0" borderId="0" xfId="0" applyNumberFormat="1"/></cellXfs><cellStyles count="1"><cellStyle name="Normal" xfId="0" builtinId="0"/></cellStyles></styleSheet>"#;
+
+/// Index into the cell formats of `STYLES`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Style {
+    Plain = 0,
+    Header = 1,
+    Amount = 2,
+    Percent = 3,
+    Factor = 4,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Empty,
+    Text(String),
+    Number(f64),
+    /// Formula with the value it had when written, shown until the spreadsheet recalculates.
+    Formula(String, f64),
+}
+
+struct Sheet {
+    name: String,
+    widths: Vec<f64>,
+    rows: Vec<Vec<(Value, Style)>>,
+}
+
+impl Sheet {
+    fn new(name: String) -> Self {
+        Sheet {
+            name,
+            widths: Vec::new(),
+            rows: Vec::new(),
+        }
+    }
+
+    fn header(&mut self, titles: &[&str]) {
+        self.rows.push(
+            titles
+                .iter()
+                .map(|title| (Value::Text(title.to_string()), Style::Header))
+                .collect(),
+        );
+    }
+
+    fn to_xml(&self) -> String {
+        let mut xml = String::new();
+        let _ = write!(
+            xml,
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<worksheet xmlns="{}"><sheetViews><sheetView workbookViewId="0"><pane ySplit="1" topLeftCell="A2" activePane="bottomLeft" state="frozen"/></sheetView></sheetViews>"#,
+            MAIN_NAMESPACE
+        );
+        if !self.widths.is_empty() {
+            xml.push_str("<cols>");
+            for (index, width) in self.widths.iter().enumerate() {
+                let _ = write!(
+                    xml,
+                    r#"<col min="{n}" max="{n}" width="{}" customWidth="1"/>"#,
+                    width,
+                    n = index + 1
+                );
+            }
+            xml.push_str("</cols>");
+        }
+
+        xml.push_str("<sheetData>");
+        for (row_index, row) in self.rows.iter().enumerate() {
+            let _ = write!(xml, r#"<row r="{}">"#, row_index + 1);
+            for (column, (value, style)) in row.iter().enumerate() {
+                let reference = format!("{}{}", column_name(column), row_index + 1);
+                let style = *style as u8;
+                let _ = match value {
+                    Value::Empty => Ok(()),
+                    Value::Text(text) => write!(
+                        xml,
+                        r#"<c r="{}" s="{}" t="inlineStr"><is><t xml:space="preserve">{}</t></is></c>"#,
+                        reference,
+                        style,
+                        escape_xml(text)
+                    ),
+                    Value::Number(number) => write!(
+                        xml,
+                        r#"<c r="{}" s="{}"><v>{}</v></c>"#,
+                        reference,
+                        style,
+                        finite(*number)
+                    ),
+                    Value::Formula(formula, cached) => write!(
+                        xml,
+                        r#"<c r="{}" s="{}"><f>{}</f><v>{}</v></c>"#,
+                        reference,
+                        style,
+                        escape_xml(formula),
+                        finite(*cached)
+                    ),
+                };
+            }
+            xml.push_str("</row>");
+        }
+        xml.push_str("</sheetData></worksheet>");
+        xml
+    }
+}
+
+/// An item on the Inputs sheet, which the category sheets add up by age.
+struct InputItem {
+    row: usize,
+    is_income: bool,
+    category: String,
+    /// Owner's age less the main person's, for the items of other household members.
+    age_offset: i64,
+    /// Earned incomes stop once their owner retires.
+    earned: bool,
+    yearly_amount: f64,
+    start_age: u32,
+    end_age: Option<u32>,
+}
+
+impl InputItem {
+    fn owner_age(&self, age: u32) -> u32 {
+        (age as i64 + self.age_offset).max(0) as u32
+    }
+
+    // Formula for the owner's age on the category sheet row `row`
+    fn owner_age_formula(&self, row: usize) -> String {
+        match self.age_offset {
+            0 => format!("$A{}", row),
+            offset if offset > 0 => format!("($A{}+{})", row, offset),
+            offset => format!("MAX($A{}-{},0)", row, -offset),
+        }
+    }
+
+    /// Amount in today's money in the year the main person is `age`, and the formula that
+    /// reads it from the Inputs sheet.
+    fn amount_at(&self, age: u32, row: usize, retirement_age: Option<u32>) -> (String, f64) {
+        let owner_age = self.owner_age(age);
+        let owner = self.owner_age_formula(row);
+        let mut conditions = vec![
+            format!("{}>=Inputs!$F${}", owner, self.row),
+            format!(
+                "OR(Inputs!$G${r}=\"\",{}<Inputs!$G${r})",
+                owner,
+                r = self.row
+            ),
+        ];
+        let mut active =
+            owner_age >= self.start_age && self.end_age.is_none_or(|end| owner_age < end);
+        if self.earned
+            && let Some(retirement_age) = retirement_age
+        {
+            conditions.push(format!("{}<{}", owner, RETIREMENT_AGE));
+            active &= owner_age < retirement_age;
+        }
+        let formula = format!("IF(AND({}),Inputs!$I${},0)", conditions.join(","), self.row);
+        (formula, if active { self.yearly_amount } else { 0.0 })
+    }
+}
+
+/// Yearly amounts of one top-level category, in today's money.
+struct CategorySheet {
+    name: String,
+    /// Income and expense columns: title, whether it is income, amount per projected age.
+    columns: Vec<(String, bool, Vec<f64>)>,
+}
+
+/// Writes the scenario and its projection up to `end_age` as an XLSX workbook: an Inputs
+/// sheet, a Results sheet whose incomes, expenses and balance are formulas over the inputs,
+/// and one sheet per top-level category with the yearly amounts in today's money.
+pub fn xlsx_workbook(scenario: &mut Scenario, end_age: u32) -> Vec<u8> {
+    package(&workbook_sheets(scenario, end_age))
+}
+
+/// Balance by age as the Results formulas of the workbook work it out, before any edit.
+pub fn xlsx_formula_balances(scenario: &mut Scenario, end_age: u32) -> Vec<(u32, f64)> {
+    let sheets = workbook_sheets(scenario, end_age);
+    sheets[1].rows[1..]
+        .iter()
+        .filter_map(|row| match (&row[0].0, &row[4].0) {
+            (Value::Number(age), Value::Formula(_, balance)) => Some((*age as u32, *balance)),
+            _ => None,
+        })
+        .collect()
+}
+
+fn workbook_sheets(scenario: &mut Scenario, end_age: u32) -> Vec<Sheet> {
+    let simulator = &mut scenario.simulator;
+    let rows = projection(simulator, end_age);
+    let person = simulator.get_person();
+    let assumptions = simulator.get_assumptions().clone();
+    let start_age = person.age;
+    // Includes the capital of other household members
+    let starting_capital = rows.first().map_or(person.capital, |row| row.balance);
+    let price_level = |age: u32| (1.0 + assumptions.inflation).powi((age - start_age) as i32);
+
+    // Inputs
+    let mut inputs = Sheet::new("Inputs".to_string());
+    inputs.widths = vec![22.0, 28.0, 12.0, 14.0, 12.0, 10.0, 10.0, 28.0, 16.0];
+    inputs.header(&["Input", "Value"]);
+    let text = |text: &str| (Value::Text(text.to_string()), Style::Plain);
+    inputs
+        .rows
+        .push(vec![text("Scenario"), text(&scenario.name)]);
+    inputs.rows.push(vec![
+        text("Current age"),
+        (Value::Number(start_age as f64), Style::Plain),
+    ]);
+    inputs.rows.push(vec![
+        text("Starting capital"),
+        (Value::Number(starting_capital), Style::Amount),
+    ]);
+    inputs.rows.push(vec![
+        text("Annual return"),
+        (
+            Value::Number(assumptions.return_at(start_age)),
+            Style::Percent,
+        ),
+    ]);
+    inputs.rows.push(vec![
+        text("Inflation"),
+        (Value::Number(assumptions.inflation), Style::Percent),
+    ]);
+    inputs.rows.push(vec![
+        text("Retirement age"),
+        match assumptions.retirement_age {
+            Some(age) => (Value::Number(age as f64), Style::Plain),
+            None => text("not set"),
+        },
+    ]);
+    inputs.rows.push(vec![
+        text("Withdrawal strategy"),
+        text(assumptions.withdrawal.name()),
+    ]);
+    inputs.rows.push(Vec::new());
+    inputs.rows.push(vec![text(
+        "Category sheets add up the items below by age in today's money, so editing an item changes them; amounts that are not items, such as property costs and pension indexation, are fixed. Results applies inflation and the return of each year.",
+    )]);
+    // What the Results formulas leave out, so their balance can differ from the simulated one
+    let mut left_out = Vec::new();
+    if assumptions.withdrawal != WithdrawalStrategy::CoverExpenses {
+        left_out.push("the withdrawal strategy");
+    }
+    if simulator.get_credit_line().is_some() {
+        left_out.push("the credit line");
+    }
+    if !simulator.get_properties().is_empty() {
+        left_out.push("property");
+    }
+    if !simulator.get_accounts().is_empty() {
+        left_out.push("tax-advantaged accounts and the tax on their withdrawals");
+    }
+    if simulator.get_simulation().time_step == TimeStep::Monthly {
+        left_out.push("the monthly time step");
+    }
+    if let Some((last, rest)) = left_out.split_last() {
+        let listed = match rest {
+            [] => last.to_string(),
+            _ => format!("{} and {}", rest.join(", "), last),
+        };
+        inputs.rows.push(vec![text(&format!(
+            "The Results formulas cover expenses from the balance once a year and leave out {}; only the Simulated balance column includes them.",
+            listed
+        ))]);
+    }
+    inputs.rows.push(Vec::new());
+    inputs.header(&[
+        "Owner",
+        "Name",
+        "Type",
+        "Amount",
+        "Frequency",
+        "Start age",
+        "End age",
+        "Category",
+        "Yearly amount",
+    ]);
+    let (incomes, expenses) = items_by_owner(simulator);
+    let items = incomes
+        .iter()
+        .map(|(owner, income)| {
+            (
+                owner,
+                &income.name,
+                "Income",
+                income.amount,
+                &income.frequency,
+                income.start_age,
+                income.end_age,
+                &income.category,
+                income.earned,
+            )
+        })
+        .chain(expenses.iter().map(|(owner, expense)| {
+            (
+                owner,
+                &expense.name,
+                "Expense",
+                expense.amount,
+                &expense.frequency,
+                expense.start_age,
+                expense.end_age,
+                &expense.category,
+                false,
+            )
+        }));
+    let members = &simulator.get_household().members;
+    let mut input_items = Vec::new();
+    for (owner, name, kind, amount, frequency, start, end, category, earned) in items {
+        let row = inputs.rows.len() + 1;
+        let age_offset = match members.iter().find(|m| &m.person.name == owner) {
+            Some(member) if owner != &person.name => member.person.age as i64 - start_age as i64,
+            _ => 0,
+        };
+        input_items.push(InputItem {
+            row,
+            is_income: kind == "Income",
+            category: category.to_string(),
+            age_offset,
+            earned,
+            yearly_amount: amount * frequency.periods_per_year(),
+            start_age: start,
+            end_age: end,
+        });
+        inputs.rows.push(vec![
+            text(owner),
+            text(name),
+            text(kind),
+            (Value::Number(amount), Style::Amount),
+            text(&format!("{:?}", frequency)),
+            (Value::Number(start as f64), Style::Plain),
+            end.map_or((Value::Empty, Style::Plain), |age| {
+                (Value::Number(age as f64), Style::Plain)
+            }),
+            text(&category.to_string()),
+            (
+                Value::Formula(
+                    format!("D{}*{}", row, frequency.periods_per_year()),
+                    amount * frequency.periods_per_year(),
+                ),
+                Style::Amount,
+            ),
+        ]);
+    }
+
+    // Yearly amounts per category, in today's money
+    let mut by_root: BTreeMap<Category, BTreeMap<(bool, String), Vec<f64>>> = BTreeMap::new();
+    for (index, row) in rows.iter().enumerate() {
+        let level = price_level(row.age);
+        for (category, totals) in simulator.category_totals_for_year(row.age).iter() {
+            for (is_income, amount) in [(true, totals.incomes), (false, totals.expenses)] {
+                if amount == 0.0 {
+                    continue;
+                }
+                let column = by_root
+                    .entry(category.truncated(1))
+                    .or_default()
+                    .entry((is_income, category.to_string()))
+                    .or_insert_with(|| vec![0.0; rows.len()]);
+                column[index] += if level > 0.0 { amount / level } else { amount };
+            }
+        }
+    }
+    let mut used_names = vec!["inputs".to_string(), "results".to_string()];
+    let category_sheets: Vec<CategorySheet> = by_root
+        .into_iter()
+        .map(|(root, columns)| {
+            let mut columns: Vec<(String, bool, Vec<f64>)> = columns
+                .into_iter()
+                .map(|((is_income, title), amounts)| {
+                    let kind = if is_income { "Income" } else { "Expense" };
+                    (format!("{}: {}", kind, title), is_income, amounts)
+                })
+                .collect();
+            // Incomes first, then expenses
+            columns.sort_by_key(|(_, is_income, _)| !is_income);
+            CategorySheet {
+                name: sheet_name(&root.to_string(), &mut used_names),
+                columns,
+            }
+        })
+        .collect();
+
+    // Per-category sheets end with total income and expense columns the results add up
+    let mut sheets = Vec::new();
+    let mut income_refs = Vec::new();
+    let mut expense_refs = Vec::new();
+    for category in &category_sheets {
+        let mut sheet = Sheet::new(category.name.clone());
+        let titles: Vec<&str> = std::iter::once("Age")
+            .chain(category.columns.iter().map(|(title, _, _)| title.as_str()))
+            .chain(["Total income", "Total expenses"])
+            .collect();
+        sheet.widths = titles
+            .iter()
+            .map(|title| (title.chars().count() as f64 + 2.0).max(10.0))
+            .collect();
+        sheet.header(&titles);
+
+        let income_column = column_name(category.columns.len() + 1);
+        let expense_column = column_name(category.columns.len() + 2);
+        income_refs.push((quote_sheet(&category.name), income_column));
+        expense_refs.push((quote_sheet(&category.name), expense_column));
+
+        for (index, row) in rows.iter().enumerate() {
+            let row_number = index + 2;
+            let mut cells = vec![(Value::Number(row.age as f64), Style::Plain)];
+            cells.extend(category.columns.iter().map(|(title, is_income, amounts)| {
+                let amount = amounts[index];
+                let category = title.split_once(": ").map_or(title.as_str(), |(_, c)| c);
+                let (terms, from_items): (Vec<String>, Vec<f64>) = input_items
+                    .iter()
+                    .filter(|item| item.is_income == *is_income && item.category == category)
+                    .map(|item| item.amount_at(row.age, row_number, assumptions.retirement_age))
+                    .unzip();
+                if terms.is_empty() {
+                    return (Value::Number(amount), Style::Amount);
+                }
+                // Whatever the items do not explain stays as a fixed amount
+                let mut formula = terms.join("+");
+                let rest = amount - from_items.iter().sum::<f64>();
+                if rest.abs() > 1e-6 {
+                    let _ = write!(
+                        formula,
+                        "{}{}",
+                        if rest < 0.0 { "-" } else { "+" },
+                        rest.abs()
+                    );
+                }
+                (Value::Formula(formula, amount), Style::Amount)
+            }));
+            for wanted in [true, false] {
+                let columns: Vec<String> = category
+                    .columns
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, (_, is_income, _))| *is_income == wanted)
+                    .map(|(column, _)| format!("{}{}", column_name(column + 1), row_number))
+                    .collect();
+                let cached: f64 = category
+                    .columns
+                    .iter()
+                    .filter(|(_, is_income, _)| *is_income == wanted)
+                    .map(|(_, _, amounts)| amounts[index])
+                    .sum();
+                let formula = if columns.is_empty() {
+                    "0".to_string()
+                } else {
+                    format!("SUM({})", columns.join(","))
+                };
+                cells.push((Value::Formula(formula, cached), Style::Amount));
+            }
+            sheet.rows.push(cells);
+        }
+        sheets.push(sheet);
+    }
+
+    // Results, computed from the inputs and the category sheets
+    let mut results = Sheet::new("Results".to_string());
+    results.widths = vec![8.0, 12.0, 16.0, 16.0, 18.0, 18.0, 10.0];
+    results.header(&[
+        "Age",
+        "Price level",
+        "Incomes",
+        "Expenses",
+        "Balance",
+        "Simulated balance",
+        "Return",
+    ]);
+    let mut balance = starting_capital;
+    let mut previous: Option<(f64, f64, f64)> = None;
+    for (index, row) in rows.iter().enumerate() {
+        let r = index + 2;
+        let level = price_level(row.age);
+        let sum_refs = |refs: &[(String, String)]| {
+            if refs.is_empty() {
+                "0".to_string()
+            } else {
+                let cells: Vec<String> = refs
+                    .iter()
+                    .map(|(sheet, column)| format!("{}!{}{}", sheet, column, r))
+                    .collect();
+                format!("({})*B{}", cells.join("+"), r)
+            }
+        };
+        let today = |wanted: bool| -> f64 {
+            category_sheets
+                .iter()
+                .flat_map(|sheet| &sheet.columns)
+                .filter(|(_, is_income, _)| *is_income == wanted)
+                .map(|(_, _, amounts)| amounts[index])
+                .sum()
+        };
+        let (incomes, expenses) = (today(true) * level, today(false) * level);
+
+        let balance_formula = match previous {
+            None => STARTING_CAPITAL.to_string(),
+            Some((previous_incomes, previous_expenses, previous_return)) => {
+                balance = balance + balance.max(0.0) * previous_return - previous_expenses
+                    + previous_incomes;
+                format!("E{p}+MAX(E{p},0)*G{p}-D{p}+C{p}", p = r - 1)
+            }
+        };
+        // An asset allocation sets the return of each year; otherwise it is the input
+        let annual_return = assumptions.return_at(row.age);
+        let return_cell = match assumptions.allocation {
+            Some(_) => Value::Number(annual_return),
+            None => Value::Formula(ANNUAL_RETURN.to_string(), annual_return),
+        };
+        previous = Some((incomes, expenses, annual_return));
+
+        results.rows.push(vec![
+            (Value::Number(row.age as f64), Style::Plain),
+            (
+                Value::Formula(format!("(1+{})^(A{}-{})", INFLATION, r, CURRENT_AGE), level),
+                Style::Factor,
+            ),
+            (
+                Value::Formula(sum_refs(&income_refs), incomes),
+                Style::Amount,
+            ),
+            (
+                Value::Formula(sum_refs(&expense_refs), expenses),
+                Style::Amount,
+            ),
+            (Value::Formula(balance_formula, balance), Style::Amount),
+            (Value::Number(row.balance), Style::Amount),
+            (return_cell, Style::Percent),
+        ]);
+    }
+
+    let mut all_sheets = vec![inputs, results];
+    all_sheets.extend(sheets);
+    all_sheets
+}
+
+fn package(sheets: &[Sheet]) -> Vec<u8> {
+    let mut content_types = String::from(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types"><Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/><Default Extension="xml" ContentType="application/xml"/><Override PartName="/xl/workbook.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml"/><Override PartName="/xl/styles.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.styles+xml"/>"#,
+    );
+    let mut workbook = format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<workbook xmlns="{}" xmlns:r="{}"><sheets>"#,
+        MAIN_NAMESPACE, RELATIONSHIP_NAMESPACE
+    );
+    let mut relationships = String::from(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">"#,
+    );
+    for (index, sheet) in sheets.iter().enumerate() {
+        let n = index + 1;
+        let _ = write!(
+            content_types,
+            r#"<Override PartName="/xl/worksheets/sheet{}.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml"/>"#,
+            n
+        );
+        let _ = write!(
+            workbook,
+            r#"<sheet name="{}" sheetId="{n}" r:id="rId{n}"/>"#,
+            escape_xml(&sheet.name),
+            n = n
+        );
+        let _ = write!(
+            relationships,
+            r#"<Relationship Id="rId{n}" Type="{}/worksheet" Target="worksheets/sheet{n}.xml"/>"#,
+            RELATIONSHIP_NAMESPACE,
+            n = n
+        );
+    }
+    content_types.push_str("</Types>");
+    // Recalculate on open so the cached values never go stale
+    workbook.push_str(r#"</sheets><calcPr calcId="0" fullCalcOnLoad="1"/></workbook>"#);
+    let _ = write!(
+        relationships,
+        r#"<Relationship Id="rId{}" Type="{}/styles" Target="styles.xml"/></Relationships>"#,
+        sheets.len() + 1,
+        RELATIONSHIP_NAMESPACE
+    );
+
+    let mut zip = ZipWriter::new();
+    zip.add("[Content_Types].xml", content_types.as_bytes());
+    zip.add(
+        "_rels/.rels",
+        format!(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="{}/officeDocument" Target="xl/workbook.xml"/></Relationships>"#,
+            RELATIONSHIP_NAMESPACE
+        )
+        .as_bytes(),
+    );
+    zip.add("xl/workbook.xml", workbook.as_bytes());
+    zip.add("xl/_rels/workbook.xml.rels", relationships.as_bytes());
+    zip.add("xl/styles.xml", STYLES.as_bytes());
+    for (index, sheet) in sheets.iter().enumerate() {
+        zip.add(
+            &format!("xl/worksheets/sheet{}.xml", index + 1),
+            sheet.to_xml().as_bytes(),
+        );
+    }
+    zip.finish()
+}
+
+/// Spreadsheet column letters: A to Z, then AA, AB and so on.
+pub fn column_name(index: usize) -> String {
+    let mut name = String::new();
+    let mut index = index + 1;
+    while index > 0 {
+        let remainder = (index - 1) % 26;
+        name.insert(0, (b'A' + remainder as u8) as char);
+        index = (index - 1) / 26;
+    }
+    name
+}
+
+// Sheet names are at most 31 characters, unique ignoring case and free of []:*?/\
+fn sheet_name(wanted: &str, used: &mut Vec<String>) -> String {
+    let cleaned: String = wanted
+        .chars()
+        .filter(|c| !matches!(c, '[' | ']' | ':' | '*' | '?' | '/' | '\\'))
+        .take(31)
+        .collect();
+    let base = if cleaned.trim().is_empty() {
+        "Sheet".to_string()
+    } else {
+        cleaned.trim().to_string()
+    };
+    let mut name = base.clone();
+    let mut counter = 2;
+    while used.contains(&name.to_lowercase()) {
+        let suffix = format!(" ({})", counter);
+        name = base
+            .chars()
+            .take(31 - suffix.chars().count())
+            .collect::<String>()
+            + &suffix;
+        counter += 1;
+    }
+    used.push(name.to_lowercase());
+    name
+}
+
+fn quote_sheet(name: &str) -> String {
+    format!("'{}'", name.replace('\'', "''"))
+}
+
+fn finite(value: f64) -> f64 {
+    if value.is_finite() { value } else { 0.0 }
+}
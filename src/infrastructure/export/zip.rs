@@ -0,0 +1,93 @@
+use crate::infrastructure::export::deflate::{crc32, deflate};
+
+// DOS date of 1980-01-01 with time 00:00, so the same input always gives the same archive
+const DOS_DATE: u16 = (1 << 5) | 1;
+const DOS_TIME: u16 = 0;
+const DEFLATED: u16 = 8;
+
+struct Entry {
+    name: String,
+    crc: u32,
+    compressed_size: u32,
+    size: u32,
+    offset: u32,
+}
+
+/// Builds a ZIP archive in memory, compressing every file with DEFLATE.
+pub struct ZipWriter {
+    data: Vec<u8>,
+    entries: Vec<Entry>,
+}
+
+impl Default for ZipWriter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ZipWriter {
+    pub fn new() -> Self {
+        ZipWriter {
+            data: Vec::new(),
+            entries: Vec::new(),
+        }
+    }
+
+    pub fn add(&mut self, name: &str, contents: &[u8]) {
+        let compressed = deflate(contents);
+        let entry = Entry {
+            name: name.to_string(),
+            crc: crc32(contents),
+            compressed_size: compressed.len() as u32,
+            size: contents.len() as u32,
+            offset: self.data.len() as u32,
+        };
+
+        self.data.extend(0x0403_4b50u32.to_le_bytes());
+        self.data.extend(20u16.to_le_bytes()); // version needed to extract
+        self.data.extend(0u16.to_le_bytes()); // flags
+        self.data.extend(DEFLATED.to_le_bytes());
+        self.data.extend(DOS_TIME.to_le_bytes());
+        self.data.extend(DOS_DATE.to_le_bytes());
+        self.data.extend(entry.crc.to_le_bytes());
+        self.data.extend(entry.compressed_size.to_le_bytes());
+        self.data.extend(entry.size.to_le_bytes());
+        self.data.extend((name.len() as u16).to_le_bytes());
+        self.data.extend(0u16.to_le_bytes()); // extra field length
+        self.data.extend_from_slice(name.as_bytes());
+        self.data.extend(compressed);
+        self.entries.push(entry);
+    }
+
+    /// Writes the central directory and returns the archive.
+    pub fn finish(mut self) -> Vec<u8> {
+        let directory_offset = self.data.len() as u32;
+        for entry in &self.entries {
+            self.data.extend(0x0201_4b50u32.to_le_bytes());
+            self.data.extend(20u16.to_le_bytes()); // version made by
+            self.data.extend(20u16.to_le_bytes()); // version needed to extract
+            self.data.extend(0u16.to_le_bytes()); // flags
+            self.data.extend(DEFLATED.to_le_bytes());
+            self.data.extend(DOS_TIME.to_le_bytes());
+            self.data.extend(DOS_DATE.to_le_bytes());
+            self.data.extend(entry.crc.to_le_bytes());
+            self.data.extend(entry.compressed_size.to_le_bytes());
+            self.data.extend(entry.size.to_le_bytes());
+            self.data.extend((entry.name.len() as u16).to_le_bytes());
+            // Extra field, comment, disk number, internal and external attributes
+            self.data.extend([0; 12]);
+            self.data.extend(entry.offset.to_le_bytes());
+            self.data.extend_from_slice(entry.name.as_bytes());
+        }
+        let directory_size = self.data.len() as u32 - directory_offset;
+
+        self.data.extend(0x0605_4b50u32.to_le_bytes());
+        self.data.extend([0; 4]); // disk numbers
+        self.data.extend((self.entries.len() as u16).to_le_bytes());
+        self.data.extend((self.entries.len() as u16).to_le_bytes());
+        self.data.extend(directory_size.to_le_bytes());
+        self.data.extend(directory_offset.to_le_bytes());
+        self.data.extend(0u16.to_le_bytes()); // comment length
+        self.data
+    }
+}
@@ -0,0 +1,318 @@
+use crate::domain::{Date, Transaction};
+use std::fs;
+use std::path::Path;
+
+/// A column picked either by its header name or by its zero-based position.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Column {
+    Index(usize),
+    Name(String),
+}
+
+impl Column {
+    /// Reads a number as a position and anything else as a header name.
+    pub fn parse(text: &str) -> Self {
+        let text = text.trim();
+        text.parse::<usize>()
+            .map_or_else(|_| Column::Name(text.to_string()), Column::Index)
+    }
+
+    fn resolve(&self, header: Option<&[String]>) -> Result<usize, String> {
+        match self {
+            Column::Index(index) => Ok(*index),
+            Column::Name(name) => header
+                .and_then(|header| {
+                    header
+                        .iter()
+                        .position(|field| field.trim().eq_ignore_ascii_case(name))
+                })
+                .ok_or_else(|| format!("No column named \"{}\"", name)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DateFormat {
+    YearMonthDay,
+    DayMonthYear,
+    MonthDayYear,
+}
+
+impl DateFormat {
+    pub const ALL: [DateFormat; 3] = [
+        DateFormat::YearMonthDay,
+        DateFormat::DayMonthYear,
+        DateFormat::MonthDayYear,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            DateFormat::YearMonthDay => "YYYY-MM-DD",
+            DateFormat::DayMonthYear => "DD.MM.YYYY",
+            DateFormat::MonthDayYear => "MM/DD/YYYY",
+        }
+    }
+}
+
+/// Where the amount of a transaction comes from and which way round its sign is.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AmountColumns {
+    /// One column holding signed amounts.
+    Signed {
+        column: Column,
+        /// Set for exports that list money leaving the account as positive.
+        positive_is_expense: bool,
+    },
+    /// Separate columns for money out and money in; whichever is filled is used.
+    DebitCredit { debit: Column, credit: Column },
+}
+
+/// Describes the layout of a bank export.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CsvMapping {
+    pub delimiter: char,
+    pub has_header: bool,
+    pub date_column: Column,
+    pub description_column: Column,
+    pub amount: AmountColumns,
+    pub date_format: DateFormat,
+    /// Amounts are written as `1.234,56` rather than `1,234.56`.
+    pub decimal_comma: bool,
+}
+
+impl Default for CsvMapping {
+    fn default() -> Self {
+        CsvMapping {
+            delimiter: ',',
+            has_header: true,
+            date_column: Column::Index(0),
+            description_column: Column::Index(1),
+            amount: AmountColumns::Signed {
+                column: Column::Index(2),
+                positive_is_expense: false,
+            },
+            date_format: DateFormat::YearMonthDay,
+            decimal_comma: false,
+        }
+    }
+}
+
+impl CsvMapping {
+    /// Guesses the delimiter and columns from the first line of an export.
+    pub fn detect(text: &str) -> Self {
+        let first_line = text.lines().next().unwrap_or_default();
+        let delimiter = [';', '\t', ',']
+            .into_iter()
+            .max_by_key(|delimiter| first_line.matches(*delimiter).count())
+            .unwrap_or(',');
+        let mut mapping = CsvMapping {
+            delimiter,
+            decimal_comma: delimiter == ';',
+            ..Default::default()
+        };
+
+        let header = parse_rows(first_line, delimiter)
+            .into_iter()
+            .next()
+            .unwrap_or_default();
+        let find = |names: &[&str]| {
+            header.iter().position(|field| {
+                field
+                    .to_lowercase()
+                    .split(|c: char| !c.is_alphanumeric())
+                    .any(|word| names.contains(&word))
+            })
+        };
+        // Short names such as "in" only count as the whole header, not as in "Amount in EUR"
+        let find_header = |names: &[&str]| {
+            header
+                .iter()
+                .position(|field| names.contains(&field.trim().to_lowercase().as_str()))
+        };
+
+        if let Some(date) = find(&["date"]) {
+            mapping.date_column = Column::Index(date);
+        }
+        if let Some(description) = find(&["description", "payee", "memo", "details", "name"]) {
+            mapping.description_column = Column::Index(description);
+        }
+        if let (Some(debit), Some(credit)) = (
+            find(&["debit", "withdrawal"])
+                .or_else(|| find_header(&["out", "money out", "paid out"])),
+            find(&["credit", "deposit"]).or_else(|| find_header(&["in", "money in", "paid in"])),
+        ) {
+            mapping.amount = AmountColumns::DebitCredit {
+                debit: Column::Index(debit),
+                credit: Column::Index(credit),
+            };
+        } else if let Some(amount) = find(&["amount", "value"]) {
+            mapping.amount = AmountColumns::Signed {
+                column: Column::Index(amount),
+                positive_is_expense: false,
+            };
+        }
+        // Headers are recognized by their names, or by not holding any dates or amounts
+        mapping.has_header = find(&["date", "amount", "description"]).is_some()
+            || !first_line.chars().any(|c| c.is_ascii_digit());
+        mapping
+    }
+}
+
+/// Splits CSV text into rows of fields. Quoted fields may contain the delimiter, doubled
+/// quotes and line breaks.
+pub fn parse_rows(text: &str, delimiter: char) -> Vec<Vec<String>> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => in_quotes = !in_quotes,
+            c if c == delimiter && !in_quotes => row.push(std::mem::take(&mut field)),
+            '\r' if !in_quotes => {}
+            '\n' if !in_quotes => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            c => field.push(c),
+        }
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+
+    rows.retain(|row| row.iter().any(|field| !field.trim().is_empty()));
+    rows
+}
+
+/// Reads a date written with any separator, e.g. `2024-03-01`, `01.03.2024` or `3/1/24`.
+pub fn parse_date(text: &str, format: DateFormat) -> Result<Date, String> {
+    let parts: Vec<&str> = text
+        .trim()
+        .split(|c: char| !c.is_ascii_digit())
+        .filter(|part| !part.is_empty())
+        .collect();
+    let numbers: Vec<u32> = parts.iter().filter_map(|part| part.parse().ok()).collect();
+    let [a, b, c] = numbers[..] else {
+        return Err(format!("Invalid date \"{}\"", text.trim()));
+    };
+    let (year, month, day) = match format {
+        DateFormat::YearMonthDay => (a, b, c),
+        DateFormat::DayMonthYear => (c, b, a),
+        DateFormat::MonthDayYear => (c, a, b),
+    };
+    // Two-digit years are taken to be in this century
+    let year = if year < 100 { year + 2000 } else { year };
+    Date::new(year as i32, month, day).ok_or_else(|| format!("Invalid date \"{}\"", text.trim()))
+}
+
+/// Reads an amount such as `-1,234.56`, `1.234,56 EUR`, `$12.00`, `12.00-` or `(12.00)`.
+pub fn parse_amount(text: &str, decimal_comma: bool) -> Result<f64, String> {
+    let trimmed = text.trim();
+    let invalid = || format!("Invalid amount \"{}\"", trimmed);
+    let (body, parenthesised) = match trimmed.strip_prefix('(').and_then(|t| t.strip_suffix(')')) {
+        Some(inner) => (inner, true),
+        None => (trimmed, false),
+    };
+    let decimal = if decimal_comma { ',' } else { '.' };
+
+    // A minus sign only counts before or after the number, never inside it
+    let is_number = |c: char| c.is_ascii_digit() || c == decimal;
+    let start = body.find(is_number).ok_or_else(invalid)?;
+    let end = body.rfind(is_number).map_or(start, |end| end + 1);
+    let number = &body[start..end];
+    if number.contains('-') {
+        return Err(invalid());
+    }
+    let negative = parenthesised || body[..start].contains('-') || body[end..].contains('-');
+
+    let digits: String = number
+        .chars()
+        .filter(|c| c.is_ascii_digit() || *c == decimal)
+        .map(|c| if c == decimal { '.' } else { c })
+        .collect();
+    let value = digits.parse::<f64>().map_err(|_| invalid())?;
+    Ok(if negative { -value } else { value })
+}
+
+/// Reads the transactions of a bank export. Rows whose amount fields are all empty are skipped.
+pub fn transactions_from_csv(text: &str, mapping: &CsvMapping) -> Result<Vec<Transaction>, String> {
+    let mut rows = parse_rows(text, mapping.delimiter).into_iter();
+    let header = if mapping.has_header {
+        rows.next()
+    } else {
+        None
+    };
+    let header = header.as_deref();
+
+    let date_column = mapping.date_column.resolve(header)?;
+    let description_column = mapping.description_column.resolve(header)?;
+    let amount_columns = match &mapping.amount {
+        AmountColumns::Signed { column, .. } => (column.resolve(header)?, None),
+        AmountColumns::DebitCredit { debit, credit } => {
+            (debit.resolve(header)?, Some(credit.resolve(header)?))
+        }
+    };
+
+    let mut transactions = Vec::new();
+    for (index, row) in rows.enumerate() {
+        let line = index + 1 + usize::from(mapping.has_header);
+        let field = |column: usize| row.get(column).map_or("", |field| field.trim());
+        let amount = |column: usize| {
+            let text = field(column);
+            if text.is_empty() {
+                Ok(None)
+            } else {
+                parse_amount(text, mapping.decimal_comma).map(Some)
+            }
+        };
+        let with_line = |e: String| format!("Line {}: {}", line, e);
+
+        let amount = match (&mapping.amount, amount_columns) {
+            (
+                AmountColumns::Signed {
+                    positive_is_expense,
+                    ..
+                },
+                (column, _),
+            ) => amount(column)
+                .map_err(with_line)?
+                .map(|value| if *positive_is_expense { -value } else { value }),
+            (_, (debit, credit)) => {
+                let debit = amount(debit).map_err(with_line)?;
+                let credit = credit.map_or(Ok(None), amount).map_err(with_line)?;
+                match (debit, credit) {
+                    (Some(debit), _) if debit != 0.0 => Some(-debit.abs()),
+                    (_, Some(credit)) => Some(credit.abs()),
+                    (debit, None) => debit,
+                }
+            }
+        };
+        let Some(amount) = amount else {
+            continue;
+        };
+
+        let date = parse_date(field(date_column), mapping.date_format).map_err(with_line)?;
+        transactions.push(Transaction::new(
+            date,
+            amount,
+            field(description_column).to_string(),
+        ));
+    }
+    Ok(transactions)
+}
+
+pub fn load_csv_transactions(
+    path: &Path,
+    mapping: &CsvMapping,
+) -> Result<Vec<Transaction>, String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("Failed to read file: {}", e))?;
+    transactions_from_csv(&text, mapping)
+}
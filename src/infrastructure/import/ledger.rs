@@ -0,0 +1,222 @@
+use crate::domain::{Category, Date, Transaction};
+use crate::infrastructure::import::csv_import::{DateFormat, parse_amount, parse_date};
+use crate::infrastructure::import::statement::ImportedStatement;
+use std::collections::BTreeMap;
+
+// Top-level accounts whose balances make up the starting capital
+const CAPITAL_ROOTS: [&str; 2] = ["assets", "liabilities"];
+// Top-level accounts whose postings become expenses and incomes
+const EXPENSE_ROOTS: [&str; 1] = ["expenses"];
+const INCOME_ROOTS: [&str; 3] = ["income", "revenue", "revenues"];
+
+/// One posting of a journal entry. Amounts follow the ledger convention: money flowing into an
+/// account is positive, so incomes are booked as negative amounts.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Posting {
+    pub account: String,
+    pub amount: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct JournalEntry {
+    pub date: Date,
+    pub description: String,
+    pub postings: Vec<Posting>,
+}
+
+/// A plain-text accounting journal in ledger or hledger format. Only the parts needed to seed a
+/// simulation are read: dated entries with their postings in a single currency.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Journal {
+    pub entries: Vec<JournalEntry>,
+}
+
+impl Journal {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut entries = Vec::new();
+        // Postings of periodic (`~`) and automated (`=`) entries are skipped with them
+        let mut current: Option<(JournalEntry, Option<usize>)> = None;
+        let mut skipping = false;
+
+        for (index, raw_line) in text.lines().enumerate() {
+            let with_line = |e: String| format!("Line {}: {}", index + 1, e);
+            let line = strip_comment(raw_line);
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            if raw_line.starts_with([' ', '\t']) {
+                if skipping {
+                    continue;
+                }
+                let Some((entry, missing)) = current.as_mut() else {
+                    continue;
+                };
+                let posting = parse_posting(line.trim()).map_err(with_line)?;
+                match posting {
+                    (account, Some(amount)) => entry.postings.push(Posting { account, amount }),
+                    (account, None) if missing.is_none() => {
+                        *missing = Some(entry.postings.len());
+                        entry.postings.push(Posting {
+                            account,
+                            amount: 0.0,
+                        });
+                    }
+                    _ => return Err(with_line("More than one posting without an amount".into())),
+                }
+                continue;
+            }
+
+            if let Some(done) = current.take() {
+                entries.push(balance_entry(done));
+            }
+            skipping = !line.starts_with(|c: char| c.is_ascii_digit());
+            if !skipping {
+                current = Some((parse_header(line).map_err(with_line)?, None));
+            }
+        }
+        if let Some(done) = current.take() {
+            entries.push(balance_entry(done));
+        }
+
+        Ok(Journal { entries })
+    }
+
+    /// Final balance of every account.
+    pub fn balances(&self) -> BTreeMap<String, f64> {
+        let mut balances = BTreeMap::new();
+        for posting in self.entries.iter().flat_map(|entry| &entry.postings) {
+            *balances.entry(posting.account.clone()).or_insert(0.0) += posting.amount;
+        }
+        balances
+    }
+
+    /// Net worth held in asset accounts less what is owed on liability accounts.
+    pub fn capital(&self) -> f64 {
+        self.balances()
+            .iter()
+            .filter(|(account, _)| CAPITAL_ROOTS.contains(&root(account).as_str()))
+            .map(|(_, balance)| balance)
+            .sum()
+    }
+
+    /// Expense and income postings as transactions, categorized by their account below the
+    /// top level (`Expenses:Housing:Rent` is filed under `Housing > Rent`).
+    pub fn transactions(&self) -> Vec<Transaction> {
+        let mut transactions = Vec::new();
+        for entry in &self.entries {
+            for posting in &entry.postings {
+                let root = root(&posting.account);
+                if !EXPENSE_ROOTS.contains(&root.as_str()) && !INCOME_ROOTS.contains(&root.as_str())
+                {
+                    continue;
+                }
+                let category = Category::new(
+                    posting
+                        .account
+                        .split(':')
+                        .skip(1)
+                        .map(String::from)
+                        .collect(),
+                );
+                let description = if entry.description.is_empty() {
+                    posting.account.clone()
+                } else {
+                    entry.description.clone()
+                };
+                transactions.push(
+                    Transaction::new(entry.date, -posting.amount, description)
+                        .with_category(category),
+                );
+            }
+        }
+        transactions
+    }
+}
+
+pub fn statement_from_journal(text: &str) -> Result<ImportedStatement, String> {
+    let journal = Journal::parse(text)?;
+    if journal.entries.is_empty() {
+        return Err("No journal entries found".to_string());
+    }
+    Ok(ImportedStatement {
+        transactions: journal.transactions(),
+        balance: Some(journal.capital()),
+    })
+}
+
+fn root(account: &str) -> String {
+    account
+        .split(':')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_lowercase()
+}
+
+fn strip_comment(line: &str) -> &str {
+    let line = line.split(';').next().unwrap_or_default();
+    // hledger also starts comments with `#` or `*` at the beginning of a line
+    if line.starts_with(['#', '*', '%', '|']) {
+        ""
+    } else {
+        line.trim_end()
+    }
+}
+
+// `2024-01-31 * (code) Description`, optionally with a secondary date after `=`
+fn parse_header(line: &str) -> Result<JournalEntry, String> {
+    let (date, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+    let date = date.split('=').next().unwrap_or_default();
+    let date = parse_date(date, DateFormat::YearMonthDay)?;
+
+    let mut description = rest.trim().trim_start_matches(['*', '!']).trim_start();
+    if description.starts_with('(')
+        && let Some(end) = description.find(')')
+    {
+        description = description[end + 1..].trim_start();
+    }
+    // hledger writes `payee | note`; the payee is what repeats
+    let description = description.split(" | ").next().unwrap_or_default().trim();
+
+    Ok(JournalEntry {
+        date,
+        description: description.to_string(),
+        postings: Vec::new(),
+    })
+}
+
+// `Account:Name  $1,200.00`; the account ends at two spaces or a tab
+fn parse_posting(line: &str) -> Result<(String, Option<f64>), String> {
+    let split = [line.find("  "), line.find('\t')]
+        .into_iter()
+        .flatten()
+        .min();
+    let (account, amount) = match split {
+        Some(split) => (&line[..split], line[split..].trim()),
+        None => (line, ""),
+    };
+    let account = account
+        .trim()
+        .trim_start_matches(['*', '!'])
+        .trim()
+        .trim_matches(['(', ')', '[', ']'])
+        .to_string();
+
+    // Prices (`@`) and balance assertions (`=`) after the amount are ignored
+    let amount = amount.split(['@', '=']).next().unwrap_or_default().trim();
+    if amount.is_empty() {
+        Ok((account, None))
+    } else {
+        Ok((account, Some(parse_amount(amount, false)?)))
+    }
+}
+
+// Fills in the posting left without an amount so that the entry sums to zero
+fn balance_entry((mut entry, missing): (JournalEntry, Option<usize>)) -> JournalEntry {
+    if let Some(index) = missing {
+        let total: f64 = entry.postings.iter().map(|posting| posting.amount).sum();
+        entry.postings[index].amount = -total;
+    }
+    entry
+}
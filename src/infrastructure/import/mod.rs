@@ -0,0 +1,13 @@
+pub mod csv_import;
+pub mod ledger;
+pub mod ofx;
+pub mod qif;
+pub mod statement;
+pub use csv_import::{
+    AmountColumns, Column, CsvMapping, DateFormat, load_csv_transactions, parse_amount, parse_date,
+    parse_rows, transactions_from_csv,
+};
+pub use ledger::{Journal, JournalEntry, Posting, statement_from_journal};
+pub use ofx::statement_from_ofx;
+pub use qif::statement_from_qif;
+pub use statement::{ImportFormat, ImportedStatement, import_statement, load_statement};
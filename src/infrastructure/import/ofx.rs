@@ -0,0 +1,76 @@
+use crate::domain::{Date, Transaction};
+use crate::infrastructure::import::csv_import::parse_amount;
+use crate::infrastructure::import::statement::ImportedStatement;
+
+/// Reads the transactions and ledger balances of an OFX file. Both the SGML flavour of OFX 1.x,
+/// where tags are not closed, and the XML flavour of OFX 2.x are understood.
+pub fn statement_from_ofx(text: &str) -> Result<ImportedStatement, String> {
+    let mut transactions = Vec::new();
+    for block in blocks(text, "STMTTRN") {
+        let amount = tag_value(block, "TRNAMT").ok_or("Transaction without TRNAMT")?;
+        let amount = parse_amount(amount, false)?;
+        let posted = tag_value(block, "DTPOSTED").ok_or("Transaction without DTPOSTED")?;
+        let date = parse_ofx_date(posted)?;
+        let description = tag_value(block, "NAME")
+            .or_else(|| tag_value(block, "MEMO"))
+            .unwrap_or_default();
+        transactions.push(Transaction::new(date, amount, description.to_string()));
+    }
+
+    // Each statement reports the balance of its account; several accounts are added up
+    let mut balance = None;
+    for block in blocks(text, "LEDGERBAL") {
+        if let Some(amount) = tag_value(block, "BALAMT") {
+            *balance.get_or_insert(0.0) += parse_amount(amount, false)?;
+        }
+    }
+
+    if transactions.is_empty() && balance.is_none() {
+        return Err("No OFX transactions found".to_string());
+    }
+    Ok(ImportedStatement {
+        transactions,
+        balance,
+    })
+}
+
+// Parts of `text` between `<TAG>` and `</TAG>`, or up to the next `<TAG>` when unclosed
+fn blocks<'a>(text: &'a str, tag: &str) -> Vec<&'a str> {
+    let upper = text.to_ascii_uppercase();
+    let open = format!("<{}>", tag);
+    let close = format!("</{}>", tag);
+    let mut blocks = Vec::new();
+    let mut position = 0;
+    while let Some(start) = upper[position..].find(&open) {
+        let start = position + start + open.len();
+        let end = [&close, &open]
+            .iter()
+            .filter_map(|marker| upper[start..].find(marker.as_str()))
+            .min()
+            .map_or(text.len(), |end| start + end);
+        blocks.push(&text[start..end]);
+        position = end;
+    }
+    blocks
+}
+
+// Value of `<TAG>value` up to the next tag or line break
+fn tag_value<'a>(block: &'a str, tag: &str) -> Option<&'a str> {
+    let open = format!("<{}>", tag);
+    let start = block.to_ascii_uppercase().find(&open)? + open.len();
+    let value = block[start..]
+        .split(['<', '\n', '\r'])
+        .next()
+        .unwrap_or_default()
+        .trim();
+    (!value.is_empty()).then_some(value)
+}
+
+// OFX dates start with YYYYMMDD and may carry a time and time zone
+fn parse_ofx_date(text: &str) -> Result<Date, String> {
+    let invalid = || format!("Invalid OFX date \"{}\"", text);
+    let digits = text.get(..8).ok_or_else(invalid)?;
+    let number =
+        |range: std::ops::Range<usize>| digits[range].parse::<u32>().map_err(|_| invalid());
+    Date::new(number(0..4)? as i32, number(4..6)?, number(6..8)?).ok_or_else(invalid)
+}
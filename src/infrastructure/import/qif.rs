@@ -0,0 +1,78 @@
+use crate::domain::{Category, Date, Transaction};
+use crate::infrastructure::import::csv_import::{DateFormat, parse_amount, parse_date};
+use crate::infrastructure::import::statement::ImportedStatement;
+
+/// Reads a QIF bank or cash register. QIF has no balance of its own, so one is only reported
+/// when the file starts from an "Opening Balance" record; that record is not a transaction.
+pub fn statement_from_qif(
+    text: &str,
+    date_format: DateFormat,
+) -> Result<ImportedStatement, String> {
+    let mut transactions = Vec::new();
+    let mut opening_balance = None;
+    let mut record = QifRecord::default();
+
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim_end();
+        let with_line = |e: String| format!("Line {}: {}", index + 1, e);
+        let Some(code) = line.chars().next() else {
+            continue;
+        };
+        let value = line[code.len_utf8()..].trim();
+        match code {
+            '!' => {}
+            'D' => record.date = Some(parse_date(value, date_format).map_err(with_line)?),
+            'T' | 'U' => record.amount = Some(parse_amount(value, false).map_err(with_line)?),
+            'P' => record.payee = value.to_string(),
+            'M' => record.memo = value.to_string(),
+            // Bracketed categories are transfers between accounts
+            'L' if !value.starts_with('[') => record.category = Some(qif_category(value)),
+            '^' => {
+                let record = std::mem::take(&mut record);
+                let (Some(date), Some(amount)) = (record.date, record.amount) else {
+                    continue;
+                };
+                if record.payee.eq_ignore_ascii_case("opening balance") {
+                    opening_balance = Some(amount);
+                    continue;
+                }
+                let description = if record.payee.is_empty() {
+                    record.memo
+                } else {
+                    record.payee
+                };
+                let mut transaction = Transaction::new(date, amount, description);
+                if let Some(category) = record.category {
+                    transaction = transaction.with_category(category);
+                }
+                transactions.push(transaction);
+            }
+            _ => {}
+        }
+    }
+
+    if transactions.is_empty() && opening_balance.is_none() {
+        return Err("No QIF transactions found".to_string());
+    }
+    let balance =
+        opening_balance.map(|opening| opening + transactions.iter().map(|t| t.amount).sum::<f64>());
+    Ok(ImportedStatement {
+        transactions,
+        balance,
+    })
+}
+
+#[derive(Default)]
+struct QifRecord {
+    date: Option<Date>,
+    amount: Option<f64>,
+    payee: String,
+    memo: String,
+    category: Option<Category>,
+}
+
+// QIF separates category levels with colons and appends classes after a slash
+fn qif_category(value: &str) -> Category {
+    let path = value.split('/').next().unwrap_or_default();
+    Category::new(path.split(':').map(String::from).collect())
+}
@@ -0,0 +1,87 @@
+use crate::domain::Transaction;
+use crate::infrastructure::import::csv_import::{CsvMapping, DateFormat, transactions_from_csv};
+use crate::infrastructure::import::ledger::statement_from_journal;
+use crate::infrastructure::import::ofx::statement_from_ofx;
+use crate::infrastructure::import::qif::statement_from_qif;
+use std::fs;
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ImportFormat {
+    Csv,
+    Ofx,
+    Qif,
+    Ledger,
+}
+
+impl ImportFormat {
+    pub const ALL: [ImportFormat; 4] = [
+        ImportFormat::Csv,
+        ImportFormat::Ofx,
+        ImportFormat::Qif,
+        ImportFormat::Ledger,
+    ];
+
+    /// Picks the format from a file extension; anything unknown is read as CSV.
+    pub fn from_path(path: &Path) -> Self {
+        let extension = path
+            .extension()
+            .map(|extension| extension.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        match extension.as_str() {
+            "ofx" | "qfx" => ImportFormat::Ofx,
+            "qif" => ImportFormat::Qif,
+            "journal" | "ledger" | "hledger" | "dat" => ImportFormat::Ledger,
+            _ => ImportFormat::Csv,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ImportFormat::Csv => "CSV",
+            ImportFormat::Ofx => "OFX",
+            ImportFormat::Qif => "QIF",
+            ImportFormat::Ledger => "Ledger journal",
+        }
+    }
+}
+
+/// Transactions read from a statement or journal, with the account balance if the file has one.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ImportedStatement {
+    pub transactions: Vec<Transaction>,
+    pub balance: Option<f64>,
+}
+
+/// Reads a statement in any supported format. The CSV mapping is only used for CSV files, and
+/// its date format also applies to QIF dates.
+pub fn import_statement(
+    text: &str,
+    format: ImportFormat,
+    mapping: &CsvMapping,
+) -> Result<ImportedStatement, String> {
+    match format {
+        ImportFormat::Csv => Ok(ImportedStatement {
+            transactions: transactions_from_csv(text, mapping)?,
+            balance: None,
+        }),
+        ImportFormat::Ofx => statement_from_ofx(text),
+        ImportFormat::Qif => statement_from_qif(text, mapping.date_format),
+        ImportFormat::Ledger => statement_from_journal(text),
+    }
+}
+
+/// Reads a file, picking its format from the extension. CSV files are read with a detected
+/// column mapping and QIF files with US dates.
+pub fn load_statement(path: &Path) -> Result<ImportedStatement, String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("Failed to read file: {}", e))?;
+    let format = ImportFormat::from_path(path);
+    let mapping = match format {
+        ImportFormat::Csv => CsvMapping::detect(&text),
+        _ => CsvMapping {
+            date_format: DateFormat::MonthDayYear,
+            ..Default::default()
+        },
+    };
+    import_statement(&text, format, &mapping)
+}
@@ -0,0 +1,7 @@
+pub mod scenario_file;
+pub mod templates;
+pub use scenario_file::{load_scenario, save_scenario, scenario_from_json, scenario_to_json};
+pub use templates::{
+    ScenarioTemplate, TemplateSource, available_templates, builtin_templates, find_template,
+    user_template_dir,
+};
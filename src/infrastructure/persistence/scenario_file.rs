@@ -0,0 +1,28 @@
+use crate::domain::Scenario;
+use std::fs;
+use std::path::Path;
+
+pub fn scenario_to_json(scenario: &Scenario) -> Result<String, String> {
+    serde_json::to_string_pretty(scenario).map_err(|e| format!("Failed to encode scenario: {}", e))
+}
+
+pub fn scenario_from_json(json: &str) -> Result<Scenario, String> {
+    let mut scenario: Scenario =
+        serde_json::from_str(json).map_err(|e| format!("Invalid scenario file: {}", e))?;
+    // Balances are not stored, start projecting again from the person's capital
+    scenario.simulator.invalidate();
+    // Hand-written files may file items under categories missing from the tree
+    scenario.simulator.sync_categories();
+    Ok(scenario)
+}
+
+pub fn load_scenario(path: &Path) -> Result<Scenario, String> {
+    let json = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    scenario_from_json(&json)
+}
+
+pub fn save_scenario(path: &Path, scenario: &Scenario) -> Result<(), String> {
+    let json = scenario_to_json(scenario)?;
+    fs::write(path, json).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
@@ -0,0 +1,142 @@
+use crate::domain::Scenario;
+use crate::infrastructure::persistence::scenario_file::scenario_from_json;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Templates shipped with the application, as (id, JSON) pairs
+const BUILTIN_TEMPLATES: [(&str, &str); 4] = [
+    (
+        "young-renter",
+        include_str!("../../../templates/young-renter.json"),
+    ),
+    (
+        "homeowner-with-mortgage",
+        include_str!("../../../templates/homeowner-with-mortgage.json"),
+    ),
+    (
+        "family-with-two-kids",
+        include_str!("../../../templates/family-with-two-kids.json"),
+    ),
+    (
+        "early-retiree",
+        include_str!("../../../templates/early-retiree.json"),
+    ),
+];
+
+/// Environment variable that points at a directory of extra template files.
+pub const TEMPLATE_DIR_VAR: &str = "LIFE_SIMULATOR_TEMPLATES";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TemplateSource {
+    Builtin,
+    File(PathBuf),
+}
+
+/// A scenario file that new scenarios can be started from.
+#[derive(Debug, Clone)]
+pub struct ScenarioTemplate {
+    /// File name without the extension, used to pick the template on the command line.
+    pub id: String,
+    pub source: TemplateSource,
+    json: String,
+}
+
+impl ScenarioTemplate {
+    /// Builds a fresh scenario from the template.
+    pub fn instantiate(&self) -> Result<Scenario, String> {
+        scenario_from_json(&self.json).map_err(|e| format!("Template \"{}\": {}", self.id, e))
+    }
+
+    /// The scenario name stored in the template, or its id if it cannot be read.
+    pub fn title(&self) -> String {
+        self.instantiate()
+            .map_or_else(|_| self.id.clone(), |scenario| scenario.name)
+    }
+}
+
+pub fn builtin_templates() -> Vec<ScenarioTemplate> {
+    BUILTIN_TEMPLATES
+        .iter()
+        .map(|(id, json)| ScenarioTemplate {
+            id: id.to_string(),
+            source: TemplateSource::Builtin,
+            json: json.to_string(),
+        })
+        .collect()
+}
+
+/// Directory searched for user templates: `$LIFE_SIMULATOR_TEMPLATES`, otherwise
+/// `$XDG_CONFIG_HOME/life_simulator/templates` or `~/.config/life_simulator/templates`.
+pub fn user_template_dir() -> Option<PathBuf> {
+    if let Some(dir) = env::var_os(TEMPLATE_DIR_VAR) {
+        return Some(PathBuf::from(dir));
+    }
+    let config = env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".config")))
+        .or_else(|| env::var_os("APPDATA").map(PathBuf::from))?;
+    Some(config.join("life_simulator").join("templates"))
+}
+
+/// Reads every `*.json` file in `dir` as a template. A missing directory has no templates.
+pub fn templates_in_dir(dir: &Path) -> Result<Vec<ScenarioTemplate>, String> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Ok(Vec::new());
+    };
+    let mut templates = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().is_none_or(|extension| extension != "json") {
+            continue;
+        }
+        let json = fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let id = path
+            .file_stem()
+            .map_or_else(String::new, |stem| stem.to_string_lossy().into_owned());
+        templates.push(ScenarioTemplate {
+            id,
+            source: TemplateSource::File(path),
+            json,
+        });
+    }
+    templates.sort_by(|a, b| a.id.cmp(&b.id));
+    Ok(templates)
+}
+
+/// Built-in templates followed by the user's; a user template replaces a built-in one
+/// with the same id.
+pub fn available_templates() -> Result<Vec<ScenarioTemplate>, String> {
+    let user = match user_template_dir() {
+        Some(dir) => templates_in_dir(&dir)?,
+        None => Vec::new(),
+    };
+    let mut templates: Vec<ScenarioTemplate> = builtin_templates()
+        .into_iter()
+        .filter(|builtin| !user.iter().any(|template| template.id == builtin.id))
+        .collect();
+    templates.extend(user);
+    Ok(templates)
+}
+
+/// Finds a template by id, or loads it from a path to a template file.
+pub fn find_template(name: &str) -> Result<ScenarioTemplate, String> {
+    if let Some(template) = available_templates()?
+        .into_iter()
+        .find(|template| template.id == name)
+    {
+        return Ok(template);
+    }
+    let path = Path::new(name);
+    if path.is_file() {
+        let json = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        return Ok(ScenarioTemplate {
+            id: name.to_string(),
+            source: TemplateSource::File(path.to_path_buf()),
+            json,
+        });
+    }
+    Err(format!("Unknown template \"{}\"", name))
+}
@@ -1,18 +1,48 @@
 pub mod cli;
 pub mod domain;
+pub mod infrastructure;
 
 #[cfg(feature = "desktop")]
 pub mod desktop;
 
 #[cfg(test)]
 mod tests {
-    use crate::domain::{Expense, Frequency, LifeSimulator, Person};
+    use crate::domain::{
+        Account, Allocation, AssetAllocation, Assumptions, BranchChange, Category, Child,
+        ChildTemplate, CreditLine, Expense, FireVariant, Frequency, GlidePath, Goal, Horizon,
+        Income, ItemRef, LifeSimulator, LifeTable, Member, Mortality, Mortgage, Pension,
+        PensionRules, Person, Property, Role, Scenario, ScenarioSet, Sex, SimulationConfig,
+        TaxTreatment, TimeStep, WhatIf, WithdrawalStrategy, balance_alert, buy_vs_rent,
+        compare_balances, configured_projection, detect_recurring, evaluate_goals, fire_report,
+        longevity_report, person_from_transactions, projection, retirement_report, weigh_paths,
+    };
+    use crate::infrastructure::export::{
+        Chart, ChartFormat, ReportFormat, SeriesKind, Summary,
+        deflate::{adler32, crc32},
+        format::format_amount,
+        xlsx::column_name,
+        xlsx_formula_balances,
+    };
+    use crate::infrastructure::import::{
+        AmountColumns, Column, CsvMapping, DateFormat, Journal, parse_amount, statement_from_ofx,
+        statement_from_qif, transactions_from_csv,
+    };
+    use crate::infrastructure::persistence::{
+        builtin_templates, scenario_from_json, scenario_to_json,
+    };
 
     #[test]
     fn test_life_simulator_basic_functionality() {
-        // Create a person with initial parameters
-        let person = Person::new("John Doe".to_string(), 25, 50000.0);
+        // Create a person with no starting capital and a yearly salary
+        let person = Person::new("John Doe".to_string(), 25, 0.0);
         let mut simulator = LifeSimulator::new(person);
+        simulator.add_income(Income::new(
+            "Salary".to_string(),
+            50000.0,
+            Frequency::Yearly,
+            25,
+            None,
+        ));
 
         // Add an expense
         let rent_expense = Expense::new("Rent".to_string(), 1000.0, Frequency::Monthly, 25, None);
@@ -28,8 +58,15 @@ mod tests {
 
     #[test]
     fn test_life_simulator_with_ending_expense() {
-        let person = Person::new("Jane Smith".to_string(), 30, 60000.0);
+        let person = Person::new("Jane Smith".to_string(), 30, 0.0);
         let mut simulator = LifeSimulator::new(person);
+        simulator.add_income(Income::new(
+            "Salary".to_string(),
+            60000.0,
+            Frequency::Yearly,
+            30,
+            None,
+        ));
 
         // Add an expense that ends at age 40
         let car_payment = Expense::new(
@@ -51,8 +88,15 @@ mod tests {
 
     #[test]
     fn test_life_simulator_multiple_expenses() {
-        let person = Person::new("Bob Johnson".to_string(), 20, 40000.0);
+        let person = Person::new("Bob Johnson".to_string(), 20, 0.0);
         let mut simulator = LifeSimulator::new(person);
+        simulator.add_income(Income::new(
+            "Salary".to_string(),
+            40000.0,
+            Frequency::Yearly,
+            20,
+            None,
+        ));
 
         // Add multiple expenses
         let rent = Expense::new("Rent".to_string(), 800.0, Frequency::Monthly, 20, None);
@@ -71,8 +115,15 @@ mod tests {
 
     #[test]
     fn test_life_simulator_daily_expense() {
-        let person = Person::new("Alice Brown".to_string(), 22, 45000.0);
+        let person = Person::new("Alice Brown".to_string(), 22, 0.0);
         let mut simulator = LifeSimulator::new(person);
+        simulator.add_income(Income::new(
+            "Salary".to_string(),
+            45000.0,
+            Frequency::Yearly,
+            22,
+            None,
+        ));
 
         // Add a daily expense
         let coffee = Expense::new("Coffee".to_string(), 5.0, Frequency::Daily, 22, None);
@@ -85,4 +136,1526 @@ mod tests {
         // = 45000 - 1825 = 43175
         assert_eq!(balance_at_23, 45000.0 - 1825.0);
     }
+
+    #[test]
+    fn test_income_starts_at_start_age() {
+        let person = Person::new("Carol White".to_string(), 20, 1000.0);
+        let mut simulator = LifeSimulator::new(person);
+
+        let pension = Income::new("Pension".to_string(), 500.0, Frequency::Monthly, 65, None);
+        simulator.add_income(pension);
+
+        assert_eq!(simulator.calculate_balance_at_age(30), 1000.0);
+    }
+
+    #[test]
+    fn test_adding_items_invalidates_cached_balances() {
+        let person = Person::new("Dan Green".to_string(), 30, 0.0);
+        let mut simulator = LifeSimulator::new(person);
+        assert_eq!(simulator.calculate_balance_at_age(35), 0.0);
+
+        let rent = Expense::new("Rent".to_string(), 100.0, Frequency::Monthly, 30, None);
+        simulator.add_expense(rent);
+
+        assert_eq!(simulator.calculate_balance_at_age(35), -6000.0);
+    }
+
+    #[test]
+    fn test_category_totals_roll_up_to_parents() {
+        let person = Person::new("Eve Black".to_string(), 30, 0.0);
+        let mut simulator = LifeSimulator::new(person);
+
+        let rent = Expense::new("Rent".to_string(), 1000.0, Frequency::Monthly, 30, None)
+            .with_category(Category::parse("Housing > Rent"));
+        let power = Expense::new("Power".to_string(), 1000.0, Frequency::Yearly, 30, Some(31))
+            .with_category(Category::parse("Housing > Utilities"))
+            .with_tags(vec!["energy".to_string()]);
+        let fuel = Expense::new("Fuel".to_string(), 2000.0, Frequency::Yearly, 30, None)
+            .with_category(Category::parse("Transport > Fuel"));
+        simulator.add_expense(rent);
+        simulator.add_expense(power);
+        simulator.add_expense(fuel);
+
+        let housing = Category::parse("Housing");
+        let first_year = simulator.category_totals_for_year(30);
+        assert_eq!(first_year.within(&housing).expenses, 13000.0);
+        assert_eq!(first_year.rolled_up(1).iter().count(), 2);
+
+        // Two years: housing 12000 * 2 + 1000, transport 2000 * 2
+        let lifetime = simulator.category_totals_between(30, 32);
+        assert_eq!(lifetime.within(&housing).expenses, 25000.0);
+        assert_eq!(lifetime.expense_share(&housing), 25000.0 / 29000.0);
+        assert!(simulator.get_person().expenses[1].has_tag("Energy"));
+    }
+
+    #[test]
+    fn test_renaming_category_moves_items() {
+        let mut person = Person::new("Frank Gray".to_string(), 30, 0.0);
+        person.add_expense(
+            Expense::new("Rent".to_string(), 900.0, Frequency::Monthly, 30, None)
+                .with_category(Category::parse("Housing > Rent")),
+        );
+
+        person.rename_category(&Category::parse("Housing"), "Home");
+        assert_eq!(person.expenses[0].category, Category::parse("Home > Rent"));
+        assert!(
+            person
+                .categories
+                .contains(&Category::parse("Home > Utilities"))
+        );
+        assert!(!person.categories.contains(&Category::parse("Housing")));
+
+        person.remove_category(&Category::parse("Home"));
+        assert!(person.expenses[0].category.is_uncategorized());
+    }
+
+    #[test]
+    fn test_category_composition_groups_children() {
+        let person = Person::new("Grace Hill".to_string(), 40, 0.0);
+        let mut simulator = LifeSimulator::new(person);
+        for (name, path, amount) in [
+            ("Rent", "Housing > Rent", 800.0),
+            ("Water", "Housing > Utilities > Water", 30.0),
+            ("Power", "Housing > Utilities > Power", 70.0),
+            ("Bus", "Transport", 50.0),
+        ] {
+            simulator.add_expense(
+                Expense::new(name.to_string(), amount, Frequency::Monthly, 40, None)
+                    .with_category(Category::parse(path)),
+            );
+        }
+        simulator.add_expense(Expense::new(
+            "Misc".to_string(),
+            10.0,
+            Frequency::Monthly,
+            40,
+            None,
+        ));
+
+        let year = simulator.category_totals_for_year(40);
+        let roots = year.composition(None);
+        assert_eq!(roots.len(), 3);
+        assert_eq!(roots[0].0, Category::uncategorized());
+        assert_eq!(roots[1].1.expenses, 900.0 * 12.0);
+
+        let housing = year.composition(Some(&Category::parse("Housing")));
+        assert_eq!(housing.len(), 2);
+        assert_eq!(housing[0].0, Category::parse("Housing > Rent"));
+        assert_eq!(housing[1].1.expenses, 100.0 * 12.0);
+    }
+
+    #[test]
+    fn test_scenario_json_round_trip() {
+        let person = Person::new("Hana Ito".to_string(), 30, 5000.0);
+        let mut simulator = LifeSimulator::new(person);
+        simulator.add_expense(
+            Expense::new("Rent".to_string(), 700.0, Frequency::Monthly, 30, Some(40))
+                .with_category(Category::parse("Housing > Rent"))
+                .with_tags(vec!["fixed".to_string()]),
+        );
+        simulator.add_income(Income::new(
+            "Salary".to_string(),
+            30000.0,
+            Frequency::Yearly,
+            30,
+            None,
+        ));
+        let expected = simulator.calculate_balance_at_age(45);
+
+        let json = scenario_to_json(&Scenario::new("Renting".to_string(), simulator)).unwrap();
+        let mut loaded = scenario_from_json(&json).unwrap();
+
+        assert_eq!(loaded.name, "Renting");
+        let person = loaded.simulator.get_person();
+        assert_eq!(
+            person.expenses[0].category,
+            Category::parse("Housing > Rent")
+        );
+        assert_eq!(person.expenses[0].tags, vec!["fixed".to_string()]);
+        assert_eq!(loaded.simulator.calculate_balance_at_age(45), expected);
+    }
+
+    #[test]
+    fn test_compare_balances_reports_delta_per_age() {
+        let mut renting = LifeSimulator::new(Person::new("Ivan".to_string(), 30, 10000.0));
+        renting.add_expense(Expense::new(
+            "Rent".to_string(),
+            1000.0,
+            Frequency::Monthly,
+            30,
+            None,
+        ));
+        let mut buying = LifeSimulator::new(Person::new("Ivan".to_string(), 30, 10000.0));
+        buying.add_expense(Expense::new(
+            "Mortgage".to_string(),
+            1500.0,
+            Frequency::Monthly,
+            30,
+            Some(32),
+        ));
+
+        let deltas = compare_balances(&mut renting, &mut buying, 34);
+
+        assert_eq!(deltas.len(), 5);
+        assert_eq!(deltas[0].delta(), 0.0);
+        assert_eq!(deltas[2].delta(), -12000.0);
+        assert_eq!(deltas[4].delta(), 12000.0);
+    }
+
+    #[test]
+    fn test_scenario_set_keeps_names_unique_and_tracks_active() {
+        let mut scenarios = ScenarioSet::new();
+        let simulator = LifeSimulator::new(Person::new("Jo".to_string(), 30, 0.0));
+        scenarios.add(Scenario::new("Plan".to_string(), simulator.clone()));
+        scenarios.add(Scenario::new("Plan".to_string(), simulator));
+        scenarios.duplicate(0);
+
+        let names: Vec<&str> = scenarios.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["Plan", "Plan 2", "Plan (copy)"]);
+        assert_eq!(scenarios.active_index(), Some(2));
+
+        scenarios.remove(0);
+        assert_eq!(scenarios.active_index(), Some(1));
+        assert_eq!(scenarios.active_scenario().unwrap().name, "Plan (copy)");
+    }
+
+    #[test]
+    fn test_branch_overrides_items_only_after_fork() {
+        let mut scenarios = ScenarioSet::new();
+        let mut base = LifeSimulator::new(Person::new("Kim".to_string(), 40, 0.0));
+        base.add_income(Income::new(
+            "Job".to_string(),
+            50000.0,
+            Frequency::Yearly,
+            40,
+            None,
+        ));
+        scenarios.add(Scenario::new("Base".to_string(), base));
+        scenarios.fork(0, "Quit at 45", 45);
+
+        scenarios.push_branch_change(BranchChange::ReplaceIncome {
+            name: "Job".to_string(),
+            with: None,
+        });
+        scenarios.add_expense(Expense::new(
+            "Travel".to_string(),
+            5000.0,
+            Frequency::Yearly,
+            40,
+            None,
+        ));
+
+        let branch = &mut scenarios.get_mut(1).unwrap().simulator;
+        // 5 years of salary, then 5 years of travel starting at the fork age
+        assert_eq!(branch.calculate_balance_at_age(50), 250000.0 - 25000.0);
+        let base = &mut scenarios.get_mut(0).unwrap().simulator;
+        assert_eq!(base.calculate_balance_at_age(50), 500000.0);
+    }
+
+    #[test]
+    fn test_parent_edits_propagate_to_branches() {
+        let mut scenarios = ScenarioSet::new();
+        let base = LifeSimulator::new(Person::new("Lee".to_string(), 30, 0.0));
+        scenarios.add(Scenario::new("Base".to_string(), base));
+        scenarios.fork(0, "Child", 35);
+        scenarios.push_branch_change(BranchChange::ReplaceExpense {
+            name: "Rent".to_string(),
+            with: Some(Expense::new(
+                "Rent".to_string(),
+                500.0,
+                Frequency::Monthly,
+                30,
+                None,
+            )),
+        });
+
+        // Editing and renaming the parent after forking still reaches the child
+        scenarios.select(0);
+        scenarios.add_expense(Expense::new(
+            "Rent".to_string(),
+            1000.0,
+            Frequency::Monthly,
+            30,
+            None,
+        ));
+        scenarios.add_expense(Expense::new(
+            "Food".to_string(),
+            100.0,
+            Frequency::Monthly,
+            30,
+            None,
+        ));
+        scenarios.rename(0, "Plan A");
+
+        let child = scenarios.get(1).unwrap();
+        assert_eq!(child.branch.as_ref().unwrap().parent, "Plan A");
+        let mut child = child.simulator.clone();
+        // Rent 1000 for 5 years, then 500 for 5 years; food for all 10 years
+        assert_eq!(
+            child.calculate_balance_at_age(40),
+            -(60000.0 + 30000.0 + 12000.0)
+        );
+    }
+
+    #[test]
+    fn test_what_if_replaces_an_item_the_branch_added() {
+        let mut scenarios = ScenarioSet::new();
+        let base = LifeSimulator::new(Person::new("Sam".to_string(), 30, 0.0));
+        scenarios.add(Scenario::new("Base".to_string(), base));
+        scenarios.fork(0, "Gym", 35);
+        scenarios.add_expense(Expense::new(
+            "Gym".to_string(),
+            100.0,
+            Frequency::Monthly,
+            30,
+            None,
+        ));
+
+        let mut what_if = WhatIf::from_simulator(scenarios.active().unwrap(), 40);
+        what_if.item_amount = Some((ItemRef::Expense(0), 50.0));
+        scenarios.save_what_if(&what_if);
+
+        // The added gym is replaced rather than counted a second time
+        let branch = scenarios.active_mut().unwrap();
+        assert_eq!(branch.get_person().expenses.len(), 1);
+        assert_eq!(branch.calculate_balance_at_age(40), -3000.0);
+
+        // A direct edit to the branch outlives a rebuild from its parent
+        let travel = Category::parse("Travel");
+        branch.get_person_mut().categories.add(travel.clone());
+        scenarios.select(0);
+        scenarios.add_expense(Expense::new(
+            "Rent".to_string(),
+            500.0,
+            Frequency::Monthly,
+            30,
+            None,
+        ));
+        let branch = &scenarios.get(1).unwrap().simulator;
+        assert!(branch.get_person().categories.contains(&travel));
+        assert_eq!(branch.get_person().expenses.len(), 2);
+    }
+
+    #[test]
+    fn test_branch_edit_keeps_items_sharing_a_name() {
+        let mut scenarios = ScenarioSet::new();
+        let mut base = LifeSimulator::new(Person::new("Alex".to_string(), 30, 0.0));
+        for amount in [500.0, 300.0] {
+            base.add_expense(Expense::new(
+                "Rent".to_string(),
+                amount,
+                Frequency::Monthly,
+                30,
+                None,
+            ));
+        }
+        scenarios.add(Scenario::new("Base".to_string(), base));
+        scenarios.fork(0, "Cheaper", 35);
+        scenarios.active_mut().unwrap().get_person_mut().expenses[1].amount = 400.0;
+
+        // Rebuilding from the parent keeps both rents, with only the second one changed
+        scenarios.select(0);
+        scenarios.add_expense(Expense::new(
+            "Food".to_string(),
+            100.0,
+            Frequency::Monthly,
+            30,
+            None,
+        ));
+        let branch = &mut scenarios.get_mut(1).unwrap().simulator;
+        assert_eq!(
+            branch.calculate_balance_at_age(40),
+            -(60000.0 + 18000.0 + 24000.0 + 12000.0)
+        );
+    }
+
+    #[test]
+    fn test_branch_records_pension_edits() {
+        let rules = PensionRules::find("us-social-security").unwrap().clone();
+        let mut scenarios = ScenarioSet::new();
+        let mut base = LifeSimulator::new(Person::new("Jo".to_string(), 30, 0.0));
+        base.add_pension(Pension::new("State".to_string(), rules.clone()));
+        scenarios.add(Scenario::new("Base".to_string(), base));
+        scenarios.fork(0, "Private", 35);
+
+        scenarios.remove_pension(0);
+        scenarios.add_pension(Pension::new("Private".to_string(), rules));
+        // An edit to the parent rebuilds the branch without undoing its pension edits
+        scenarios.select(0);
+        scenarios.add_expense(Expense::new(
+            "Food".to_string(),
+            100.0,
+            Frequency::Monthly,
+            30,
+            None,
+        ));
+        let names: Vec<&str> = scenarios
+            .get(1)
+            .unwrap()
+            .simulator
+            .get_person()
+            .pensions
+            .iter()
+            .map(|pension| pension.name.as_str())
+            .collect();
+        assert_eq!(names, ["Private"]);
+        assert_eq!(
+            scenarios
+                .get(0)
+                .unwrap()
+                .simulator
+                .get_person()
+                .pensions
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_assumptions_apply_return_inflation_and_retirement() {
+        let mut person = Person::new("Ana".to_string(), 60, 1000.0);
+        person.add_income(
+            Income::new("Salary".to_string(), 100.0, Frequency::Yearly, 60, None).with_earned(true),
+        );
+        person.add_expense(Expense::new(
+            "Food".to_string(),
+            100.0,
+            Frequency::Yearly,
+            60,
+            None,
+        ));
+        let mut simulator = LifeSimulator::new(person);
+        simulator.set_assumptions(Assumptions {
+            annual_return: 0.1,
+            inflation: 0.5,
+            retirement_age: Some(61),
+            ..Default::default()
+        });
+
+        // Year 60: 10% return, salary covers food
+        assert_eq!(simulator.calculate_balance_at_age(61), 1100.0);
+        // Year 61: retired, so only the food (inflated by 50%) is paid
+        assert_eq!(
+            simulator.calculate_balance_at_age(62),
+            1100.0 + 110.0 - 150.0
+        );
+    }
+
+    #[test]
+    fn test_what_if_reports_delta_and_saves_as_baseline() {
+        let mut person = Person::new("Kim".to_string(), 30, 0.0);
+        person.add_expense(Expense::new(
+            "Rent".to_string(),
+            1000.0,
+            Frequency::Monthly,
+            30,
+            None,
+        ));
+        let mut scenarios = ScenarioSet::new();
+        scenarios.add(Scenario::new(
+            "Base".to_string(),
+            LifeSimulator::new(person),
+        ));
+
+        let mut what_if = WhatIf::from_simulator(scenarios.active().unwrap(), 40);
+        what_if.item_amount = Some((ItemRef::Expense(0), 500.0));
+        let outcome = what_if.evaluate(scenarios.active().unwrap());
+        assert_eq!(outcome.final_delta(), 60000.0);
+        assert_eq!(outcome.delta_at(35), Some(30000.0));
+        assert_eq!(outcome.lowest_balance(), Some((40, -60000.0)));
+
+        // The scenario itself is untouched until the what-if is saved
+        assert_eq!(
+            scenarios.active_mut().unwrap().calculate_balance_at_age(40),
+            -120000.0
+        );
+        scenarios.save_what_if(&what_if);
+        assert_eq!(
+            scenarios.active_mut().unwrap().calculate_balance_at_age(40),
+            -60000.0
+        );
+    }
+
+    #[test]
+    fn test_fixed_rate_withdrawals_follow_inflation_until_depleted() {
+        let mut person = Person::new("Max".to_string(), 65, 100000.0);
+        person.add_expense(Expense::new(
+            "Living".to_string(),
+            3000.0,
+            Frequency::Yearly,
+            65,
+            None,
+        ));
+        let mut simulator = LifeSimulator::new(person);
+        simulator.set_assumptions(Assumptions {
+            inflation: 0.5,
+            retirement_age: Some(65),
+            withdrawal: WithdrawalStrategy::FixedRate { rate: 0.4 },
+            ..Default::default()
+        });
+
+        let report = retirement_report(&mut simulator, 70).unwrap();
+        let withdrawals: Vec<f64> = report.years.iter().map(|y| y.withdrawal).collect();
+        // 40% of the starting portfolio, then 50% more each year until nothing is left
+        assert_eq!(withdrawals, vec![40000.0, 60000.0, 0.0, 0.0, 0.0]);
+        assert_eq!(report.survival_age, Some(67));
+        // Both withdrawals are spent in full, and the emptied portfolio still pays the expenses
+        assert_eq!(report.excess_years(), 2);
+        assert_eq!(report.shortfall_years(), 0);
+        assert_eq!(simulator.calculate_balance_at_age(68), -6750.0);
+    }
+
+    #[test]
+    fn test_withdrawal_is_the_spending_that_is_paid() {
+        let mut person = Person::new("Max".to_string(), 60, 100000.0);
+        person.add_expense(Expense::new(
+            "Living".to_string(),
+            10000.0,
+            Frequency::Yearly,
+            60,
+            None,
+        ));
+        let mut simulator = LifeSimulator::new(person);
+        simulator.set_assumptions(Assumptions {
+            retirement_age: Some(60),
+            withdrawal: WithdrawalStrategy::FixedRate { rate: 0.04 },
+            ..Default::default()
+        });
+
+        // The 4% rule takes out 4000 a year and the other 6000 of expenses go unpaid
+        assert_eq!(simulator.calculate_balance_at_age(61), 96000.0);
+        assert_eq!(simulator.calculate_balance_at_age(71), 56000.0);
+        let report = retirement_report(&mut simulator, 71).unwrap();
+        assert_eq!(report.years[0].shortfall, 6000.0);
+        assert_eq!(report.survival_age, None);
+
+        // A withdrawal above the expenses is spent as well
+        let mut assumptions = simulator.get_assumptions().clone();
+        assumptions.withdrawal = WithdrawalStrategy::ConstantDollar { amount: 25000.0 };
+        simulator.set_assumptions(assumptions);
+        assert_eq!(simulator.calculate_balance_at_age(61), 75000.0);
+        let report = retirement_report(&mut simulator, 71).unwrap();
+        assert_eq!(report.years[0].excess(), 15000.0);
+        assert_eq!(report.survival_age, Some(64));
+    }
+
+    #[test]
+    fn test_guardrails_cut_withdrawal_when_rate_rises() {
+        let strategy = WithdrawalStrategy::Guardrails {
+            initial_rate: 0.05,
+            band: 0.2,
+            adjustment: 0.1,
+        };
+        let assumptions = Assumptions::default();
+        assert_eq!(
+            strategy.withdrawal(65, 100000.0, 0.0, None, &assumptions),
+            5000.0
+        );
+        // 5000 of 80000 is 6.25%, above the 6% guardrail
+        assert_eq!(
+            strategy.withdrawal(66, 80000.0, 0.0, Some(5000.0), &assumptions),
+            4500.0
+        );
+        // 5000 of 90000 stays inside the band
+        assert_eq!(
+            strategy.withdrawal(66, 90000.0, 0.0, Some(5000.0), &assumptions),
+            5000.0
+        );
+    }
+
+    #[test]
+    fn test_fire_report_finds_independence_age() {
+        let mut person = Person::new("Sam".to_string(), 30, 0.0);
+        person.add_income(Income::new(
+            "Salary".to_string(),
+            50000.0,
+            Frequency::Yearly,
+            30,
+            None,
+        ));
+        person.add_expense(Expense::new(
+            "Living".to_string(),
+            10000.0,
+            Frequency::Yearly,
+            30,
+            None,
+        ));
+        let mut simulator = LifeSimulator::new(person);
+
+        // Saving 40000 a year reaches 25 x 10000 after 7 years
+        let report = fire_report(&mut simulator, FireVariant::Regular, 0.04, 60);
+        assert_eq!(report.current_fire_number(), Some(250000.0));
+        assert_eq!(report.years[0].savings_rate(), Some(0.8));
+        assert_eq!(report.fire_age, Some(37));
+
+        // Lean FIRE needs 187500, reached after 5 years
+        let lean = fire_report(&mut simulator, FireVariant::Lean, 0.04, 60);
+        assert_eq!(lean.fire_age, Some(35));
+    }
+
+    #[test]
+    fn test_goals_report_shortfall_and_required_saving() {
+        let mut person = Person::new("Eli".to_string(), 25, 0.0);
+        person.add_income(Income::new(
+            "Salary".to_string(),
+            1000.0,
+            Frequency::Monthly,
+            25,
+            None,
+        ));
+        let mut simulator = LifeSimulator::new(person);
+        let goals = vec![
+            Goal::new("Emergency fund".to_string(), 50000.0, 30),
+            Goal::new("Down payment".to_string(), 80000.0, 35),
+        ];
+
+        let progress = evaluate_goals(&mut simulator, &goals);
+        assert!(progress[0].is_met());
+        assert_eq!(progress[0].shortfall(), 0.0);
+        // 10 years at 12000 a year reach 120000, so the down payment is met as well
+        assert_eq!(progress[1].projected, 120000.0);
+        assert!(progress[1].is_met());
+
+        let late = evaluate_goals(
+            &mut simulator,
+            &[Goal::new("Million".to_string(), 240000.0, 35)],
+        );
+        assert_eq!(late[0].shortfall(), 120000.0);
+        // 120000 over 10 years without returns is 1000 a month
+        assert_eq!(late[0].required_monthly_saving, Some(1000.0));
+    }
+
+    #[test]
+    fn test_balance_alert_reports_periods_below_buffer() {
+        let mut person = Person::new("Noa".to_string(), 30, 10000.0);
+        person.add_expense(Expense::new(
+            "Studies".to_string(),
+            6000.0,
+            Frequency::Yearly,
+            30,
+            Some(33),
+        ));
+        person.add_income(Income::new(
+            "Job".to_string(),
+            5000.0,
+            Frequency::Yearly,
+            33,
+            None,
+        ));
+        let mut simulator = LifeSimulator::new(person);
+
+        // 10000, 4000, -2000, -8000, -3000, 2000, ...
+        let alert = balance_alert(&mut simulator, 40);
+        assert_eq!(alert.first_age(), Some(32));
+        assert_eq!(alert.periods[0].recovered_at, Some(35));
+        assert_eq!(alert.periods[0].lowest_age, 33);
+        assert_eq!(alert.years_below(), 3);
+
+        // A buffer of 5000 also flags age 31 and keeps the period open until 36
+        simulator.set_cash_buffer(5000.0);
+        let alert = balance_alert(&mut simulator, 40);
+        assert_eq!(alert.first_age(), Some(31));
+        assert_eq!(alert.periods[0].recovered_at, Some(36));
+        assert_eq!(alert.years_below(), 5);
+    }
+
+    #[test]
+    fn test_credit_line_borrows_charges_interest_and_repays() {
+        let mut person = Person::new("Ivy".to_string(), 30, 0.0);
+        person.add_expense(Expense::new(
+            "Car".to_string(),
+            10000.0,
+            Frequency::Yearly,
+            30,
+            Some(31),
+        ));
+        person.add_income(Income::new(
+            "Job".to_string(),
+            8000.0,
+            Frequency::Yearly,
+            31,
+            None,
+        ));
+        let mut simulator = LifeSimulator::new(person);
+        simulator.set_credit_line(Some(CreditLine::new(8000.0, 0.1)));
+
+        // Only 8000 can be borrowed, the remaining 2000 is still missing
+        assert_eq!(simulator.calculate_balance_at_age(31), -10000.0);
+        assert_eq!(simulator.year_result(30).unwrap().debt, 8000.0);
+        // 800 interest, then the rest of the salary repays the debt
+        assert_eq!(simulator.calculate_balance_at_age(32), -2800.0);
+        let year = simulator.year_result(31).unwrap();
+        assert_eq!(year.interest, 800.0);
+        assert_eq!(year.debt, 2800.0);
+        assert_eq!(
+            simulator.calculate_balance_at_age(33),
+            8000.0 - 2800.0 - 280.0
+        );
+        assert_eq!(simulator.year_result(32).unwrap().debt, 0.0);
+    }
+
+    #[test]
+    fn test_household_uses_each_members_age_and_joint_balance() {
+        let mut simulator = LifeSimulator::new(Person::new("Jo".to_string(), 40, 1000.0));
+        simulator.set_assumptions(Assumptions {
+            retirement_age: Some(65),
+            ..Default::default()
+        });
+        let mut partner = Person::new("Ari".to_string(), 63, 500.0);
+        partner.add_income(
+            Income::new("Wage".to_string(), 10000.0, Frequency::Yearly, 20, None).with_earned(true),
+        );
+        simulator.add_member(Member::new(Role::Partner, partner));
+        let child = Person::new("Bo".to_string(), 16, 0.0);
+        simulator.add_member(Member::new(Role::Child, child));
+        // University while the child is 18 to 20
+        simulator.add_member_expense(
+            1,
+            Expense::new(
+                "University".to_string(),
+                3000.0,
+                Frequency::Yearly,
+                18,
+                Some(21),
+            ),
+        );
+        simulator.add_shared_expense(Expense::new(
+            "Groceries".to_string(),
+            2000.0,
+            Frequency::Yearly,
+            40,
+            None,
+        ));
+
+        assert_eq!(simulator.calculate_balance_at_age(40), 1500.0);
+        // Ages 40 and 41: the partner still works (63, 64), no university yet
+        assert_eq!(
+            simulator.calculate_balance_at_age(42),
+            1500.0 + 2.0 * 8000.0
+        );
+        // Age 42: the partner has retired and the child turns 18
+        assert_eq!(simulator.calculate_balance_at_age(43), 17500.0 - 5000.0);
+    }
+
+    #[test]
+    fn test_child_template_generates_costs_and_benefits() {
+        let template = ChildTemplate {
+            support_ends_at: 20,
+            ..Default::default()
+        };
+        let child =
+            Child::born_in_year("Mia".to_string(), 2030, 2026, 30, template).with_benefits(true);
+        assert_eq!(child.born_at, 34);
+
+        let expenses = child.expenses();
+        let childcare = &expenses[0];
+        assert_eq!(childcare.name, "Mia: Childcare");
+        assert_eq!((childcare.start_age, childcare.end_age), (34, Some(37)));
+        assert_eq!(
+            childcare.category,
+            Category::parse("Children > Mia > Childcare")
+        );
+        // University is cut short by the end of support
+        let university = expenses
+            .iter()
+            .find(|e| e.name == "Mia: University")
+            .unwrap();
+        assert_eq!((university.start_age, university.end_age), (52, Some(54)));
+
+        let mut simulator = LifeSimulator::new(Person::new("Pat".to_string(), 30, 0.0));
+        simulator.add_child(child);
+        // At age 34: childcare and living costs, offset by the child benefit
+        let totals = simulator.yearly_totals(34, None);
+        assert_eq!(totals.expenses, 12.0 * (800.0 + 400.0));
+        assert_eq!(totals.incomes, 12.0 * 250.0);
+        assert_eq!(simulator.yearly_totals(54, None).expenses, 0.0);
+    }
+
+    #[test]
+    fn test_builtin_templates_load() {
+        let templates = builtin_templates();
+        assert_eq!(templates.len(), 4);
+        for template in &templates {
+            let mut scenario = template.instantiate().unwrap();
+            assert!(!scenario.simulator.get_person().expenses.is_empty());
+            scenario.simulator.calculate_balance_at_age(90);
+        }
+
+        let family = templates
+            .iter()
+            .find(|t| t.id == "family-with-two-kids")
+            .unwrap()
+            .instantiate()
+            .unwrap();
+        let household = family.simulator.get_household();
+        assert_eq!(household.partners().count(), 1);
+        assert_eq!(household.children.len(), 2);
+        // Child categories are added to the tree when loading
+        let categories = &family.simulator.get_person().categories;
+        assert!(categories.contains(&Category::parse("Children > First child > Childcare")));
+    }
+
+    #[test]
+    fn test_csv_import_reads_mapping_and_sign_conventions() {
+        let csv = "Buchungstag;Empfänger;Soll;Haben\n\
+                   01.02.2024;\"Miete; Wohnung\";850,00;\n\
+                   28.02.2024;ACME GmbH Gehalt;;3.250,50\n";
+        let mut mapping = CsvMapping::detect(csv);
+        assert_eq!(mapping.delimiter, ';');
+        assert!(matches!(mapping.amount, AmountColumns::Signed { .. }));
+
+        mapping.date_format = DateFormat::DayMonthYear;
+        mapping.description_column = Column::parse("Empfänger");
+        mapping.amount = AmountColumns::DebitCredit {
+            debit: Column::parse("Soll"),
+            credit: Column::parse("3"),
+        };
+        let transactions = transactions_from_csv(csv, &mapping).unwrap();
+        assert_eq!(transactions.len(), 2);
+        assert_eq!(transactions[0].description, "Miete; Wohnung");
+        assert_eq!(transactions[0].amount, -850.0);
+        assert_eq!(transactions[1].amount, 3250.5);
+        assert_eq!(transactions[1].date.to_string(), "2024-02-28");
+
+        assert_eq!(parse_amount("(1,234.50)", false).unwrap(), -1234.5);
+        assert!(parse_amount("n/a", false).is_err());
+        // A dash is a sign only before or after the number
+        assert_eq!(parse_amount("$-12.00", false).unwrap(), -12.0);
+        assert_eq!(parse_amount("1.234,56- EUR", true).unwrap(), -1234.56);
+        assert!(parse_amount("2024-01-15", false).is_err());
+
+        // "in" and "out" only name a column on their own, not inside a longer header
+        let csv =
+            "Date,Check in,Check out,Payee,Amount in EUR\n2024-01-15,14:00,11:00,Hotel,-12.50\n";
+        let mapping = CsvMapping::detect(csv);
+        assert!(matches!(mapping.amount, AmountColumns::Signed { .. }));
+        assert_eq!(
+            transactions_from_csv(csv, &mapping).unwrap()[0].amount,
+            -12.5
+        );
+        let csv = "Date,Payee,Out,In\n2024-01-15,Shop,12.50,\n";
+        let mapping = CsvMapping::detect(csv);
+        assert!(matches!(mapping.amount, AmountColumns::DebitCredit { .. }));
+        assert_eq!(
+            transactions_from_csv(csv, &mapping).unwrap()[0].amount,
+            -12.5
+        );
+    }
+
+    #[test]
+    fn test_recurring_transactions_become_items() {
+        let mut csv = String::from("Date,Description,Amount\n");
+        for month in 1..=12 {
+            csv += &format!(
+                "2024-{:02}-01,RENT Flat 12 ref {}000,-1200.00\n",
+                month, month
+            );
+            csv += &format!("2024-{:02}-25,ACME Payroll {},\"4,000.00\"\n", month, month);
+            csv += &format!("2024-{:02}-14,NETFLIX.COM,-15.99\n", month);
+        }
+        csv += "2024-03-09,Hardware store,-89.00\n";
+        for day in [3, 10, 17, 24, 31] {
+            csv += &format!("2024-01-{:02},Corner bakery,-10.00\n", day);
+        }
+        for date in ["01-05", "01-19", "02-02", "02-16", "03-01"] {
+            csv += &format!("2024-{},Side gig pay,1000.00\n", date);
+        }
+        for month in [1, 4, 7, 10] {
+            csv += &format!("2024-{:02}-15,City water bill,-90.00\n", month);
+        }
+
+        let transactions = transactions_from_csv(&csv, &CsvMapping::detect(&csv)).unwrap();
+        let items = detect_recurring(&transactions);
+        let names: Vec<&str> = items.iter().map(|item| item.name.as_str()).collect();
+        assert!(!names.iter().any(|name| name.contains("Hardware")));
+
+        let salary = items.iter().find(|item| item.is_income).unwrap();
+        assert_eq!(salary.name, "Acme Payroll");
+        assert_eq!(
+            (salary.frequency.clone(), salary.amount),
+            (Frequency::Monthly, 4000.0)
+        );
+        assert_eq!(salary.category, Category::parse("Work > Salary"));
+
+        let rent = items
+            .iter()
+            .find(|item| item.name == "Rent Flat Ref")
+            .unwrap();
+        assert_eq!(rent.to_expense(30).yearly_amount(), 14400.0);
+        assert_eq!(rent.category, Category::parse("Housing > Rent"));
+        let bakery = items
+            .iter()
+            .find(|item| item.name == "Corner Bakery")
+            .unwrap();
+        assert_eq!(bakery.frequency, Frequency::Monthly);
+        assert!((bakery.amount - 10.0 * 52.0 / 12.0).abs() < 1e-9);
+        // Biweekly pay becomes monthly and a quarterly bill yearly, at the same yearly amount
+        let gig = items
+            .iter()
+            .find(|item| item.name == "Side Gig Pay")
+            .unwrap();
+        assert_eq!(gig.frequency, Frequency::Monthly);
+        assert!((gig.yearly_amount() - 26000.0).abs() < 1e-9);
+        let water = items
+            .iter()
+            .find(|item| item.name == "City Water Bill")
+            .unwrap();
+        assert_eq!(
+            (water.frequency.clone(), water.amount),
+            (Frequency::Yearly, 360.0)
+        );
+        assert_eq!(water.category, Category::parse("Housing > Utilities"));
+        let netflix = items
+            .iter()
+            .find(|item| item.name == "Netflix Com")
+            .unwrap();
+        assert_eq!(netflix.category, Category::parse("Leisure > Subscriptions"));
+    }
+
+    #[test]
+    fn test_ofx_and_qif_statements_are_read() {
+        let ofx = "OFXHEADER:100\n<OFX><BANKMSGSRSV1><STMTTRNRS><STMTRS><BANKTRANLIST>\n\
+                   <STMTTRN>\n<TRNTYPE>DEBIT\n<DTPOSTED>20240105120000[-5:EST]\n\
+                   <TRNAMT>-1200.00\n<NAME>Landlord\n</STMTTRN>\n\
+                   <STMTTRN><TRNTYPE>CREDIT</TRNTYPE><DTPOSTED>20240125</DTPOSTED>\
+                   <TRNAMT>3000</TRNAMT><MEMO>Salary</MEMO></STMTTRN>\n\
+                   </BANKTRANLIST><LEDGERBAL><BALAMT>5400.50<DTASOF>20240131</LEDGERBAL>\n";
+        let statement = statement_from_ofx(ofx).unwrap();
+        assert_eq!(statement.balance, Some(5400.5));
+        assert_eq!(statement.transactions.len(), 2);
+        assert_eq!(statement.transactions[0].description, "Landlord");
+        assert_eq!(statement.transactions[0].date.to_string(), "2024-01-05");
+        assert_eq!(statement.transactions[1].amount, 3000.0);
+        assert_eq!(statement.transactions[1].description, "Salary");
+
+        let qif = "!Type:Bank\nD01/01/2024\nT1,000.00\nPOpening Balance\nL[Checking]\n^\n\
+                   D01/03/2024\nT-45.20\nPCorner Shop\nLFood:Groceries\n^\n\
+                   D01/15'24\nU-9.99\nPStreaming\nMMonthly plan\n^\n";
+        let statement = statement_from_qif(qif, DateFormat::MonthDayYear).unwrap();
+        assert_eq!(statement.transactions.len(), 2);
+        assert_eq!(
+            statement.transactions[0].category,
+            Category::parse("Food > Groceries")
+        );
+        assert_eq!(statement.transactions[1].date.to_string(), "2024-01-15");
+        assert!((statement.balance.unwrap() - 944.81).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_ledger_journal_seeds_person() {
+        let mut journal = String::from(
+            "; opening\n\
+             account Assets:Checking\n\
+             \x20   note everyday account\n\
+             \n\
+             2024-01-01 * Opening balances\n\
+             \x20   Assets:Checking        $2,500.00\n\
+             \x20   Liabilities:Card        -$300.00\n\
+             \x20   Equity:Opening\n\
+             \n\
+             ~ monthly\n\
+             \x20   Expenses:Ignored   $1\n\
+             \x20   Assets:Checking\n",
+        );
+        for month in 1..=6 {
+            journal += &format!(
+                "\n2024/{:02}/01 (#{}) Flat rent | month {}\n\
+                 \x20   Expenses:Housing:Rent   $1,100.00  ; landlord\n\
+                 \x20   Assets:Checking\n\
+                 \n2024-{:02}-28 ACME payroll\n\
+                 \x20   Assets:Checking         $3,000.00\n\
+                 \x20   Expenses:Taxes:Income     $900.00\n\
+                 \x20   Income:Salary          $-3,900.00\n",
+                month, month, month, month
+            );
+        }
+
+        let parsed = Journal::parse(&journal).unwrap();
+        assert_eq!(parsed.entries.len(), 13);
+        assert_eq!(parsed.capital(), 2200.0 + 6.0 * (3000.0 - 1100.0));
+
+        let person = person_from_transactions(
+            "Robin".to_string(),
+            35,
+            parsed.capital(),
+            &parsed.transactions(),
+        );
+        assert_eq!(person.capital, 13600.0);
+        let rent = person
+            .expenses
+            .iter()
+            .find(|e| e.name == "Flat Rent")
+            .unwrap();
+        assert_eq!(
+            (rent.amount, rent.frequency.clone()),
+            (1100.0, Frequency::Monthly)
+        );
+        assert_eq!(rent.category, Category::parse("Housing > Rent"));
+        let taxes = person
+            .expenses
+            .iter()
+            .find(|e| e.name == "Acme Payroll")
+            .unwrap();
+        assert_eq!(taxes.category, Category::parse("Taxes > Income"));
+        assert_eq!(person.incomes.len(), 1);
+        assert_eq!(person.incomes[0].amount, 3900.0);
+        assert_eq!(person.incomes[0].category, Category::parse("Salary"));
+        assert!(
+            person
+                .categories
+                .contains(&Category::parse("Taxes > Income"))
+        );
+    }
+
+    #[test]
+    fn test_html_report_is_self_contained() {
+        let mut person = Person::new("Sam <& Co>".to_string(), 40, 10000.0);
+        person.add_income(Income::new(
+            "Salary".to_string(),
+            2000.0,
+            Frequency::Monthly,
+            40,
+            Some(45),
+        ));
+        person.add_expense(Expense::new(
+            "Rent".to_string(),
+            1500.0,
+            Frequency::Monthly,
+            40,
+            None,
+        ));
+        let mut scenario = Scenario::new("Plan".to_string(), LifeSimulator::new(person));
+        scenario
+            .goals
+            .push(Goal::new("Boat".to_string(), 1_000_000.0, 50));
+
+        let rows = projection(&mut scenario.simulator, 50);
+        assert_eq!(rows.len(), 11);
+        assert_eq!(rows[1].net_change, 6000.0);
+        assert_eq!(rows[6].net_change, -18000.0);
+
+        let html = String::from_utf8(
+            ReportFormat::parse("HTML")
+                .unwrap()
+                .render(&mut scenario, 50),
+        )
+        .unwrap();
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("Sam &lt;&amp; Co&gt;"));
+        assert!(html.contains("<svg"));
+        assert!(html.contains("Goal \"Boat\" is missed"));
+        // The balance first goes negative five years after the salary stops
+        assert!(html.contains("The balance is negative from age 48"));
+        assert!(!html.contains("src=") && !html.contains("href="));
+        assert!(ReportFormat::parse("pdf").is_err());
+        assert_eq!(format_amount(-1234567.891), "-1,234,567.89");
+    }
+
+    #[test]
+    fn test_chart_export_keeps_view_and_series() {
+        let mut person = Person::new("Sam".to_string(), 40, 10000.0);
+        person.add_expense(Expense::new(
+            "Rent".to_string(),
+            1000.0,
+            Frequency::Monthly,
+            40,
+            None,
+        ));
+        let mut simulator = LifeSimulator::new(person);
+        let chart = Chart::balance("Plan", &mut simulator, 60, None);
+        assert_eq!(chart.series.len(), 3);
+        assert_eq!(chart.bounds().0, [40.0, 60.0]);
+
+        // Only the balance between ages 45 and 50, as when zoomed in on screen
+        let chart = chart
+            .retain(&[SeriesKind::parse("Balance").unwrap()])
+            .with_view(Some([45.0, 50.0]), None);
+        let svg = chart.to_svg(960, 400);
+        assert_eq!(svg.matches("clip-path=").count(), 1);
+        assert!(svg.contains("Balance over time") && !svg.contains("Total Expenses"));
+        // The y axis still fits the balance, which falls to -50,000 by age 45
+        assert!(chart.view().1[0] <= -50000.0);
+        assert!(svg.contains(">45</text>") && !svg.contains(">40</text>"));
+
+        let png = ChartFormat::from_path(std::path::Path::new("chart.PNG"))
+            .unwrap()
+            .render(&chart, 300, 200, 2);
+        assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+        assert_eq!(&png[16..24], &[0, 0, 2, 88, 0, 0, 1, 144]);
+        assert!(png.ends_with(&[0xAE, 0x42, 0x60, 0x82]));
+        assert!(ChartFormat::from_path(std::path::Path::new("chart.gif")).is_err());
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        assert_eq!(adler32(b"Wikipedia"), 0x11E6_0398);
+    }
+
+    #[test]
+    fn test_markdown_summary_lists_key_numbers_and_decades() {
+        let mut person = Person::new("Sam".to_string(), 45, 10000.0);
+        person.add_income(
+            Income::new(
+                "Salary".to_string(),
+                1000.0,
+                Frequency::Monthly,
+                45,
+                Some(50),
+            )
+            .with_category(Category::parse("Work > Salary")),
+        );
+        person.add_expense(
+            Expense::new("Rent".to_string(), 1500.0, Frequency::Monthly, 45, None)
+                .with_category(Category::parse("Housing > Rent")),
+        );
+        let mut scenario = Scenario::new("Plan | A".to_string(), LifeSimulator::new(person));
+
+        let summary = Summary::new(&mut scenario, 62);
+        // 10,000 plus 6,000 short each year while working and 18,000 afterwards
+        assert_eq!(summary.depletion_age, Some(47));
+        assert_eq!(summary.fire_age, None);
+        assert_eq!(summary.lifetime.incomes, 60000.0);
+        assert_eq!(summary.categories[0].0, Category::parse("Housing"));
+        let ages: Vec<(u32, u32)> = summary
+            .decades
+            .iter()
+            .map(|decade| (decade.from_age, decade.to_age))
+            .collect();
+        assert_eq!(ages, vec![(45, 49), (50, 59), (60, 62)]);
+        assert_eq!(summary.decades[0].incomes, 5.0 * 12000.0);
+
+        let markdown =
+            String::from_utf8(ReportFormat::parse("md").unwrap().render(&mut scenario, 62))
+                .unwrap();
+        assert!(markdown.starts_with("# Plan \\| A\n"));
+        assert!(markdown.contains("- **Money runs out:** at age 47"));
+        assert!(markdown.contains("| 45-49 |"));
+        let text = String::from_utf8(
+            ReportFormat::parse("text")
+                .unwrap()
+                .render(&mut scenario, 62),
+        )
+        .unwrap();
+        assert!(text.starts_with("Plan | A\n========") && !text.contains("| :"));
+    }
+
+    #[test]
+    fn test_xlsx_workbook_is_a_zip_of_sheets() {
+        let mut person = Person::new("Sam".to_string(), 40, 10000.0);
+        person.add_expense(
+            Expense::new("Rent".to_string(), 1000.0, Frequency::Monthly, 40, None)
+                .with_category(Category::parse("Housing > Rent")),
+        );
+        person.add_income(
+            Income::new("Salary".to_string(), 2000.0, Frequency::Monthly, 40, None)
+                .with_category(Category::parse("Work")),
+        );
+        let mut scenario = Scenario::new("Plan".to_string(), LifeSimulator::new(person));
+
+        let format = ReportFormat::parse("XLSX").unwrap();
+        assert!(!format.is_text());
+        let workbook = format.render(&mut scenario, 50);
+        assert_eq!(&workbook[..4], b"PK\x03\x04");
+        // End of central directory record: Inputs, Results and the Housing and Work sheets,
+        // plus the content types, relationships, workbook and styles
+        let end = &workbook[workbook.len() - 22..];
+        assert_eq!(&end[..4], b"PK\x05\x06");
+        assert_eq!(u16::from_le_bytes([end[10], end[11]]), 9);
+        let contains = |name: &str| workbook.windows(name.len()).any(|w| w == name.as_bytes());
+        assert!(contains("xl/worksheets/sheet4.xml") && !contains("xl/worksheets/sheet5.xml"));
+
+        assert_eq!(column_name(0), "A");
+        assert_eq!(column_name(25), "Z");
+        assert_eq!(column_name(26), "AA");
+        assert_eq!(column_name(702), "AAA");
+
+        // Without a withdrawal strategy the formulas reach the simulated balance; the early
+        // retiree's fixed-rate withdrawals are left to the Simulated balance column
+        for template in builtin_templates() {
+            let mut scenario = template.instantiate().unwrap();
+            let balances = xlsx_formula_balances(&mut scenario, 90);
+            let simulator = &mut scenario.simulator;
+            assert_eq!(balances.len(), 91 - simulator.get_current_age() as usize);
+            let matches = balances.iter().all(|&(age, balance)| {
+                (balance - simulator.calculate_balance_at_age(age)).abs() < 1e-6
+            });
+            let covered =
+                simulator.get_assumptions().withdrawal == WithdrawalStrategy::CoverExpenses;
+            assert_eq!(matches, covered, "{}", template.id);
+        }
+    }
+
+    #[test]
+    fn test_simulation_config_sets_horizon_steps_and_rows() {
+        let mut person = Person::new("Sam".to_string(), 40, 10000.0);
+        person.add_income(Income::new(
+            "Salary".to_string(),
+            1000.0,
+            Frequency::Monthly,
+            40,
+            None,
+        ));
+        let mut simulator = LifeSimulator::new(person);
+        simulator.set_assumptions(Assumptions {
+            annual_return: 0.06,
+            ..Assumptions::default()
+        });
+
+        // Older scenario files without settings project to 100 a year at a time
+        let json = scenario_to_json(&Scenario::new("Plan".to_string(), simulator.clone()))
+            .unwrap()
+            .replace("\"simulation\"", "\"ignored\"");
+        let loaded = scenario_from_json(&json).unwrap();
+        assert_eq!(
+            *loaded.simulator.get_simulation(),
+            SimulationConfig::default()
+        );
+        assert_eq!(loaded.simulator.get_simulation().end_age(), 100);
+
+        let yearly = simulator.calculate_balance_at_age(41);
+        assert!((yearly - (10000.0 * 1.06 + 12000.0)).abs() < 1e-9);
+
+        let config = SimulationConfig {
+            start_age: Some(45),
+            horizon: Horizon::LifeExpectancy { age: 52, margin: 3 },
+            time_step: TimeStep::Monthly,
+            granularity: 5,
+        };
+        config.validate(40).unwrap();
+        assert_eq!(config.output_ages(40), vec![45, 50, 55]);
+        simulator.set_simulation(config.clone());
+
+        // Monthly steps earn returns on the salary during the year too
+        let monthly = simulator.calculate_balance_at_age(41);
+        assert!(monthly > yearly && monthly < yearly + 12000.0 * 0.06);
+
+        let rows = configured_projection(&mut simulator, &config);
+        assert_eq!(
+            rows.iter().map(|row| row.age).collect::<Vec<_>>(),
+            [45, 50, 55]
+        );
+        assert!((rows[0].incomes - 60000.0).abs() < 1e-9);
+        assert!((rows[2].incomes - 12000.0).abs() < 1e-9);
+        assert!((rows[1].net_change - (rows[1].balance - rows[0].balance)).abs() < 1e-9);
+
+        let saved = scenario_to_json(&Scenario::new("Plan".to_string(), simulator)).unwrap();
+        let loaded = scenario_from_json(&saved).unwrap();
+        assert_eq!(*loaded.simulator.get_simulation(), config);
+
+        assert!(
+            SimulationConfig {
+                start_age: Some(60),
+                horizon: Horizon::EndAge(60),
+                ..SimulationConfig::default()
+            }
+            .validate(40)
+            .is_err()
+        );
+        assert_eq!(TimeStep::parse("Monthly").unwrap(), TimeStep::Monthly);
+    }
+
+    #[test]
+    fn test_mortality_weighs_ruin_and_bequest() {
+        // Every bundled table parses and covers both sexes
+        assert!(!LifeTable::builtin().is_empty());
+        for table in LifeTable::builtin() {
+            for sex in Sex::ALL {
+                assert!(!table.death_probabilities(sex).is_empty());
+            }
+        }
+        let table = LifeTable::find("us-2019-approx").unwrap();
+        let male = Mortality::Table {
+            table: table.id.clone(),
+            sex: Sex::Male,
+        };
+        let female = Mortality::Table {
+            table: table.id.clone(),
+            sex: Sex::Female,
+        };
+        let male_expectancy = male.life_expectancy(0).unwrap();
+        assert!((male_expectancy - 76.2).abs() < 0.5);
+        assert!(female.life_expectancy(65).unwrap() > male.life_expectancy(65).unwrap());
+        let survival = male.survival_from(65).unwrap();
+        assert_eq!(survival[0], (65, 1.0));
+        assert_eq!(survival.last().unwrap().1, 0.0);
+
+        let curve = Mortality::parse_curve("60:1, 80:0.5, 90:0").unwrap();
+        assert_eq!(curve.to_string(), "60:1, 80:0.5, 90:0");
+        assert_eq!(curve.survival_from(70).unwrap()[0], (70, 1.0));
+        assert!((curve.survival_from(60).unwrap()[10].1 - 0.75).abs() < 1e-9);
+        assert!(Mortality::parse_curve("60:1, 80:1.5").is_err());
+        assert!(Mortality::parse_curve("80:0.5, 60:1").is_err());
+
+        // Each path counts equally, so randomised runs can be weighed together
+        let survival = [(60, 1.0), (61, 0.5), (62, 0.0)];
+        let ruined = vec![(60, 100.0), (61, -1.0), (62, -2.0)];
+        let lasting = vec![(60, 100.0), (61, 100.0), (62, 100.0)];
+        assert_eq!(
+            weigh_paths(std::slice::from_ref(&ruined), &survival),
+            (0.5, 0.0)
+        );
+        assert_eq!(
+            weigh_paths(std::slice::from_ref(&lasting), &survival),
+            (0.0, 100.0)
+        );
+        assert_eq!(weigh_paths(&[ruined, lasting], &survival), (0.25, 50.0));
+
+        let mut person = Person::new("Sam".to_string(), 60, 100000.0);
+        person.add_expense(Expense::new(
+            "Living".to_string(),
+            10000.0,
+            Frequency::Yearly,
+            60,
+            None,
+        ));
+        let mut simulator = LifeSimulator::new(person);
+        simulator.set_simulation(SimulationConfig {
+            horizon: Horizon::Mortality(curve.clone()),
+            ..SimulationConfig::default()
+        });
+        assert_eq!(simulator.get_simulation().end_age(), 90);
+        let report = longevity_report(&mut simulator, &curve).unwrap();
+        // Ten years of expenses empty the balance at 70, which three in four people reach
+        assert_eq!(report.depletion_age, Some(70));
+        assert!((report.ruin_probability - 0.75).abs() < 1e-9);
+        assert!(report.expected_bequest > 0.0 && report.expected_bequest < 100000.0);
+
+        let json = scenario_to_json(&Scenario::new("Plan".to_string(), simulator)).unwrap();
+        let loaded = scenario_from_json(&json).unwrap();
+        assert_eq!(loaded.simulator.get_simulation().mortality(), Some(&curve));
+    }
+
+    #[test]
+    fn test_pension_is_worked_out_from_contributions() {
+        // Every bundled rule set parses and is valid
+        assert_eq!(PensionRules::builtin().len(), 2);
+        for rules in PensionRules::builtin() {
+            assert!(rules.validate().is_ok());
+        }
+        let us = PensionRules::find("us-social-security").unwrap();
+        let accrued = us.formula.accrued(&[50000.0; 35]);
+        assert!((accrued - (0.9 * 14088.0 + 0.32 * (50000.0 - 14088.0))).abs() < 1e-6);
+        // Too few contribution years pay nothing
+        let short = Pension::new("Social Security".to_string(), us.clone());
+        assert_eq!(short.benefit(&[(30, 50000.0); 5]), 0.0);
+        assert!(short.clone().with_claiming_age(60).validate().is_err());
+
+        let rules = PensionRules::from_json(
+            r#"{
+                "id": "points", "name": "Points", "source": "Test",
+                "formula": { "Points": {
+                    "reference_earnings": 40000.0, "point_value": 400.0, "max_points_per_year": 2.0
+                } },
+                "normal_age": 65, "earliest_age": 60, "latest_age": 70,
+                "early_reduction": 0.05, "late_increase": 0.06,
+                "indexation": { "Fixed": { "rate": 0.0 } }
+            }"#,
+        )
+        .unwrap();
+        assert!((rules.claiming_adjustment(63) - 0.9).abs() < 1e-9);
+        assert!((rules.claiming_adjustment(67) - 1.12).abs() < 1e-9);
+        assert_eq!(
+            Pension::parse_history("20-21:40000, 25:20000").unwrap(),
+            vec![(20, 40000.0), (21, 40000.0), (25, 20000.0)]
+        );
+
+        // Ten past years earn a point each and the salary earns the capped two points a year
+        // until retirement at 60
+        let mut person = Person::new("Kim".to_string(), 30, 0.0);
+        person.add_income(
+            Income::new("Salary".to_string(), 100000.0, Frequency::Yearly, 30, None)
+                .with_earned(true),
+        );
+        let mut simulator = LifeSimulator::new(person);
+        simulator.set_assumptions(Assumptions {
+            retirement_age: Some(60),
+            ..Assumptions::default()
+        });
+        let history = Pension::parse_history("20-29:40000").unwrap();
+        simulator.add_pension(Pension::new("State".to_string(), rules).with_history(history));
+        let pension = &simulator.get_person().pensions[0];
+        let income = simulator.pension_income(simulator.get_person(), pension);
+        assert!((income.amount - 70.0 * 400.0).abs() < 1e-6);
+        assert_eq!(income.start_age, 65);
+
+        // A frozen pension pays the same nominal amount each year while prices rise
+        let assumptions = simulator.get_assumptions().clone();
+        let at_65 = simulator.yearly_totals(65, None).incomes;
+        assert!((at_65 - income.amount * assumptions.inflation_factor(35)).abs() < 1e-6);
+        let at_66 = simulator.yearly_totals(66, None).incomes;
+        assert!((at_66 - at_65).abs() < 1e-6);
+        assert_eq!(simulator.yearly_totals(64, None).incomes, 0.0);
+
+        let json = scenario_to_json(&Scenario::new("Plan".to_string(), simulator)).unwrap();
+        let loaded = scenario_from_json(&json).unwrap();
+        assert_eq!(loaded.simulator.get_person().pensions.len(), 1);
+    }
+
+    #[test]
+    fn test_accounts_take_savings_by_priority_and_tax_withdrawals() {
+        let deferred = TaxTreatment::Deferred {
+            relief: 0.2,
+            withdrawal_tax: 0.1,
+        };
+        let pot = Account::new("Pot", deferred)
+            .with_limit(5000.0)
+            .with_match(0.5, 0.06)
+            .with_penalty(60, 0.1);
+        // Relief makes 4000 of net pay fill the 5000 limit
+        assert_eq!(pot.contribute(8000.0, 1.0), (4000.0, 5000.0));
+        assert!((pot.net_share(40) - 0.8).abs() < 1e-9);
+        assert!((pot.net_share(60) - 0.9).abs() < 1e-9);
+
+        let mut person = Person::new("Ash".to_string(), 30, 0.0);
+        person.add_income(
+            Income::new("Salary".to_string(), 50000.0, Frequency::Yearly, 30, None)
+                .with_earned(true),
+        );
+        person.add_expense(Expense::new(
+            "Living".to_string(),
+            40000.0,
+            Frequency::Yearly,
+            30,
+            None,
+        ));
+        person.add_expense(Expense::new(
+            "Roof".to_string(),
+            20000.0,
+            Frequency::Yearly,
+            31,
+            Some(32),
+        ));
+        let mut simulator = LifeSimulator::new(person);
+        simulator.set_accounts(vec![pot, Account::new("ISA", TaxTreatment::TaxFree)]);
+
+        // The pot takes 4000 of the 10000 saved plus a 1500 match on 6% of pay, the ISA the rest
+        assert!((simulator.calculate_balance_at_age(31) - 12500.0).abs() < 1e-6);
+        assert_eq!(simulator.account_balances_at(31), vec![6500.0, 6000.0]);
+
+        // A 10000 shortfall empties the ISA first, then pays tax and penalty on the pot
+        assert!((simulator.calculate_balance_at_age(32) - 1500.0).abs() < 1e-6);
+        let balances = simulator.account_balances_at(32);
+        assert!((balances[0] - 1500.0).abs() < 1e-6 && balances[1].abs() < 1e-6);
+        assert!((simulator.year_result(31).unwrap().tax - 1000.0).abs() < 1e-6);
+
+        let json = scenario_to_json(&Scenario::new("Plan".to_string(), simulator)).unwrap();
+        let loaded = scenario_from_json(&json).unwrap();
+        assert_eq!(loaded.simulator.get_accounts().len(), 2);
+    }
+
+    #[test]
+    fn test_allocation_follows_glide_path() {
+        let path = GlidePath::parse("30:90/10/0, 60:50/50/0").unwrap();
+        assert_eq!(path.allocation_at(20), Allocation::new(0.9, 0.1, 0.0));
+        let middle = path.allocation_at(45);
+        assert!((middle.stocks - 0.7).abs() < 1e-9 && (middle.bonds - 0.3).abs() < 1e-9);
+        assert_eq!(path.allocation_at(70), Allocation::new(0.5, 0.5, 0.0));
+        assert!(GlidePath::parse("60:50/50/0, 30:90/10/0").is_err());
+        assert!(GlidePath::parse("30:90/10").is_err());
+
+        let mut allocation = AssetAllocation {
+            glide_path: GlidePath::constant(Allocation::new(1.0, 0.0, 0.0)),
+            ..AssetAllocation::default()
+        };
+        allocation.stocks.expected_return = 0.1;
+        allocation.bonds.expected_return = 0.04;
+        assert!((allocation.blended_return(&middle) - 0.082).abs() < 1e-9);
+
+        // Cash follows the scenario's allocation, the account its own all-bond path
+        let mut account = Account::new("ISA", TaxTreatment::TaxFree).with_balance(1000.0);
+        account.glide_path = Some(GlidePath::constant(Allocation::new(0.0, 1.0, 0.0)));
+        let mut simulator = LifeSimulator::new(Person::new("Ash".to_string(), 30, 1000.0));
+        simulator.set_assumptions(Assumptions {
+            allocation: Some(allocation),
+            ..Assumptions::default()
+        });
+        simulator.set_accounts(vec![account]);
+        assert!((simulator.calculate_balance_at_age(31) - 2140.0).abs() < 1e-6);
+        assert!((simulator.account_balances_at(31)[0] - 1040.0).abs() < 1e-6);
+
+        let json = scenario_to_json(&Scenario::new("Plan".to_string(), simulator)).unwrap();
+        let loaded = scenario_from_json(&json).unwrap();
+        let assumptions = loaded.simulator.get_assumptions();
+        assert!((assumptions.return_at(30) - 0.1).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_property_feeds_net_worth_until_sold() {
+        let mortgage = Mortgage::new(0.05, 2);
+        assert!((mortgage.payment(1000.0) - 537.8049).abs() < 1e-4);
+        assert!((mortgage.balance_after(1000.0, 1) - 512.1951).abs() < 1e-4);
+        assert_eq!(mortgage.balance_after(1000.0, 2), 0.0);
+
+        let home = Property::new("Home", 100000.0, 30)
+            .with_mortgage(0.2, Mortgage::new(0.0, 10))
+            .with_appreciation(0.1)
+            .with_costs(0.01, 0.0, 0.0)
+            .with_rental_income(5000.0)
+            .with_sale(32, 0.05);
+        assert!(home.validate().is_ok());
+        assert!(home.clone().with_sale(30, 0.05).validate().is_err());
+
+        let mut simulator = LifeSimulator::new(Person::new("Ash".to_string(), 30, 100000.0));
+        simulator.set_properties(vec![home]);
+        // 20000 down, then 8000 instalments, 1% upkeep and 5000 rent while held
+        assert!((simulator.calculate_balance_at_age(30) - 100000.0).abs() < 1e-6);
+        assert!((simulator.property_equity_at(31) - 38000.0).abs() < 1e-6);
+        assert!((simulator.calculate_balance_at_age(31) - 76000.0).abs() < 1e-6);
+        assert!((simulator.net_worth_at(31) - 114000.0).abs() < 1e-6);
+        assert!((simulator.calculate_balance_at_age(32) - 71900.0).abs() < 1e-6);
+        assert!((simulator.net_worth_at(32) - 128900.0).abs() < 1e-6);
+        // The sale pays 5% costs and clears the 64000 left on the mortgage
+        assert!((simulator.calculate_balance_at_age(33) - 122850.0).abs() < 1e-6);
+        assert!((simulator.net_worth_at(33) - 122850.0).abs() < 1e-6);
+        assert_eq!(simulator.year_result(32).unwrap().equity, 0.0);
+
+        let deltas = buy_vs_rent(&simulator, 6000.0, 33).unwrap();
+        let last = deltas.last().unwrap();
+        assert!((last.baseline - 88000.0).abs() < 1e-6);
+        assert!((last.delta() - 34850.0).abs() < 1e-6);
+
+        let json = scenario_to_json(&Scenario::new("Plan".to_string(), simulator)).unwrap();
+        let loaded = scenario_from_json(&json).unwrap();
+        assert_eq!(loaded.simulator.get_properties().len(), 1);
+    }
+
+    #[test]
+    fn test_property_does_not_delay_depletion() {
+        let mut person = Person::new("Ash".to_string(), 60, 50000.0);
+        person.add_expense(Expense::new(
+            "Living".to_string(),
+            10000.0,
+            Frequency::Yearly,
+            60,
+            None,
+        ));
+        let mut renter = LifeSimulator::new(person);
+        renter.set_cash_buffer(20000.0);
+        let mut owner = renter.clone();
+        owner.set_properties(vec![Property::new("Home", 500000.0, 40)]);
+
+        let renter_series = renter.balance_series(80);
+        assert_eq!(owner.balance_series(80), renter_series);
+        let depletion = LifeSimulator::depletion_age(&renter_series);
+        assert!(depletion.is_some());
+        assert_eq!(
+            LifeSimulator::depletion_age(&owner.balance_series(80)),
+            depletion
+        );
+        assert_eq!(
+            balance_alert(&mut owner, 80).first_age(),
+            balance_alert(&mut renter, 80).first_age()
+        );
+
+        // The home only shows up in the net worth
+        let balance = owner.calculate_balance_at_age(70);
+        assert!((owner.net_worth_at(70) - balance - 500000.0).abs() < 1e-6);
+        let kinds: Vec<SeriesKind> = Chart::balance("Owner", &mut owner, 80, None)
+            .series
+            .iter()
+            .map(|series| series.kind)
+            .collect();
+        assert!(kinds.contains(&SeriesKind::NetWorth));
+        let mut scenario = Scenario::new("Owner".to_string(), owner);
+        let summary = Summary::new(&mut scenario, 80);
+        assert_eq!(summary.depletion_age, depletion);
+        assert!(summary.net_worth.unwrap() > 500000.0 + summary.milestones.last().unwrap().2 - 1.0);
+    }
 }
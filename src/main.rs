@@ -2,7 +2,14 @@
 use life_simulator::desktop::ui::run_gui;
 
 #[cfg(feature = "cli")]
-use life_simulator::cli::Cli;
+use clap::Parser;
+#[cfg(feature = "cli")]
+use life_simulator::cli::{
+    Cli,
+    commands::{CliArgs, run_command},
+};
+#[cfg(feature = "cli")]
+use life_simulator::infrastructure::persistence::find_template;
 
 fn main() {
     #[cfg(feature = "desktop")]
@@ -12,7 +19,31 @@ fn main() {
 
     #[cfg(feature = "cli")]
     {
-        let mut cli = Cli::new();
-        cli.run();
+        let args = CliArgs::parse();
+        match args.command {
+            Some(command) => {
+                if let Err(e) = run_command(command) {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(1);
+                }
+            }
+            None => {
+                let mut cli = match args.template.as_deref().map(find_template) {
+                    Some(Ok(template)) => match template.instantiate() {
+                        Ok(scenario) => Cli::with_simulator(scenario.simulator),
+                        Err(e) => {
+                            eprintln!("Error: {}", e);
+                            std::process::exit(1);
+                        }
+                    },
+                    Some(Err(e)) => {
+                        eprintln!("Error: {}", e);
+                        std::process::exit(1);
+                    }
+                    None => Cli::new(),
+                };
+                cli.run();
+            }
+        }
     }
 }